@@ -3,6 +3,35 @@
  */
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::Window;
+use tokio::sync::oneshot;
+use crate::ai_provider::{
+    build_messages, default_base_url, load_ai_config, save_ai_config, save_api_key, stream_chat, AiConfig, AiError,
+    DEFAULT_CONNECT_TIMEOUT_SECS, DEFAULT_MAX_TOKENS, DEFAULT_TIMEOUT_SECS, PROVIDERS,
+};
+use crate::db::get_db;
+use crate::system_info::cached_system_info;
+use crate::util::unix_now;
+
+/// 系统提示词
+const SYSTEM_PROMPT: &str = "你是 myssh 内置的服务器运维助手，帮助用户排查服务器问题、解释输出和编写命令。\
+回答使用中文，命令放在代码块中；涉及删除数据、重启服务、修改防火墙等高风险操作时，先说明影响和回滚方法。";
+
+/// 回复片段事件名
+const AI_CHAT_TOKEN_EVENT: &str = "ai-chat-token";
+/// 回复结束事件名
+const AI_CHAT_FINISHED_EVENT: &str = "ai-chat-finished";
+/// 回复失败事件名
+const AI_CHAT_ERROR_EVENT: &str = "ai-chat-error";
+
+/// 正在进行的流式对话（request_id -> 取消信号）
+type ChatTasks = Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>;
+
+lazy_static::lazy_static! {
+    static ref CHAT_TASKS: ChatTasks = Arc::new(Mutex::new(HashMap::new()));
+}
 
 /// 对话消息
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -48,7 +77,88 @@ pub struct GetAiQuickActionsResult {
     pub actions: Vec<QuickAction>,
 }
 
+/// 流式对话参数
+#[derive(Debug, Deserialize)]
+pub struct StartAiChatParams {
+    pub server_id: String,
+    pub request_id: String, // 请求ID（由前端生成，用于匹配事件）
+    pub question: String,
+    pub history: Vec<ChatMessage>,
+}
+
+/// 流式对话返回
+#[derive(Debug, Serialize)]
+pub struct StartAiChatResult {
+    pub success: bool,
+    pub request_id: String,
+}
+
+/// 取消流式对话参数
+#[derive(Debug, Deserialize)]
+pub struct CancelAiChatParams {
+    pub request_id: String,
+}
+
+/// 取消流式对话返回
+#[derive(Debug, Serialize)]
+pub struct CancelAiChatResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 回复片段事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct AiChatTokenEvent {
+    pub request_id: String,
+    pub delta: String, // 新增的回复文本
+}
+
+/// 回复结束事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct AiChatFinishedEvent {
+    pub request_id: String,
+    pub content: String, // 完整回复（取消时为已收到的部分）
+    pub timestamp: u64,
+    pub cancelled: bool, // 是否被用户取消
+}
+
+/// 回复失败事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct AiChatErrorEvent {
+    pub request_id: String,
+    #[serde(flatten)]
+    pub error: AiError,
+    pub content: String, // 出错前已收到的部分回复
+}
+
+/// AI 服务配置（不含 API Key 明文）
+#[derive(Debug, Serialize)]
+pub struct AiConfigInfo {
+    pub configured: bool,
+    pub provider: String,         // "openai" | "anthropic" | "ollama"
+    pub base_url: String,         // 实际使用的服务地址
+    pub model: String,
+    pub has_api_key: bool,
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    pub max_tokens: u32,
+}
+
+/// 保存 AI 服务配置参数
+#[derive(Debug, Deserialize)]
+pub struct SetAiConfigParams {
+    pub provider: String,
+    pub base_url: Option<String>,          // 为空时使用默认地址
+    pub model: String,
+    pub api_key: Option<String>,           // 不传则保留原值，传空字符串则清除
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub max_tokens: Option<u32>,
+}
+
 /// 与 AI 对话
+///
+/// 将对话历史和本次问题发送给已配置的 AI 服务，已获取主机信息时附带服务器环境作为上下文。
 /// 
 /// # 命令名称
 /// `chat_with_ai`
@@ -63,25 +173,209 @@ pub struct GetAiQuickActionsResult {
 /// - `timestamp`: 时间戳
 #[tauri::command]
 pub async fn chat_with_ai(params: ChatWithAiParams) -> Result<ChatWithAiResult, String> {
-    // TODO: 实现实际的 AI 对话逻辑
-    // 这里需要调用 AI 服务（如 OpenAI API、本地模型等）
-    // 可以根据服务器信息和对话历史生成回复
-    
-    // 模拟 AI 响应
-    let response = format!(
-        "这是对问题 \"{}\" 的模拟回复。\n实际实现中需要调用 AI 服务来生成回复。",
-        params.question
-    );
+    let config = {
+        let db = get_db();
+        let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+        load_ai_config(&conn)?
+    }
+    .ok_or_else(|| "尚未配置 AI 服务，请先在设置中填写服务地址和模型".to_string())?;
+
+    let messages = build_messages(&params.history, &params.question);
+    let content = stream_chat(&config, &system_prompt(&params.server_id), &messages, |_| {})
+        .await
+        .map_err(|e| e.message)?;
     
     Ok(ChatWithAiResult {
-        content: response,
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
+        content,
+        timestamp: unix_now() as u64,
     })
 }
 
+/// 与 AI 对话（流式返回）
+///
+/// 回复通过 `ai-chat-token` 事件逐段推送，正常结束或被取消时推送 `ai-chat-finished` 事件，
+/// 未配置服务、网络错误、服务端错误等推送 `ai-chat-error` 事件（包含错误类型、HTTP 状态码、
+/// 是否可重试以及已收到的部分回复）。两种结束事件只会推送其中一个。
+///
+/// # 命令名称
+/// `start_ai_chat`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `request_id`: 请求ID
+/// - `question`: 用户问题
+/// - `history`: 对话历史
+///
+/// # 返回
+/// - `success`: 是否成功启动
+/// - `request_id`: 请求ID
+#[tauri::command]
+pub async fn start_ai_chat(window: Window, params: StartAiChatParams) -> Result<StartAiChatResult, String> {
+    let (cancel_tx, mut cancel_rx) = oneshot::channel();
+    {
+        let mut tasks = CHAT_TASKS.lock().unwrap();
+        if tasks.contains_key(&params.request_id) {
+            return Err("对话请求已存在".to_string());
+        }
+        tasks.insert(params.request_id.clone(), cancel_tx);
+    }
+
+    let request_id = params.request_id.clone();
+    tokio::spawn(async move {
+        let mut content = String::new();
+        let result = {
+            let chat = async {
+                let config = {
+                    let db = get_db();
+                    let conn = db
+                        .lock()
+                        .map_err(|e| AiError::new("config", None, format!("数据库锁定失败: {}", e)))?;
+                    load_ai_config(&conn).map_err(|e| AiError::new("config", None, e))?
+                }
+                .ok_or_else(|| AiError::new("config", None, "尚未配置 AI 服务，请先在设置中填写服务地址和模型"))?;
+
+                let messages = build_messages(&params.history, &params.question);
+                stream_chat(&config, &system_prompt(&params.server_id), &messages, |delta| {
+                    content.push_str(delta);
+                    let _ = window.emit(AI_CHAT_TOKEN_EVENT, AiChatTokenEvent {
+                        request_id: request_id.clone(),
+                        delta: delta.to_string(),
+                    });
+                })
+                .await
+            };
+
+            tokio::select! {
+                result = chat => Some(result),
+                _ = &mut cancel_rx => None,
+            }
+        };
+        CHAT_TASKS.lock().unwrap().remove(&request_id);
+
+        match result {
+            Some(Err(error)) => {
+                let _ = window.emit(AI_CHAT_ERROR_EVENT, AiChatErrorEvent {
+                    request_id,
+                    error,
+                    content,
+                });
+            }
+            result => {
+                let _ = window.emit(AI_CHAT_FINISHED_EVENT, AiChatFinishedEvent {
+                    request_id,
+                    content,
+                    timestamp: unix_now() as u64,
+                    cancelled: result.is_none(),
+                });
+            }
+        }
+    });
+
+    Ok(StartAiChatResult {
+        success: true,
+        request_id: params.request_id,
+    })
+}
+
+/// 取消流式对话，已收到的部分回复随 `ai-chat-finished` 事件返回
+///
+/// # 命令名称
+/// `cancel_ai_chat`
+///
+/// # 参数
+/// - `request_id`: 请求ID
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn cancel_ai_chat(params: CancelAiChatParams) -> Result<CancelAiChatResult, String> {
+    let sender = CHAT_TASKS.lock().unwrap().remove(&params.request_id);
+
+    match sender {
+        Some(sender) => {
+            let _ = sender.send(());
+            Ok(CancelAiChatResult {
+                success: true,
+                message: Some("已停止回复".to_string()),
+            })
+        }
+        None => Ok(CancelAiChatResult {
+            success: true,
+            message: Some("回复已结束".to_string()),
+        }),
+    }
+}
+
+/// 获取 AI 服务配置
+///
+/// # 命令名称
+/// `get_ai_config`
+///
+/// # 返回
+/// AI 服务配置，API Key 只返回是否已设置
+#[tauri::command]
+pub async fn get_ai_config() -> Result<AiConfigInfo, String> {
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    let config = load_ai_config(&conn)?;
+    Ok(config_info(config.as_ref()))
+}
+
+/// 保存 AI 服务配置
+///
+/// 支持 OpenAI 兼容接口（包括各类兼容服务和本地推理服务）、Anthropic 和 Ollama。
+/// API Key 使用本机密钥加密后保存。
+///
+/// # 命令名称
+/// `set_ai_config`
+///
+/// # 参数
+/// - `provider`: 服务提供方（`openai` | `anthropic` | `ollama`）
+/// - `base_url`: 服务地址（可选）
+/// - `model`: 模型名称
+/// - `api_key`: API Key（可选，不传则保留原值，空字符串表示清除）
+/// - `timeout_secs`: 响应超时时间，即等待响应和两次收到数据之间的最长间隔（可选，默认 120 秒）
+/// - `connect_timeout_secs`: 连接超时时间（可选，默认 10 秒）
+/// - `max_tokens`: 最大回复 token 数（可选，默认 4096）
+///
+/// # 返回
+/// 保存后的配置
+#[tauri::command]
+pub async fn set_ai_config(params: SetAiConfigParams) -> Result<AiConfigInfo, String> {
+    if !PROVIDERS.contains(&params.provider.as_str()) {
+        return Err(format!("不支持的 AI 服务: {}", params.provider));
+    }
+    let model = params.model.trim().to_string();
+    if model.is_empty() {
+        return Err("模型名称不能为空".to_string());
+    }
+    let base_url = params.base_url.unwrap_or_default().trim().to_string();
+    if !base_url.is_empty() && !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+        return Err("服务地址必须以 http:// 或 https:// 开头".to_string());
+    }
+
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    let config = AiConfig {
+        provider: params.provider,
+        base_url,
+        model,
+        api_key: None,
+        timeout_secs: params.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS).max(1),
+        connect_timeout_secs: params.connect_timeout_secs.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS).max(1),
+        max_tokens: params.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS).max(1),
+    };
+    save_ai_config(&conn, &config)?;
+    // 未传入 API Key 时不改动已保存的密文，即使当前无法解密也不会被清除
+    if let Some(key) = params.api_key {
+        let key = key.trim();
+        save_api_key(&conn, if key.is_empty() { None } else { Some(key) })?;
+    }
+
+    Ok(config_info(load_ai_config(&conn)?.as_ref()))
+}
+
 /// 获取 AI 快速操作建议
 /// 
 /// # 命令名称
@@ -118,9 +412,57 @@ pub async fn get_ai_quick_actions(params: GetAiQuickActionsParams) -> Result<Get
             action: "查看运行进程".to_string(),
         },
     ];
+
+    // 根据主机信息补充与发行版相关的操作
+    let mut actions = actions;
+    if let Some(package_manager) = cached_system_info(&params.server_id).and_then(|info| info.package_manager) {
+        actions.push(QuickAction {
+            id: "4".to_string(),
+            title: "检查软件包更新".to_string(),
+            description: format!("使用 {} 检查可更新的软件包", package_manager),
+            action: format!("使用 {} 检查可更新的软件包", package_manager),
+        });
+    }
     
     Ok(GetAiQuickActionsResult {
         actions: actions,
     })
 }
 
+/// 生成系统提示词，已获取主机信息时附带服务器环境描述
+fn system_prompt(server_id: &str) -> String {
+    match cached_system_info(server_id) {
+        Some(info) => format!("{}\n\n当前服务器环境：\n{}", SYSTEM_PROMPT, info.describe()),
+        None => SYSTEM_PROMPT.to_string(),
+    }
+}
+
+/// 生成返回给前端的配置信息
+fn config_info(config: Option<&AiConfig>) -> AiConfigInfo {
+    match config {
+        Some(config) => AiConfigInfo {
+            configured: !config.model.is_empty(),
+            provider: config.provider.clone(),
+            base_url: if config.base_url.is_empty() {
+                default_base_url(&config.provider).to_string()
+            } else {
+                config.base_url.clone()
+            },
+            model: config.model.clone(),
+            has_api_key: config.api_key.is_some(),
+            timeout_secs: config.timeout_secs,
+            connect_timeout_secs: config.connect_timeout_secs,
+            max_tokens: config.max_tokens,
+        },
+        None => AiConfigInfo {
+            configured: false,
+            provider: "openai".to_string(),
+            base_url: default_base_url("openai").to_string(),
+            model: String::new(),
+            has_api_key: false,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            max_tokens: DEFAULT_MAX_TOKENS,
+        },
+    }
+}
@@ -0,0 +1,705 @@
+/**
+ * AI 服务提供方（OpenAI 兼容接口、Anthropic、Ollama）
+ */
+
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use crate::ai::ChatMessage;
+use crate::db::{delete_setting, get_setting, set_setting};
+use crate::secret::{decrypt_secret, encrypt_secret};
+
+/// 支持的服务提供方
+pub(crate) const PROVIDERS: &[&str] = &["openai", "anthropic", "ollama"];
+
+/// 默认响应超时时间（秒）：等待响应以及两次收到数据之间的最长间隔
+pub(crate) const DEFAULT_TIMEOUT_SECS: u64 = 120;
+/// 默认连接超时时间（秒）
+pub(crate) const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+/// 默认最大回复 token 数（Anthropic 接口必填）
+pub(crate) const DEFAULT_MAX_TOKENS: u32 = 4096;
+/// 最多发送的历史消息条数
+const MAX_HISTORY_MESSAGES: usize = 40;
+/// Anthropic 接口版本
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+const SETTING_PROVIDER: &str = "ai.provider";
+const SETTING_BASE_URL: &str = "ai.base_url";
+const SETTING_MODEL: &str = "ai.model";
+const SETTING_API_KEY: &str = "ai.api_key"; // 加密存储
+const SETTING_TIMEOUT: &str = "ai.timeout_secs";
+const SETTING_CONNECT_TIMEOUT: &str = "ai.connect_timeout_secs";
+const SETTING_MAX_TOKENS: &str = "ai.max_tokens";
+
+/// AI 服务配置
+#[derive(Debug, Clone)]
+pub(crate) struct AiConfig {
+    pub provider: String,        // "openai" | "anthropic" | "ollama"
+    pub base_url: String,        // 为空时使用提供方的默认地址
+    pub model: String,
+    pub api_key: Option<String>, // 明文，仅在内存中使用
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    pub max_tokens: u32,
+}
+
+/// 发往服务提供方的 HTTP 请求（请求体以 JSON 发送）
+#[derive(Debug)]
+pub(crate) struct ProviderRequest {
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Value,
+}
+
+/// 读取已保存的 AI 服务配置，未配置时返回 None
+pub(crate) fn load_ai_config(conn: &Connection) -> Result<Option<AiConfig>, String> {
+    let get = |key: &str| get_setting(conn, key).map_err(|e| format!("读取设置失败: {}", e));
+
+    let provider = match get(SETTING_PROVIDER)? {
+        Some(provider) => provider,
+        None => return Ok(None),
+    };
+    // 无法解密（如密钥文件被删除）时视为未设置，由用户重新填写
+    let api_key = get(SETTING_API_KEY)?.and_then(|encrypted| match decrypt_secret(&encrypted) {
+        Ok(key) => Some(key),
+        Err(e) => {
+            eprintln!("读取 AI 服务 API Key 失败: {}", e);
+            None
+        }
+    });
+
+    Ok(Some(AiConfig {
+        provider,
+        base_url: get(SETTING_BASE_URL)?.unwrap_or_default(),
+        model: get(SETTING_MODEL)?.unwrap_or_default(),
+        api_key,
+        timeout_secs: get(SETTING_TIMEOUT)?.and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_TIMEOUT_SECS),
+        connect_timeout_secs: get(SETTING_CONNECT_TIMEOUT)?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS),
+        max_tokens: get(SETTING_MAX_TOKENS)?.and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_MAX_TOKENS),
+    }))
+}
+
+/// 保存 AI 服务配置（不含 API Key，API Key 由 `save_api_key` 单独保存）
+pub(crate) fn save_ai_config(conn: &Connection, config: &AiConfig) -> Result<(), String> {
+    set_setting(conn, SETTING_PROVIDER, &config.provider)
+        .and_then(|_| set_setting(conn, SETTING_BASE_URL, &config.base_url))
+        .and_then(|_| set_setting(conn, SETTING_MODEL, &config.model))
+        .and_then(|_| set_setting(conn, SETTING_TIMEOUT, &config.timeout_secs.to_string()))
+        .and_then(|_| set_setting(conn, SETTING_CONNECT_TIMEOUT, &config.connect_timeout_secs.to_string()))
+        .and_then(|_| set_setting(conn, SETTING_MAX_TOKENS, &config.max_tokens.to_string()))
+        .map_err(|e| format!("保存设置失败: {}", e))
+}
+
+/// 加密保存 API Key，为 None 时删除已保存的 Key
+pub(crate) fn save_api_key(conn: &Connection, api_key: Option<&str>) -> Result<(), String> {
+    match api_key {
+        Some(key) => set_setting(conn, SETTING_API_KEY, &encrypt_secret(key)?),
+        None => delete_setting(conn, SETTING_API_KEY),
+    }
+    .map_err(|e| format!("保存设置失败: {}", e))
+}
+
+/// 提供方的默认服务地址
+pub(crate) fn default_base_url(provider: &str) -> &'static str {
+    match provider {
+        "anthropic" => "https://api.anthropic.com",
+        "ollama" => "http://127.0.0.1:11434",
+        _ => "https://api.openai.com/v1",
+    }
+}
+
+/// 整理对话消息：只保留 user/assistant 消息，合并相邻的同角色消息，
+/// 去掉开头的 assistant 消息，最后追加本次问题
+///
+/// Anthropic 接口要求消息以 user 开头并交替出现，其他接口也按同样方式整理。
+pub(crate) fn build_messages(history: &[ChatMessage], question: &str) -> Vec<ChatMessage> {
+    let recent = &history[history.len().saturating_sub(MAX_HISTORY_MESSAGES)..];
+    let question = ChatMessage {
+        role: "user".to_string(),
+        content: question.to_string(),
+        timestamp: None,
+    };
+
+    let mut messages: Vec<ChatMessage> = Vec::new();
+    for message in recent.iter().chain(std::iter::once(&question)) {
+        let content = message.content.trim();
+        if content.is_empty() || (message.role != "user" && message.role != "assistant") {
+            continue;
+        }
+        if messages.is_empty() && message.role != "user" {
+            continue;
+        }
+        match messages.last_mut() {
+            Some(last) if last.role == message.role => {
+                last.content.push_str("\n\n");
+                last.content.push_str(content);
+            }
+            _ => messages.push(ChatMessage {
+                role: message.role.clone(),
+                content: content.to_string(),
+                timestamp: None,
+            }),
+        }
+    }
+    messages
+}
+
+/// 生成发往服务提供方的请求
+pub(crate) fn build_request(config: &AiConfig, system: &str, messages: &[ChatMessage]) -> ProviderRequest {
+    let base_url = if config.base_url.trim().is_empty() {
+        default_base_url(&config.provider)
+    } else {
+        config.base_url.trim()
+    };
+    let base_url = base_url.trim_end_matches('/');
+    let turns: Vec<Value> = messages
+        .iter()
+        .map(|m| json!({ "role": m.role, "content": m.content }))
+        .collect();
+    let with_system = || {
+        let mut all = vec![json!({ "role": "system", "content": system })];
+        all.extend(turns.iter().cloned());
+        all
+    };
+
+    let mut headers = Vec::new();
+    let (url, body) = match config.provider.as_str() {
+        "anthropic" => {
+            if let Some(key) = &config.api_key {
+                headers.push(("x-api-key", key.clone()));
+            }
+            headers.push(("anthropic-version", ANTHROPIC_VERSION.to_string()));
+            let url = if base_url.ends_with("/v1") {
+                format!("{}/messages", base_url)
+            } else {
+                format!("{}/v1/messages", base_url)
+            };
+            let body = json!({
+                "model": config.model,
+                "max_tokens": config.max_tokens,
+                "system": system,
+                "messages": turns,
+                "stream": true,
+            });
+            (url, body)
+        }
+        "ollama" => {
+            let body = json!({
+                "model": config.model,
+                "messages": with_system(),
+                "stream": true,
+            });
+            (format!("{}/api/chat", base_url), body)
+        }
+        _ => {
+            let body = json!({
+                "model": config.model,
+                "messages": with_system(),
+                "max_tokens": config.max_tokens,
+                "stream": true,
+            });
+            (format!("{}/chat/completions", base_url), body)
+        }
+    };
+
+    // Ollama 本身不需要认证，配置了 Key 时按 Bearer 发送（用于反向代理）
+    if config.provider != "anthropic" {
+        if let Some(key) = &config.api_key {
+            headers.push(("authorization", format!("Bearer {}", key)));
+        }
+    }
+
+    ProviderRequest { url, headers, body }
+}
+
+/// AI 服务请求错误
+#[derive(Debug, Clone, Serialize)]
+pub struct AiError {
+    pub kind: String,        // "config" | "connect" | "timeout" | "auth" | "rate_limit" | "http" | "invalid_response" | "stream"
+    pub status: Option<u16>, // HTTP 状态码（仅 auth/rate_limit/http）
+    pub message: String,
+    pub retryable: bool,     // 稍后重试是否可能成功
+}
+
+impl AiError {
+    pub(crate) fn new(kind: &str, status: Option<u16>, message: impl Into<String>) -> Self {
+        let retryable = match kind {
+            "connect" | "timeout" | "rate_limit" | "stream" => true,
+            "http" => status.map_or(false, |s| s >= 500),
+            _ => false,
+        };
+        AiError {
+            kind: kind.to_string(),
+            status,
+            message: message.into(),
+            retryable,
+        }
+    }
+
+    /// 根据 HTTP 错误状态码和响应体生成错误
+    fn from_status(status: u16, body: &str) -> Self {
+        let detail = parse_error_message(body).unwrap_or_else(|| body.trim().chars().take(200).collect());
+        let kind = match status {
+            401 | 403 => "auth",
+            429 => "rate_limit",
+            _ => "http",
+        };
+        AiError::new(kind, Some(status), format!("AI 服务返回错误 ({}): {}", status, detail))
+    }
+
+    fn invalid_response() -> Self {
+        AiError::new("invalid_response", None, "AI 服务返回的数据格式无法识别")
+    }
+}
+
+/// 流式响应解码器
+///
+/// OpenAI 兼容接口和 Anthropic 返回 SSE（`data: {...}` 行），Ollama 返回每行一个 JSON 对象。
+/// 响应数据可能在任意位置被分块，不完整的行留在缓冲区中等待后续数据。
+pub(crate) struct StreamDecoder {
+    provider: String,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl StreamDecoder {
+    pub(crate) fn new(provider: &str) -> Self {
+        StreamDecoder {
+            provider: provider.to_string(),
+            buffer: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// 追加响应数据，返回新解析出的文本片段
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Result<Vec<String>, AiError> {
+        self.buffer.extend_from_slice(chunk);
+        let mut deltas = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            if let Some(delta) = self.parse_line(String::from_utf8_lossy(&line).trim())? {
+                deltas.push(delta);
+            }
+        }
+        Ok(deltas)
+    }
+
+    /// 响应结束时解析缓冲区中剩余的最后一行
+    pub(crate) fn finish(&mut self) -> Result<Vec<String>, AiError> {
+        self.push(b"\n")
+    }
+
+    /// 是否已收到结束标记
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<String>, AiError> {
+        if line.is_empty() || self.done {
+            return Ok(None);
+        }
+        let payload = if self.provider == "ollama" {
+            line
+        } else {
+            // 忽略 `event:`、`id:` 和注释行，事件类型在 data 的 JSON 中也有
+            match line.strip_prefix("data:") {
+                Some(data) => data.trim(),
+                None => return Ok(None),
+            }
+        };
+        if payload == "[DONE]" {
+            self.done = true;
+            return Ok(None);
+        }
+
+        let value: Value = serde_json::from_str(payload).map_err(|_| AiError::invalid_response())?;
+        if let Some(message) = error_message(&value) {
+            return Err(AiError::new("stream", None, format!("AI 服务返回错误: {}", message)));
+        }
+        let delta = match self.provider.as_str() {
+            "anthropic" => {
+                self.done = value["type"] == "message_stop";
+                value["delta"]["text"].as_str()
+            }
+            "ollama" => {
+                self.done = value["done"] == true;
+                value["message"]["content"].as_str()
+            }
+            _ => {
+                // 部分兼容服务不发送 `[DONE]`，以 finish_reason 作为结束标记
+                self.done = !value["choices"][0]["finish_reason"].is_null();
+                value["choices"][0]["delta"]["content"].as_str()
+            }
+        };
+        Ok(delta.filter(|d| !d.is_empty()).map(|d| d.to_string()))
+    }
+}
+
+/// 从非流式响应中提取回复内容（部分 OpenAI 兼容服务会忽略 `stream` 参数）
+///
+/// - OpenAI 兼容：`choices[0].message.content`
+/// - Anthropic：`content` 中所有 `text` 块
+pub(crate) fn parse_reply(provider: &str, response: &Value) -> Option<String> {
+    match provider {
+        "anthropic" => response["content"].as_array().map(|blocks| {
+            blocks
+                .iter()
+                .filter(|b| b["type"] == "text")
+                .filter_map(|b| b["text"].as_str())
+                .collect::<String>()
+        }),
+        _ => response["choices"][0]["message"]["content"].as_str().map(|s| s.to_string()),
+    }
+}
+
+/// 从错误响应中提取错误信息
+///
+/// OpenAI 和 Anthropic 为 `{"error": {"message": ...}}`，Ollama 为 `{"error": "..."}`。
+fn error_message(value: &Value) -> Option<String> {
+    value["error"]["message"]
+        .as_str()
+        .or_else(|| value["error"].as_str())
+        .map(|s| s.to_string())
+}
+
+fn parse_error_message(body: &str) -> Option<String> {
+    error_message(&serde_json::from_str(body).ok()?)
+}
+
+/// 以流式方式发送对话请求，每收到一段文本调用一次 `on_delta`，返回完整回复
+///
+/// `timeout_secs` 限制等待响应头以及两次收到数据之间的最长间隔，长回复不会因总时长超时。
+/// 取消时直接丢弃返回的 future，连接随之关闭。
+pub(crate) async fn stream_chat<F>(
+    config: &AiConfig,
+    system: &str,
+    messages: &[ChatMessage],
+    mut on_delta: F,
+) -> Result<String, AiError>
+where
+    F: FnMut(&str),
+{
+    let idle_timeout = Duration::from_secs(config.timeout_secs);
+    let timed_out = || AiError::new("timeout", None, format!("AI 服务响应超时（{} 秒）", config.timeout_secs));
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .build()
+        .map_err(|e| AiError::new("connect", None, format!("创建 HTTP 客户端失败: {}", e)))?;
+
+    let request = build_request(config, system, messages);
+    let mut builder = client.post(&request.url).json(&request.body);
+    for (name, value) in &request.headers {
+        builder = builder.header(*name, value);
+    }
+
+    let mut response = tokio::time::timeout(idle_timeout, builder.send())
+        .await
+        .map_err(|_| timed_out())?
+        .map_err(request_error)?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = tokio::time::timeout(idle_timeout, response.text())
+            .await
+            .map_err(|_| timed_out())?
+            .unwrap_or_default();
+        return Err(AiError::from_status(status.as_u16(), &body));
+    }
+
+    let is_json = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(false, |v| v.starts_with("application/json"));
+    if is_json && config.provider != "ollama" {
+        let body = tokio::time::timeout(idle_timeout, response.text())
+            .await
+            .map_err(|_| timed_out())?
+            .map_err(request_error)?;
+        let value: Value = serde_json::from_str(&body).map_err(|_| AiError::invalid_response())?;
+        let content = parse_reply(&config.provider, &value).ok_or_else(AiError::invalid_response)?;
+        on_delta(&content);
+        return Ok(content);
+    }
+
+    let mut decoder = StreamDecoder::new(&config.provider);
+    let mut content = String::new();
+    loop {
+        let chunk = tokio::time::timeout(idle_timeout, response.chunk())
+            .await
+            .map_err(|_| timed_out())?
+            .map_err(request_error)?;
+        let deltas = match &chunk {
+            Some(chunk) => decoder.push(chunk)?,
+            None => decoder.finish()?,
+        };
+        for delta in deltas {
+            on_delta(&delta);
+            content.push_str(&delta);
+        }
+        if chunk.is_none() || decoder.is_done() {
+            break;
+        }
+    }
+
+    if !decoder.is_done() {
+        return Err(AiError::new("stream", None, "AI 服务连接意外中断，回复不完整"));
+    }
+    Ok(content)
+}
+
+/// 转换请求错误
+fn request_error(error: reqwest::Error) -> AiError {
+    if error.is_connect() {
+        AiError::new("connect", None, format!("无法连接 AI 服务: {}", error))
+    } else if error.is_timeout() {
+        AiError::new("timeout", None, format!("AI 服务响应超时: {}", error))
+    } else {
+        AiError::new("stream", None, format!("读取 AI 服务响应失败: {}", error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// 在本机启动只处理一次请求的 HTTP 服务，按顺序以分块编码发送 `chunks`，返回服务地址和收到的请求
+    async fn mock_server(status: &'static str, content_type: &'static str, chunks: Vec<&'static str>) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text[..end]
+                        .lines()
+                        .filter_map(|line| line.split_once(':'))
+                        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        break;
+                    }
+                }
+            }
+
+            let head = format!(
+                "HTTP/1.1 {}\r\ncontent-type: {}\r\ntransfer-encoding: chunked\r\n\r\n",
+                status, content_type
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            for chunk in chunks {
+                socket
+                    .write_all(format!("{:x}\r\n{}\r\n", chunk.len(), chunk).as_bytes())
+                    .await
+                    .unwrap();
+                socket.flush().await.unwrap();
+                // 让客户端分别收到各个分块
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            socket.write_all(b"0\r\n\r\n").await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        (base_url, handle)
+    }
+
+    fn test_config(provider: &str, base_url: String) -> AiConfig {
+        AiConfig {
+            provider: provider.to_string(),
+            base_url,
+            model: "test-model".to_string(),
+            api_key: Some("sk-test".to_string()),
+            timeout_secs: 5,
+            connect_timeout_secs: 5,
+            max_tokens: 100,
+        }
+    }
+
+    /// 发送一次对话请求，返回依次收到的文本片段和最终结果
+    async fn chat(config: &AiConfig) -> (Vec<String>, Result<String, AiError>) {
+        let messages = build_messages(&[], "hello");
+        let mut deltas = Vec::new();
+        let result = stream_chat(config, "system", &messages, |delta| deltas.push(delta.to_string())).await;
+        (deltas, result)
+    }
+
+    #[test]
+    fn saving_config_keeps_undecryptable_api_key() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE app_settings (key TEXT PRIMARY KEY, value TEXT NOT NULL, updated_at TEXT)", [])
+            .unwrap();
+        set_setting(&conn, SETTING_API_KEY, "v1:not-base64!").unwrap();
+
+        save_ai_config(&conn, &test_config("openai", String::new())).unwrap();
+        let config = load_ai_config(&conn).unwrap().unwrap();
+        assert_eq!(config.api_key, None);
+        assert_eq!(get_setting(&conn, SETTING_API_KEY).unwrap().as_deref(), Some("v1:not-base64!"));
+
+        save_api_key(&conn, None).unwrap();
+        assert_eq!(get_setting(&conn, SETTING_API_KEY).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn streams_openai_until_done_marker() {
+        let (url, server) = mock_server("200 OK", "text/event-stream", vec![
+            ": keep-alive\n\ndata: {\"choices\":[{\"delta\":{\"role\":\"assistant\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"},\"finish_reason\":null}]}\n\ndata: [DONE]\n\n",
+        ])
+        .await;
+
+        let (deltas, result) = chat(&test_config("openai", url)).await;
+        assert_eq!(deltas, vec!["Hel", "lo"]);
+        assert_eq!(result.unwrap(), "Hello");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /chat/completions"), "{}", request);
+        assert!(request.to_lowercase().contains("authorization: bearer sk-test"));
+        assert!(request.contains("\"stream\":true"));
+    }
+
+    #[tokio::test]
+    async fn streams_openai_ending_on_finish_reason_only() {
+        let (url, _server) = mock_server("200 OK", "text/event-stream", vec![
+            "data: {\"choices\":[{\"delta\":{\"content\":\"ok\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+        ])
+        .await;
+
+        let (deltas, result) = chat(&test_config("openai", url)).await;
+        assert_eq!(deltas, vec!["ok"]);
+        assert_eq!(result.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn streams_anthropic_until_message_stop() {
+        let (url, server) = mock_server("200 OK", "text/event-stream", vec![
+            "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{}}\n\n",
+            "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\n",
+            "event: ping\ndata: {\"type\":\"ping\"}\n\nevent: message_delta\ndata: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"}}\n\n",
+            "event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+        ])
+        .await;
+
+        let (deltas, result) = chat(&test_config("anthropic", url)).await;
+        assert_eq!(deltas, vec!["Hi"]);
+        assert_eq!(result.unwrap(), "Hi");
+
+        let request = server.await.unwrap().to_lowercase();
+        assert!(request.contains("x-api-key: sk-test"));
+        assert!(request.contains("anthropic-version"));
+    }
+
+    #[tokio::test]
+    async fn streams_ollama_ndjson() {
+        let (url, _server) = mock_server("200 OK", "application/x-ndjson", vec![
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"ol\"},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"lama\"},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true}",
+        ])
+        .await;
+
+        let (deltas, result) = chat(&test_config("ollama", url)).await;
+        assert_eq!(deltas, vec!["ol", "lama"]);
+        assert_eq!(result.unwrap(), "ollama");
+    }
+
+    #[tokio::test]
+    async fn reassembles_lines_split_across_chunks() {
+        let (url, _server) = mock_server("200 OK", "text/event-stream", vec![
+            "data: {\"choices\":[{\"delta\":{\"content\":\"你",
+            "好\"},\"finish_reason\":null}]}\n",
+            "\ndata: [DO",
+            "NE]\n\n",
+        ])
+        .await;
+
+        let (deltas, result) = chat(&test_config("openai", url)).await;
+        assert_eq!(deltas, vec!["你好"]);
+        assert_eq!(result.unwrap(), "你好");
+    }
+
+    #[tokio::test]
+    async fn maps_error_status_codes() {
+        let (url, _server) = mock_server("401 Unauthorized", "application/json", vec![
+            "{\"type\":\"error\",\"error\":{\"type\":\"authentication_error\",\"message\":\"invalid x-api-key\"}}",
+        ])
+        .await;
+        let error = chat(&test_config("anthropic", url)).await.1.unwrap_err();
+        assert_eq!((error.kind.as_str(), error.status, error.retryable), ("auth", Some(401), false));
+        assert!(error.message.contains("invalid x-api-key"));
+
+        let (url, _server) = mock_server("429 Too Many Requests", "application/json", vec![
+            "{\"error\":{\"message\":\"slow down\"}}",
+        ])
+        .await;
+        let error = chat(&test_config("openai", url)).await.1.unwrap_err();
+        assert_eq!((error.kind.as_str(), error.status, error.retryable), ("rate_limit", Some(429), true));
+        assert!(error.message.contains("slow down"));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_non_stream_json_reply() {
+        let (url, _server) = mock_server("200 OK", "application/json", vec![
+            "{\"choices\":[{\"message\":{\"role\":\"assistant\",\"content\":\"whole reply\"}}]}",
+        ])
+        .await;
+
+        let (deltas, result) = chat(&test_config("openai", url)).await;
+        assert_eq!(deltas, vec!["whole reply"]);
+        assert_eq!(result.unwrap(), "whole reply");
+    }
+
+    #[tokio::test]
+    async fn parses_non_stream_anthropic_reply() {
+        let (url, server) = mock_server("200 OK", "application/json", vec![
+            "{\"type\":\"message\",\"content\":[{\"type\":\"text\",\"text\":\"first \"},{\"type\":\"tool_use\",\"id\":\"t\"},{\"type\":\"text\",\"text\":\"second\"}]}",
+        ])
+        .await;
+
+        let (deltas, result) = chat(&test_config("anthropic", url)).await;
+        assert_eq!(deltas, vec!["first second"]);
+        assert_eq!(result.unwrap(), "first second");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /v1/messages"), "{}", request);
+        assert!(request.contains("\"system\":\"system\""));
+    }
+
+    #[tokio::test]
+    async fn rejects_non_stream_reply_without_content() {
+        let (url, _server) = mock_server("200 OK", "application/json", vec!["{\"choices\":[]}"]).await;
+
+        let (deltas, result) = chat(&test_config("openai", url)).await;
+        assert!(deltas.is_empty());
+        assert_eq!(result.unwrap_err().kind, "invalid_response");
+    }
+
+    #[tokio::test]
+    async fn reports_interrupted_stream() {
+        let (url, _server) = mock_server("200 OK", "text/event-stream", vec![
+            "data: {\"choices\":[{\"delta\":{\"content\":\"part\"},\"finish_reason\":null}]}\n\n",
+        ])
+        .await;
+
+        let (deltas, result) = chat(&test_config("openai", url)).await;
+        assert_eq!(deltas, vec!["part"]);
+        assert_eq!(result.unwrap_err().kind, "stream");
+    }
+}
@@ -0,0 +1,743 @@
+/**
+ * 监控告警相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::api::notification::Notification;
+use tauri::{AppHandle, Manager};
+use crate::db::get_db;
+use crate::monitor::SystemMonitorData;
+use crate::server::get_server_name_and_group;
+use crate::util::unix_now;
+
+/// 告警事件名
+const ALERT_EVENT: &str = "alert-event";
+/// 默认返回的告警记录数
+const DEFAULT_EVENT_LIMIT: i64 = 200;
+/// 未指定恢复阈值时，恢复阈值与触发阈值的相对差距
+const DEFAULT_HYSTERESIS: f64 = 0.05;
+
+/// 支持的指标
+const METRICS: &[&str] = &["cpu", "memory", "disk", "load", "load_per_core", "download", "upload"];
+
+lazy_static::lazy_static! {
+    static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+    static ref ALERT_ENGINE: Mutex<AlertEngine> = Mutex::new(AlertEngine::default());
+}
+
+/// 告警规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: Option<String>,              // 规则ID（新建时为空）
+    pub name: String,
+    pub server_id: Option<String>,       // 只对指定服务器生效（可选）
+    pub group_name: Option<String>,      // 只对指定分组生效（可选，都为空时对所有服务器生效）
+    pub metric: String,                  // "cpu" | "memory" | "disk" | "load" | "load_per_core" | "download" | "upload"
+    pub mount: Option<String>,           // 磁盘挂载点（metric 为 disk 时有效，为空表示使用率最高的磁盘）
+    pub operator: String,                // ">" | "<"
+    pub threshold: f64,
+    pub clear_threshold: Option<f64>,    // 恢复阈值（可选，默认与触发阈值相差 5%）
+    pub duration_secs: i64,              // 持续超过阈值多久才触发
+    pub enabled: bool,
+}
+
+impl AlertRule {
+    /// 规则是否对指定服务器生效
+    fn applies_to(&self, server_id: &str, group_name: Option<&str>) -> bool {
+        self.enabled
+            && self.server_id.as_deref().map_or(true, |id| id == server_id)
+            && self.group_name.as_deref().map_or(true, |group| Some(group) == group_name)
+    }
+
+    /// 当前值是否超过触发阈值
+    fn is_breached(&self, value: f64) -> bool {
+        match self.operator.as_str() {
+            "<" => value < self.threshold,
+            _ => value > self.threshold,
+        }
+    }
+
+    /// 当前值是否已回到恢复阈值以内
+    fn is_cleared(&self, value: f64) -> bool {
+        match self.operator.as_str() {
+            "<" => value >= self.clear_threshold.unwrap_or(self.threshold * (1.0 + DEFAULT_HYSTERESIS)),
+            _ => value <= self.clear_threshold.unwrap_or(self.threshold * (1.0 - DEFAULT_HYSTERESIS)),
+        }
+    }
+}
+
+/// 告警规则评估所需的指标
+#[derive(Debug, Clone, Default)]
+pub struct AlertSample {
+    pub cpu: f64,
+    pub memory: f64,               // 内存使用率 (0-100)
+    pub load1: f64,
+    pub cores: usize,
+    pub disks: Vec<(String, f64)>, // (挂载点, 使用率)
+    pub download: f64,
+    pub upload: f64,
+}
+
+impl From<&SystemMonitorData> for AlertSample {
+    fn from(data: &SystemMonitorData) -> Self {
+        AlertSample {
+            cpu: data.cpu.usage,
+            memory: if data.memory.total > 0 {
+                data.memory.used as f64 * 100.0 / data.memory.total as f64
+            } else {
+                0.0
+            },
+            load1: data
+                .cpu
+                .load_average
+                .split(',')
+                .next()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(0.0),
+            cores: data.cpu.cores,
+            disks: data.disk.iter().map(|d| (d.mount.clone(), d.usage)).collect(),
+            download: data.network.download as f64,
+            upload: data.network.upload as f64,
+        }
+    }
+}
+
+impl AlertSample {
+    /// 取规则对应的指标值，指标不存在时返回 None
+    fn value_for(&self, rule: &AlertRule) -> Option<f64> {
+        match rule.metric.as_str() {
+            "cpu" => Some(self.cpu),
+            "memory" => Some(self.memory),
+            "load" => Some(self.load1),
+            "load_per_core" if self.cores > 0 => Some(self.load1 / self.cores as f64),
+            "disk" => match rule.mount.as_deref() {
+                Some(mount) => self.disks.iter().find(|(m, _)| m == mount).map(|(_, usage)| *usage),
+                None => self.disks.iter().map(|(_, usage)| *usage).reduce(f64::max),
+            },
+            "download" => Some(self.download),
+            "upload" => Some(self.upload),
+            _ => None,
+        }
+    }
+}
+
+/// 告警状态变化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertTransition {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub server_id: String,
+    pub state: String, // "firing" | "resolved"
+    pub value: f64,
+    pub threshold: f64,
+}
+
+/// 单条规则在单台服务器上的状态
+#[derive(Debug, Clone, Default)]
+struct RuleState {
+    breach_since: Option<i64>, // 开始超过阈值的时间
+    firing: bool,
+}
+
+/// 告警规则评估引擎（不依赖时钟和数据库，可以直接用构造的采样验证）
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    states: HashMap<(String, String), RuleState>, // (rule_id, server_id) -> 状态
+}
+
+impl AlertEngine {
+    /// 用一次采样评估所有规则，返回状态发生变化的告警
+    pub fn evaluate(&mut self, rules: &[AlertRule], server_id: &str, group_name: Option<&str>, sample: &AlertSample, now: i64) -> Vec<AlertTransition> {
+        let mut transitions = Vec::new();
+
+        // 规则被删除、禁用或不再适用时丢弃其状态
+        let active: Vec<&str> = rules
+            .iter()
+            .filter(|rule| rule.applies_to(server_id, group_name))
+            .filter_map(|rule| rule.id.as_deref())
+            .collect();
+        self.states
+            .retain(|(rule_id, id), _| id != server_id || active.contains(&rule_id.as_str()));
+
+        for rule in rules.iter().filter(|rule| rule.applies_to(server_id, group_name)) {
+            let rule_id = match &rule.id {
+                Some(id) => id,
+                None => continue,
+            };
+            let value = match sample.value_for(rule) {
+                Some(value) => value,
+                None => continue,
+            };
+            let state = self
+                .states
+                .entry((rule_id.clone(), server_id.to_string()))
+                .or_default();
+
+            let changed_to = if state.firing {
+                if rule.is_cleared(value) {
+                    state.firing = false;
+                    state.breach_since = None;
+                    Some("resolved")
+                } else {
+                    None
+                }
+            } else if rule.is_breached(value) {
+                let since = *state.breach_since.get_or_insert(now);
+                if now - since >= rule.duration_secs {
+                    state.firing = true;
+                    Some("firing")
+                } else {
+                    None
+                }
+            } else {
+                state.breach_since = None;
+                None
+            };
+
+            if let Some(new_state) = changed_to {
+                transitions.push(AlertTransition {
+                    rule_id: rule_id.clone(),
+                    rule_name: rule.name.clone(),
+                    server_id: server_id.to_string(),
+                    state: new_state.to_string(),
+                    value,
+                    threshold: rule.threshold,
+                });
+            }
+        }
+
+        transitions
+    }
+}
+
+/// 告警记录
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEvent {
+    pub id: i64,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub server_id: String,
+    pub state: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub message: String,
+    pub created_at: i64, // Unix 时间戳，秒
+}
+
+/// 获取告警规则返回
+#[derive(Debug, Serialize)]
+pub struct GetAlertRulesResult {
+    pub rules: Vec<AlertRule>,
+}
+
+/// 保存告警规则返回
+#[derive(Debug, Serialize)]
+pub struct SaveAlertRuleResult {
+    pub success: bool,
+    pub id: String,
+    pub message: Option<String>,
+}
+
+/// 删除告警规则参数
+#[derive(Debug, Deserialize)]
+pub struct DeleteAlertRuleParams {
+    pub rule_id: String,
+}
+
+/// 获取告警记录参数
+#[derive(Debug, Deserialize)]
+pub struct GetAlertEventsParams {
+    pub server_id: Option<String>, // 只返回指定服务器的记录（可选）
+    pub limit: Option<i64>,        // 最大返回条数（默认 200）
+}
+
+/// 获取告警记录返回
+#[derive(Debug, Serialize)]
+pub struct GetAlertEventsResult {
+    pub events: Vec<AlertEvent>,
+}
+
+/// 清除告警记录参数
+#[derive(Debug, Deserialize)]
+pub struct ClearAlertEventsParams {
+    pub server_id: Option<String>, // 只清除指定服务器的记录（可选）
+}
+
+/// 告警操作返回
+#[derive(Debug, Serialize)]
+pub struct AlertOperationResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 获取所有告警规则
+///
+/// # 命令名称
+/// `get_alert_rules`
+///
+/// # 返回
+/// - `rules`: 告警规则列表
+#[tauri::command]
+pub async fn get_alert_rules() -> Result<GetAlertRulesResult, String> {
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    let rules = load_rules(&conn).map_err(|e| format!("查询告警规则失败: {}", e))?;
+
+    Ok(GetAlertRulesResult { rules })
+}
+
+/// 保存告警规则（没有 ID 时新建）
+///
+/// # 命令名称
+/// `save_alert_rule`
+///
+/// # 参数
+/// - `id`: 规则ID（可选）
+/// - `name`: 规则名称
+/// - `server_id` / `group_name`: 生效范围（可选）
+/// - `metric`: 指标
+/// - `mount`: 磁盘挂载点（可选）
+/// - `operator`: 比较方式
+/// - `threshold`: 触发阈值
+/// - `clear_threshold`: 恢复阈值（可选）
+/// - `duration_secs`: 持续时间（秒）
+/// - `enabled`: 是否启用
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `id`: 规则ID
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn save_alert_rule(params: AlertRule) -> Result<SaveAlertRuleResult, String> {
+    if !METRICS.contains(&params.metric.as_str()) {
+        return Err(format!("不支持的指标: {}", params.metric));
+    }
+    if params.operator != ">" && params.operator != "<" {
+        return Err(format!("不支持的比较方式: {}", params.operator));
+    }
+    if params.duration_secs < 0 {
+        return Err("持续时间不能为负数".to_string());
+    }
+    if let Some(clear) = params.clear_threshold {
+        // 恢复阈值必须在触发阈值的另一侧，否则告警会在触发后立即恢复或永远不会恢复
+        let valid = match params.operator.as_str() {
+            "<" => clear >= params.threshold,
+            _ => clear <= params.threshold,
+        };
+        if !valid {
+            let side = if params.operator == "<" { "不能小于" } else { "不能大于" };
+            return Err(format!("恢复阈值{}触发阈值", side));
+        }
+    }
+
+    let id = params.id.clone().unwrap_or_else(|| {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        format!("rule-{}", nanos)
+    });
+
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    conn.execute(
+        "INSERT INTO alert_rules
+            (id, name, server_id, group_name, metric, mount, operator, threshold, clear_threshold, duration_secs, enabled)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name,
+            server_id = excluded.server_id,
+            group_name = excluded.group_name,
+            metric = excluded.metric,
+            mount = excluded.mount,
+            operator = excluded.operator,
+            threshold = excluded.threshold,
+            clear_threshold = excluded.clear_threshold,
+            duration_secs = excluded.duration_secs,
+            enabled = excluded.enabled",
+        params![
+            id,
+            params.name,
+            params.server_id,
+            params.group_name,
+            params.metric,
+            params.mount,
+            params.operator,
+            params.threshold,
+            params.clear_threshold,
+            params.duration_secs,
+            params.enabled
+        ],
+    )
+    .map_err(|e| format!("保存告警规则失败: {}", e))?;
+
+    Ok(SaveAlertRuleResult {
+        success: true,
+        id,
+        message: Some("保存成功".to_string()),
+    })
+}
+
+/// 删除告警规则
+///
+/// # 命令名称
+/// `delete_alert_rule`
+///
+/// # 参数
+/// - `rule_id`: 规则ID
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn delete_alert_rule(params: DeleteAlertRuleParams) -> Result<AlertOperationResult, String> {
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    let rows_affected = conn
+        .execute("DELETE FROM alert_rules WHERE id = ?1", params![params.rule_id])
+        .map_err(|e| format!("删除告警规则失败: {}", e))?;
+
+    if rows_affected == 0 {
+        return Err("告警规则不存在".to_string());
+    }
+
+    Ok(AlertOperationResult {
+        success: true,
+        message: Some("删除成功".to_string()),
+    })
+}
+
+/// 获取告警记录（按时间倒序）
+///
+/// # 命令名称
+/// `get_alert_events`
+///
+/// # 参数
+/// - `server_id`: 服务器ID（可选）
+/// - `limit`: 最大返回条数（可选）
+///
+/// # 返回
+/// - `events`: 告警记录列表
+#[tauri::command]
+pub async fn get_alert_events(params: GetAlertEventsParams) -> Result<GetAlertEventsResult, String> {
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, rule_id, rule_name, server_id, state, value, threshold, message, created_at FROM alert_events
+             WHERE ?1 IS NULL OR server_id = ?1
+             ORDER BY created_at DESC, id DESC LIMIT ?2",
+        )
+        .map_err(|e| format!("查询准备失败: {}", e))?;
+    let events = stmt
+        .query_map(params![params.server_id, params.limit.unwrap_or(DEFAULT_EVENT_LIMIT)], |row| {
+            Ok(AlertEvent {
+                id: row.get(0)?,
+                rule_id: row.get(1)?,
+                rule_name: row.get(2)?,
+                server_id: row.get(3)?,
+                state: row.get(4)?,
+                value: row.get(5)?,
+                threshold: row.get(6)?,
+                message: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })
+        .map_err(|e| format!("查询执行失败: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("数据解析失败: {}", e))?;
+
+    Ok(GetAlertEventsResult { events })
+}
+
+/// 清除告警记录
+///
+/// # 命令名称
+/// `clear_alert_events`
+///
+/// # 参数
+/// - `server_id`: 服务器ID（可选，为空时清除全部）
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn clear_alert_events(params: ClearAlertEventsParams) -> Result<AlertOperationResult, String> {
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    conn.execute(
+        "DELETE FROM alert_events WHERE ?1 IS NULL OR server_id = ?1",
+        params![params.server_id],
+    )
+    .map_err(|e| format!("清除告警记录失败: {}", e))?;
+
+    Ok(AlertOperationResult {
+        success: true,
+        message: Some("已清除告警记录".to_string()),
+    })
+}
+
+/// 保存应用句柄，用于发送桌面通知和推送告警事件
+pub(crate) fn set_app_handle(handle: AppHandle) {
+    *APP_HANDLE.lock().unwrap() = Some(handle);
+}
+
+/// 用一次监控采样评估告警规则（失败只记录日志，不影响监控本身）
+pub(crate) fn evaluate_monitor_sample(server_id: &str, data: &SystemMonitorData) {
+    let now = unix_now();
+
+    let db = get_db();
+    let conn = match db.lock() {
+        Ok(conn) => conn,
+        Err(_) => return,
+    };
+    let rules = match load_rules(&conn) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("读取告警规则失败: {}", e);
+            return;
+        }
+    };
+    let (server_name, group_name) = get_server_name_and_group(&conn, server_id)
+        .unwrap_or_else(|| (server_id.to_string(), None));
+
+    let transitions = ALERT_ENGINE.lock().unwrap().evaluate(
+        &rules,
+        server_id,
+        group_name.as_deref(),
+        &AlertSample::from(data),
+        now,
+    );
+
+    for transition in transitions {
+        let message = format_alert_message(&server_name, &transition);
+        let result = conn.execute(
+            "INSERT INTO alert_events (rule_id, rule_name, server_id, state, value, threshold, message, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                transition.rule_id,
+                transition.rule_name,
+                transition.server_id,
+                transition.state,
+                transition.value,
+                transition.threshold,
+                message,
+                now
+            ],
+        );
+        let id = match result {
+            Ok(_) => conn.last_insert_rowid(),
+            Err(e) => {
+                eprintln!("保存告警记录失败: {}", e);
+                0
+            }
+        };
+
+        notify(AlertEvent {
+            id,
+            rule_id: transition.rule_id,
+            rule_name: transition.rule_name,
+            server_id: transition.server_id,
+            state: transition.state,
+            value: transition.value,
+            threshold: transition.threshold,
+            message,
+            created_at: now,
+        });
+    }
+}
+
+/// 发送桌面通知并推送告警事件
+fn notify(event: AlertEvent) {
+    let handle = match APP_HANDLE.lock().unwrap().clone() {
+        Some(handle) => handle,
+        None => return,
+    };
+
+    let title = if event.state == "firing" { "服务器告警" } else { "告警已恢复" };
+    let _ = Notification::new(&handle.config().tauri.bundle.identifier)
+        .title(title)
+        .body(&event.message)
+        .show();
+    let _ = handle.emit_all(ALERT_EVENT, event);
+}
+
+/// 生成告警消息
+fn format_alert_message(server_name: &str, transition: &AlertTransition) -> String {
+    if transition.state == "firing" {
+        format!(
+            "{}: {} 当前值 {:.1}，阈值 {}",
+            server_name, transition.rule_name, transition.value, transition.threshold
+        )
+    } else {
+        format!(
+            "{}: {} 已恢复，当前值 {:.1}",
+            server_name, transition.rule_name, transition.value
+        )
+    }
+}
+
+/// 从数据库读取所有告警规则
+fn load_rules(conn: &Connection) -> rusqlite::Result<Vec<AlertRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, server_id, group_name, metric, mount, operator, threshold, clear_threshold, duration_secs, enabled
+         FROM alert_rules ORDER BY created_at",
+    )?;
+    let rules = stmt
+        .query_map([], |row| {
+            Ok(AlertRule {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                server_id: row.get(2)?,
+                group_name: row.get(3)?,
+                metric: row.get(4)?,
+                mount: row.get(5)?,
+                operator: row.get(6)?,
+                threshold: row.get(7)?,
+                clear_threshold: row.get(8)?,
+                duration_secs: row.get(9)?,
+                enabled: row.get(10)?,
+            })
+        })?
+        .collect();
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(metric: &str, operator: &str, threshold: f64, clear_threshold: Option<f64>, duration_secs: i64) -> AlertRule {
+        AlertRule {
+            id: Some("rule-1".to_string()),
+            name: "test".to_string(),
+            server_id: None,
+            group_name: None,
+            metric: metric.to_string(),
+            mount: None,
+            operator: operator.to_string(),
+            threshold,
+            clear_threshold,
+            duration_secs,
+            enabled: true,
+        }
+    }
+
+    fn cpu(value: f64) -> AlertSample {
+        AlertSample { cpu: value, ..AlertSample::default() }
+    }
+
+    fn states(engine: &mut AlertEngine, rules: &[AlertRule], samples: &[(i64, f64)]) -> Vec<(i64, String)> {
+        samples
+            .iter()
+            .flat_map(|(now, value)| {
+                engine
+                    .evaluate(rules, "srv", None, &cpu(*value), *now)
+                    .into_iter()
+                    .map(move |t| (*now, t.state))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fires_only_after_breach_lasts_for_duration() {
+        let rules = [rule("cpu", ">", 90.0, None, 60)];
+        let mut engine = AlertEngine::default();
+
+        // 中途回落会重新计时
+        let fired = states(&mut engine, &rules, &[(0, 95.0), (30, 95.0), (40, 50.0), (50, 95.0), (100, 95.0), (110, 96.0)]);
+        assert_eq!(fired, vec![(110, "firing".to_string())]);
+    }
+
+    #[test]
+    fn zero_duration_fires_immediately() {
+        let rules = [rule("cpu", ">", 90.0, None, 0)];
+        let mut engine = AlertEngine::default();
+        let transitions = engine.evaluate(&rules, "srv", None, &cpu(91.0), 0);
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].state, "firing");
+        assert_eq!(transitions[0].value, 91.0);
+        assert_eq!(transitions[0].threshold, 90.0);
+    }
+
+    #[test]
+    fn resolves_only_below_clear_threshold() {
+        let rules = [rule("cpu", ">", 90.0, Some(80.0), 0)];
+        let mut engine = AlertEngine::default();
+
+        let changes = states(&mut engine, &rules, &[(0, 95.0), (10, 85.0), (20, 89.0), (30, 80.0)]);
+        assert_eq!(changes, vec![(0, "firing".to_string()), (30, "resolved".to_string())]);
+    }
+
+    #[test]
+    fn default_hysteresis_for_both_operators() {
+        let mut engine = AlertEngine::default();
+        let above = [rule("cpu", ">", 100.0, None, 0)];
+        let changes = states(&mut engine, &above, &[(0, 101.0), (10, 96.0), (20, 95.0)]);
+        assert_eq!(changes, vec![(0, "firing".to_string()), (20, "resolved".to_string())]);
+
+        let mut engine = AlertEngine::default();
+        let below = [rule("cpu", "<", 100.0, None, 0)];
+        let changes = states(&mut engine, &below, &[(0, 99.0), (10, 104.0), (20, 105.0)]);
+        assert_eq!(changes, vec![(0, "firing".to_string()), (20, "resolved".to_string())]);
+    }
+
+    #[test]
+    fn refires_after_resolving_with_new_duration() {
+        let rules = [rule("cpu", ">", 90.0, Some(80.0), 20)];
+        let mut engine = AlertEngine::default();
+
+        let changes = states(&mut engine, &rules, &[
+            (0, 95.0),
+            (20, 95.0),  // 持续 20 秒，触发
+            (30, 70.0),  // 恢复
+            (40, 95.0),  // 重新开始计时
+            (50, 95.0),
+            (60, 95.0),  // 再次持续 20 秒，重新触发
+            (70, 99.0),  // 仍在告警中，不重复通知
+        ]);
+        assert_eq!(changes, vec![
+            (20, "firing".to_string()),
+            (30, "resolved".to_string()),
+            (60, "firing".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn state_is_dropped_when_rule_no_longer_applies() {
+        let mut rules = vec![rule("cpu", ">", 90.0, None, 0)];
+        let mut engine = AlertEngine::default();
+        assert_eq!(states(&mut engine, &rules, &[(0, 95.0)]).len(), 1);
+
+        rules[0].enabled = false;
+        assert!(states(&mut engine, &rules, &[(10, 95.0)]).is_empty());
+
+        // 重新启用后视为新的告警
+        rules[0].enabled = true;
+        assert_eq!(states(&mut engine, &rules, &[(20, 95.0)]), vec![(20, "firing".to_string())]);
+    }
+
+    #[test]
+    fn disk_and_load_per_core_values() {
+        let sample = AlertSample {
+            load1: 6.0,
+            cores: 4,
+            disks: vec![("/".to_string(), 40.0), ("/data".to_string(), 92.0)],
+            ..AlertSample::default()
+        };
+        let mut disk = rule("disk", ">", 90.0, None, 0);
+        assert_eq!(sample.value_for(&disk), Some(92.0));
+        disk.mount = Some("/".to_string());
+        assert_eq!(sample.value_for(&disk), Some(40.0));
+        disk.mount = Some("/missing".to_string());
+        assert_eq!(sample.value_for(&disk), None);
+
+        assert_eq!(sample.value_for(&rule("load_per_core", ">", 2.0, None, 0)), Some(1.5));
+        assert_eq!(AlertSample::default().value_for(&rule("load_per_core", ">", 2.0, None, 0)), None);
+    }
+}
@@ -0,0 +1,493 @@
+/**
+ * 远程压缩/解压相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use russh::ChannelMsg;
+use tauri::Window;
+use crate::ssh::{exec_command, open_exec_channel, shell_quote};
+
+/// 压缩进度事件名
+const ARCHIVE_PROGRESS_EVENT: &str = "archive-progress";
+
+/// 归档格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveFormat {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// 从格式名解析
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "tar.gz" | "tgz" => Some(ArchiveFormat::TarGz),
+            "zip" => Some(ArchiveFormat::Zip),
+            _ => None,
+        }
+    }
+
+    /// 根据文件扩展名推断格式
+    fn from_path(path: &str) -> Option<Self> {
+        let lower = path.to_lowercase();
+        if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if lower.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// 远程服务器上可用的压缩工具
+#[derive(Debug, Default, Serialize)]
+pub struct ArchiveTools {
+    pub tar: bool,
+    pub gzip: bool,
+    pub zip: bool,
+    pub unzip: bool,
+    pub python3: bool, // 缺少 zip/unzip 时使用 python3 -m zipfile 代替
+}
+
+/// 压缩进度事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveProgressEvent {
+    pub task_id: String,
+    pub processed: u64, // 已处理条目数
+    pub total: u64,     // 条目总数（无法统计时为 0）
+    pub current: String, // 当前处理的条目
+}
+
+/// 获取可用压缩工具参数
+#[derive(Debug, Deserialize)]
+pub struct GetArchiveToolsParams {
+    pub server_id: String,
+}
+
+/// 创建远程压缩包参数
+#[derive(Debug, Deserialize)]
+pub struct CreateRemoteArchiveParams {
+    pub server_id: String,
+    pub task_id: String,           // 任务ID（用于匹配进度事件）
+    pub source_paths: Vec<String>, // 要压缩的文件/目录（绝对路径）
+    pub archive_path: String,      // 压缩包保存路径（绝对路径）
+    pub format: Option<String>,    // "tar.gz" | "zip"，默认根据扩展名推断
+}
+
+/// 创建远程压缩包返回
+#[derive(Debug, Serialize)]
+pub struct CreateRemoteArchiveResult {
+    pub success: bool,
+    pub archive_path: String,
+    pub message: Option<String>,
+}
+
+/// 解压远程压缩包参数
+#[derive(Debug, Deserialize)]
+pub struct ExtractRemoteArchiveParams {
+    pub server_id: String,
+    pub task_id: String,        // 任务ID（用于匹配进度事件）
+    pub archive_path: String,   // 压缩包路径
+    pub target_dir: String,     // 解压目标目录
+    pub format: Option<String>, // "tar.gz" | "zip"，默认根据扩展名推断
+}
+
+/// 解压远程压缩包返回
+#[derive(Debug, Serialize)]
+pub struct ExtractRemoteArchiveResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 获取远程服务器可用的压缩工具
+///
+/// # 命令名称
+/// `get_archive_tools`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+///
+/// # 返回
+/// - `tar` / `gzip` / `zip` / `unzip` / `python3`: 对应工具是否可用
+#[tauri::command]
+pub async fn get_archive_tools(params: GetArchiveToolsParams) -> Result<ArchiveTools, String> {
+    detect_archive_tools(&params.server_id).await
+}
+
+/// 在远程服务器上创建压缩包
+///
+/// # 命令名称
+/// `create_remote_archive`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `task_id`: 任务ID
+/// - `source_paths`: 要压缩的文件/目录
+/// - `archive_path`: 压缩包保存路径
+/// - `format`: 压缩格式（可选）
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `archive_path`: 压缩包路径
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn create_remote_archive(window: Window, params: CreateRemoteArchiveParams) -> Result<CreateRemoteArchiveResult, String> {
+    if params.source_paths.is_empty() {
+        return Err("没有要压缩的文件".to_string());
+    }
+    if !params.archive_path.starts_with('/') || params.source_paths.iter().any(|p| !p.starts_with('/')) {
+        return Err("压缩路径必须为绝对路径".to_string());
+    }
+
+    let format = resolve_format(params.format.as_deref(), &params.archive_path)?;
+    let tools = detect_archive_tools(&params.server_id).await?;
+
+    let command = build_create_command(format, &tools, &params.archive_path, &params.source_paths)?;
+
+    // 先统计条目数，用于计算进度
+    let quoted_sources: Vec<String> = params.source_paths.iter().map(|p| shell_quote(p)).collect();
+    let count_command = format!("find {} 2>/dev/null | wc -l", quoted_sources.join(" "));
+    let total = count_entries(&params.server_id, &count_command).await;
+
+    run_with_progress(&window, &params.server_id, &params.task_id, &command, total)
+        .await
+        .map_err(|e| format!("创建压缩包失败: {}", e))?;
+
+    Ok(CreateRemoteArchiveResult {
+        success: true,
+        archive_path: params.archive_path,
+        message: Some("压缩成功".to_string()),
+    })
+}
+
+/// 在远程服务器上解压压缩包
+///
+/// # 命令名称
+/// `extract_remote_archive`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `task_id`: 任务ID
+/// - `archive_path`: 压缩包路径
+/// - `target_dir`: 解压目标目录
+/// - `format`: 压缩格式（可选）
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn extract_remote_archive(window: Window, params: ExtractRemoteArchiveParams) -> Result<ExtractRemoteArchiveResult, String> {
+    let format = resolve_format(params.format.as_deref(), &params.archive_path)?;
+    let tools = detect_archive_tools(&params.server_id).await?;
+
+    let (command, count_command) = build_extract_commands(format, &tools, &params.archive_path, &params.target_dir)?;
+
+    let total = match count_command {
+        Some(count_command) => count_entries(&params.server_id, &count_command).await,
+        None => 0,
+    };
+
+    run_with_progress(&window, &params.server_id, &params.task_id, &command, total)
+        .await
+        .map_err(|e| format!("解压失败: {}", e))?;
+
+    Ok(ExtractRemoteArchiveResult {
+        success: true,
+        message: Some("解压成功".to_string()),
+    })
+}
+
+/// 构建压缩命令
+///
+/// zip 格式优先使用 `zip`，不可用时使用 `python3 -m zipfile`。`zip -r` 会向已存在的压缩包追加条目，
+/// 因此先删除目标文件，保证结果只包含本次选择的文件。
+fn build_create_command(
+    format: ArchiveFormat,
+    tools: &ArchiveTools,
+    archive_path: &str,
+    source_paths: &[String],
+) -> Result<String, String> {
+    let sources: Vec<(String, String)> = source_paths
+        .iter()
+        .map(|p| split_parent(p))
+        .collect::<Result<_, _>>()?;
+    let archive = shell_quote(archive_path);
+
+    match format {
+        ArchiveFormat::TarGz => {
+            if !tools.tar || !tools.gzip {
+                return Err("服务器缺少 tar 或 gzip，无法创建 tar.gz 压缩包".to_string());
+            }
+            let entries: Vec<String> = sources
+                .iter()
+                .map(|(parent, name)| format!("-C {} {}", shell_quote(parent), shell_quote(&format!("./{}", name))))
+                .collect();
+            Ok(format!("tar -czvf {} {}", archive, entries.join(" ")))
+        }
+        ArchiveFormat::Zip => {
+            if tools.zip {
+                // zip 只能压缩当前目录下的相对路径，按源路径逐个进入其父目录追加到压缩包
+                let mut steps = vec![format!("rm -f {}", archive)];
+                steps.extend(sources.iter().map(|(parent, name)| {
+                    format!(
+                        "(cd {} && zip -r {} {})",
+                        shell_quote(parent),
+                        archive,
+                        shell_quote(&format!("./{}", name))
+                    )
+                }));
+                Ok(steps.join(" && "))
+            } else if tools.python3 {
+                Ok(format!(
+                    "python3 -m zipfile -c {} {}",
+                    archive,
+                    source_paths.iter().map(|p| shell_quote(p)).collect::<Vec<_>>().join(" ")
+                ))
+            } else {
+                Err("服务器缺少 zip 或 python3，无法创建 zip 压缩包".to_string())
+            }
+        }
+    }
+}
+
+/// 构建解压命令，同时返回统计条目数的命令（无法统计时为 `None`）
+fn build_extract_commands(
+    format: ArchiveFormat,
+    tools: &ArchiveTools,
+    archive_path: &str,
+    target_dir: &str,
+) -> Result<(String, Option<String>), String> {
+    let archive = shell_quote(archive_path);
+    let target = shell_quote(target_dir);
+
+    match format {
+        ArchiveFormat::TarGz => {
+            if !tools.tar || !tools.gzip {
+                return Err("服务器缺少 tar 或 gzip，无法解压 tar.gz 压缩包".to_string());
+            }
+            Ok((
+                format!("mkdir -p {} && tar -xzvf {} -C {}", target, archive, target),
+                Some(format!("tar -tzf {} 2>/dev/null | wc -l", archive)),
+            ))
+        }
+        ArchiveFormat::Zip => {
+            if tools.unzip {
+                Ok((
+                    format!("mkdir -p {} && unzip -o {} -d {}", target, archive, target),
+                    Some(format!("unzip -Z1 {} 2>/dev/null | wc -l", archive)),
+                ))
+            } else if tools.python3 {
+                Ok((format!("mkdir -p {} && python3 -m zipfile -e {} {}", target, archive, target), None))
+            } else {
+                Err("服务器缺少 unzip 或 python3，无法解压 zip 压缩包".to_string())
+            }
+        }
+    }
+}
+
+/// 检测远程服务器上可用的压缩工具
+async fn detect_archive_tools(server_id: &str) -> Result<ArchiveTools, String> {
+    let output = exec_command(
+        server_id,
+        "for t in tar gzip zip unzip python3; do command -v $t >/dev/null 2>&1 && echo $t; done",
+    )
+    .await?;
+
+    let mut tools = ArchiveTools::default();
+    for line in output.stdout.lines() {
+        match line.trim() {
+            "tar" => tools.tar = true,
+            "gzip" => tools.gzip = true,
+            "zip" => tools.zip = true,
+            "unzip" => tools.unzip = true,
+            "python3" => tools.python3 = true,
+            _ => {}
+        }
+    }
+
+    Ok(tools)
+}
+
+/// 确定归档格式：优先使用显式指定的格式，否则根据扩展名推断
+fn resolve_format(format: Option<&str>, archive_path: &str) -> Result<ArchiveFormat, String> {
+    match format {
+        Some(name) => ArchiveFormat::from_name(name).ok_or_else(|| format!("不支持的压缩格式: {}", name)),
+        None => ArchiveFormat::from_path(archive_path)
+            .ok_or_else(|| "无法根据文件名识别压缩格式，请指定格式".to_string()),
+    }
+}
+
+/// 拆分路径为（父目录，文件名）
+fn split_parent(path: &str) -> Result<(String, String), String> {
+    let trimmed = path.trim_end_matches('/');
+    match trimmed.rfind('/') {
+        Some(pos) if pos + 1 < trimmed.len() => {
+            let parent = if pos == 0 { "/" } else { &trimmed[..pos] };
+            Ok((parent.to_string(), trimmed[pos + 1..].to_string()))
+        }
+        _ => Err(format!("无效的路径: {}", path)),
+    }
+}
+
+/// 执行统计命令，返回条目数（失败时返回 0）
+async fn count_entries(server_id: &str, command: &str) -> u64 {
+    match exec_command(server_id, command).await {
+        Ok(output) => output.stdout.trim().parse().unwrap_or(0),
+        Err(_) => 0,
+    }
+}
+
+/// 执行压缩/解压命令，每输出一行视为处理了一个条目并推送进度事件
+async fn run_with_progress(window: &Window, server_id: &str, task_id: &str, command: &str, total: u64) -> Result<(), String> {
+    let mut channel = open_exec_channel(server_id, command).await?;
+
+    let mut pending = Vec::new();
+    let mut stderr = Vec::new();
+    let mut processed = 0;
+    let mut exit_code = 0;
+
+    loop {
+        let data = match channel.wait().await {
+            Some(ChannelMsg::Data { data }) => data.to_vec(),
+            Some(ChannelMsg::ExtendedData { data, ext: 1 }) => {
+                // bsdtar 的 -v 输出写到 stderr，同时保留用于错误信息
+                stderr.extend_from_slice(&data);
+                data.to_vec()
+            }
+            Some(ChannelMsg::ExitStatus { exit_status }) => {
+                exit_code = exit_status;
+                continue;
+            }
+            Some(ChannelMsg::Close) | None => break,
+            _ => continue,
+        };
+
+        pending.extend_from_slice(&data);
+        while let Some(pos) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).collect();
+            let current = String::from_utf8_lossy(&line).trim().to_string();
+            if current.is_empty() {
+                continue;
+            }
+
+            processed += 1;
+            let _ = window.emit(ARCHIVE_PROGRESS_EVENT, ArchiveProgressEvent {
+                task_id: task_id.to_string(),
+                processed: if total > 0 { processed.min(total) } else { processed },
+                total,
+                current,
+            });
+        }
+    }
+
+    let _ = channel.close().await;
+
+    if exit_code != 0 {
+        let message = String::from_utf8_lossy(&stderr).trim().to_string();
+        return Err(if message.is_empty() {
+            format!("命令退出码 {}", exit_code)
+        } else {
+            message
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools(tar: bool, zip: bool, unzip: bool, python3: bool) -> ArchiveTools {
+        ArchiveTools { tar, gzip: tar, zip, unzip, python3 }
+    }
+
+    fn paths(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn tar_command_quotes_paths_with_spaces_and_quotes() {
+        let command = build_create_command(
+            ArchiveFormat::TarGz,
+            &tools(true, false, false, false),
+            "/tmp/my backup.tar.gz",
+            &paths(&["/home/user/it's mine", "/etc"]),
+        )
+        .unwrap();
+        assert_eq!(
+            command,
+            "tar -czvf '/tmp/my backup.tar.gz' -C '/home/user' './it'\\''s mine' -C '/' './etc'"
+        );
+    }
+
+    #[test]
+    fn zip_command_removes_existing_archive_first() {
+        let command = build_create_command(
+            ArchiveFormat::Zip,
+            &tools(false, true, false, true),
+            "/tmp/out.zip",
+            &paths(&["/srv/a b", "/srv/c"]),
+        )
+        .unwrap();
+        assert_eq!(
+            command,
+            "rm -f '/tmp/out.zip' && (cd '/srv' && zip -r '/tmp/out.zip' './a b') && (cd '/srv' && zip -r '/tmp/out.zip' './c')"
+        );
+    }
+
+    #[test]
+    fn zip_falls_back_to_python3() {
+        let command = build_create_command(
+            ArchiveFormat::Zip,
+            &tools(true, false, false, true),
+            "/tmp/out.zip",
+            &paths(&["/srv/a b"]),
+        )
+        .unwrap();
+        assert_eq!(command, "python3 -m zipfile -c '/tmp/out.zip' '/srv/a b'");
+
+        let err = build_create_command(ArchiveFormat::Zip, &tools(true, false, false, false), "/tmp/out.zip", &paths(&["/srv/a"]));
+        assert!(err.is_err());
+        let err = build_create_command(ArchiveFormat::TarGz, &tools(false, true, true, true), "/tmp/out.tgz", &paths(&["/srv/a"]));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn extract_commands_pick_available_tool() {
+        let (command, count) = build_extract_commands(
+            ArchiveFormat::TarGz,
+            &tools(true, false, false, false),
+            "/tmp/a b.tgz",
+            "/srv/out",
+        )
+        .unwrap();
+        assert_eq!(command, "mkdir -p '/srv/out' && tar -xzvf '/tmp/a b.tgz' -C '/srv/out'");
+        assert_eq!(count.as_deref(), Some("tar -tzf '/tmp/a b.tgz' 2>/dev/null | wc -l"));
+
+        let (command, count) =
+            build_extract_commands(ArchiveFormat::Zip, &tools(false, false, true, true), "/tmp/a.zip", "/srv/out").unwrap();
+        assert_eq!(command, "mkdir -p '/srv/out' && unzip -o '/tmp/a.zip' -d '/srv/out'");
+        assert!(count.is_some());
+
+        let (command, count) =
+            build_extract_commands(ArchiveFormat::Zip, &tools(false, false, false, true), "/tmp/a.zip", "/srv/out").unwrap();
+        assert_eq!(command, "mkdir -p '/srv/out' && python3 -m zipfile -e '/tmp/a.zip' '/srv/out'");
+        assert_eq!(count, None);
+
+        assert!(build_extract_commands(ArchiveFormat::Zip, &tools(true, true, false, false), "/tmp/a.zip", "/srv").is_err());
+    }
+
+    #[test]
+    fn resolves_format_and_splits_paths() {
+        assert_eq!(resolve_format(None, "/tmp/A.TGZ").unwrap(), ArchiveFormat::TarGz);
+        assert_eq!(resolve_format(Some("zip"), "/tmp/a.tar.gz").unwrap(), ArchiveFormat::Zip);
+        assert!(resolve_format(None, "/tmp/a.rar").is_err());
+        assert!(resolve_format(Some("rar"), "/tmp/a.zip").is_err());
+
+        assert_eq!(split_parent("/etc/nginx/").unwrap(), ("/etc".to_string(), "nginx".to_string()));
+        assert_eq!(split_parent("/etc").unwrap(), ("/".to_string(), "etc".to_string()));
+        assert!(split_parent("/").is_err());
+    }
+}
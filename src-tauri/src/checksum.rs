@@ -0,0 +1,225 @@
+/**
+ * 文件校验和相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+use crate::ssh::{exec_command, shell_quote};
+
+/// 每次远程计算校验和的文件数
+const CHECKSUM_BATCH_SIZE: usize = 100;
+
+/// 校验算法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Sha1,
+    Md5,
+}
+
+impl ChecksumAlgorithm {
+    /// 从算法名解析
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "sha256" => Some(ChecksumAlgorithm::Sha256),
+            "sha1" => Some(ChecksumAlgorithm::Sha1),
+            "md5" => Some(ChecksumAlgorithm::Md5),
+            _ => None,
+        }
+    }
+
+    /// 远程计算命令：优先使用 GNU coreutils，不存在时退回 BSD/macOS 自带工具
+    fn remote_command(&self, quoted_paths: &str) -> String {
+        let (gnu, fallback) = match self {
+            ChecksumAlgorithm::Sha256 => ("sha256sum", "shasum -a 256"),
+            ChecksumAlgorithm::Sha1 => ("sha1sum", "shasum -a 1"),
+            ChecksumAlgorithm::Md5 => ("md5sum", "md5 -r"),
+        };
+        format!(
+            "if command -v {gnu} >/dev/null 2>&1; then {gnu} -- {paths}; else {fallback} {paths}; fi 2>/dev/null",
+            gnu = gnu,
+            fallback = fallback,
+            paths = quoted_paths
+        )
+    }
+}
+
+/// 远程文件校验和
+#[derive(Debug, Serialize)]
+pub struct RemoteChecksum {
+    pub path: String,
+    pub checksum: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 计算远程文件校验和参数
+#[derive(Debug, Deserialize)]
+pub struct ComputeRemoteChecksumsParams {
+    pub server_id: String,
+    pub paths: Vec<String>,
+    pub algorithm: Option<String>, // "sha256"（默认）| "sha1" | "md5"
+}
+
+/// 计算远程文件校验和返回
+#[derive(Debug, Serialize)]
+pub struct ComputeRemoteChecksumsResult {
+    pub algorithm: String,
+    pub checksums: Vec<RemoteChecksum>,
+}
+
+/// 计算远程文件校验和
+///
+/// # 命令名称
+/// `compute_remote_checksums`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `paths`: 远程文件路径数组
+/// - `algorithm`: 校验算法（可选）
+///
+/// # 返回
+/// - `algorithm`: 使用的校验算法
+/// - `checksums`: 各文件的校验和（无法计算时 `error` 有值）
+#[tauri::command]
+pub async fn compute_remote_checksums(params: ComputeRemoteChecksumsParams) -> Result<ComputeRemoteChecksumsResult, String> {
+    let algorithm_name = params.algorithm.unwrap_or_else(|| "sha256".to_string());
+    let algorithm = ChecksumAlgorithm::from_name(&algorithm_name)
+        .ok_or_else(|| format!("不支持的校验算法: {}", algorithm_name))?;
+
+    let sums = remote_checksums(&params.server_id, &params.paths, algorithm).await?;
+
+    let checksums = params
+        .paths
+        .into_iter()
+        .map(|path| match sums.get(&path) {
+            Some(sum) => RemoteChecksum {
+                path,
+                checksum: Some(sum.clone()),
+                error: None,
+            },
+            None => RemoteChecksum {
+                path,
+                checksum: None,
+                error: Some("文件不存在或无法读取".to_string()),
+            },
+        })
+        .collect();
+
+    Ok(ComputeRemoteChecksumsResult {
+        algorithm: algorithm_name.to_lowercase(),
+        checksums,
+    })
+}
+
+/// 计算本地文件的 SHA-256
+pub(crate) async fn local_sha256(path: &Path) -> Result<String, String> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("打开本地文件失败: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let n = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("读取本地文件失败: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 计算单个远程文件的 SHA-256
+pub(crate) async fn remote_sha256(server_id: &str, path: &str) -> Result<String, String> {
+    let sums = remote_checksums(server_id, &[path.to_string()], ChecksumAlgorithm::Sha256).await?;
+    sums.get(path)
+        .cloned()
+        .ok_or_else(|| format!("无法计算远程文件校验和: {}", path))
+}
+
+/// 在远程服务器上批量计算校验和，返回 路径 -> 校验和
+pub(crate) async fn remote_checksums(server_id: &str, paths: &[String], algorithm: ChecksumAlgorithm) -> Result<HashMap<String, String>, String> {
+    let mut sums = HashMap::new();
+
+    for batch in paths.chunks(CHECKSUM_BATCH_SIZE) {
+        let quoted: Vec<String> = batch.iter().map(|p| shell_quote(p)).collect();
+        let output = exec_command(server_id, &algorithm.remote_command(&quoted.join(" "))).await?;
+        sums.extend(parse_checksum_output(&output.stdout));
+    }
+
+    Ok(sums)
+}
+
+/// 解析 `<校验和>  <路径>` 格式的输出（`*` 前缀表示二进制模式）
+///
+/// 路径包含反斜杠或换行时，coreutils 和 shasum 会在行首加 `\` 并转义路径
+/// （`\\`、`\n`、`\r`），这里还原为原始路径。
+fn parse_checksum_output(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (escaped, line) = match line.strip_prefix('\\') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let (sum, rest) = line.split_once(' ')?;
+            let path = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*')).unwrap_or(rest);
+            if sum.is_empty() || path.is_empty() {
+                return None;
+            }
+            let path = if escaped { unescape_path(path)? } else { path.to_string() };
+            Some((path, sum.to_lowercase()))
+        })
+        .collect()
+}
+
+/// 还原转义的路径，遇到无法识别的转义序列时返回 None
+fn unescape_path(path: &str) -> Option<String> {
+    let mut result = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => result.push('\\'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_binary_mode_lines() {
+        let sums = parse_checksum_output("ABC123  /srv/a b.txt\ndef456 */srv/bin.dat\n\n");
+        assert_eq!(sums.len(), 2);
+        assert_eq!(sums["/srv/a b.txt"], "abc123");
+        assert_eq!(sums["/srv/bin.dat"], "def456");
+    }
+
+    #[test]
+    fn unescapes_backslash_and_newline_paths() {
+        let sums = parse_checksum_output("\\abc  /tmp/a\\\\b\\nc\n\\def  /tmp/cr\\r\n");
+        assert_eq!(sums["/tmp/a\\b\nc"], "abc");
+        assert_eq!(sums["/tmp/cr\r"], "def");
+    }
+
+    #[test]
+    fn skips_invalid_escapes() {
+        assert!(parse_checksum_output("\\abc  /tmp/bad\\x\n").is_empty());
+    }
+}
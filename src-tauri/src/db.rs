@@ -2,7 +2,7 @@
  * SQLite 数据库管理模块
  */
 
-use rusqlite::{Connection, Result as SqliteResult};
+use rusqlite::{Connection, OptionalExtension, Result as SqliteResult};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
@@ -21,18 +21,21 @@ pub fn get_db() -> Arc<Mutex<Connection>> {
     DB.clone()
 }
 
-/// 初始化数据库
-fn init_database() -> SqliteResult<Connection> {
-    // 获取应用数据目录
+/// 获取应用数据目录（不存在时自动创建）
+pub fn data_dir() -> PathBuf {
     let data_dir = dirs::data_dir()
         .or_else(|| dirs::home_dir().map(|h| h.join(".myssh")))
         .unwrap_or_else(|| PathBuf::from("."));
     
     // 确保目录存在
     std::fs::create_dir_all(&data_dir).ok();
-    
+    data_dir
+}
+
+/// 初始化数据库
+fn init_database() -> SqliteResult<Connection> {
     // 数据库文件路径
-    let db_path = data_dir.join("myssh.db");
+    let db_path = data_dir().join("myssh.db");
 
     
     // 打开或创建数据库
@@ -83,8 +86,10 @@ pub fn migrate_database(conn: &Connection) -> SqliteResult<()> {
         .unwrap_or(0);
     
     // 如果版本为 0，创建版本表
+    // 每个版本的迁移在单独的事务中执行，中途失败不会留下只完成一半的版本
     if version == 0 {
-        conn.execute(
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
             "CREATE TABLE IF NOT EXISTS schema_version (
                 version INTEGER PRIMARY KEY,
                 applied_at TEXT NOT NULL DEFAULT (datetime('now'))
@@ -92,15 +97,184 @@ pub fn migrate_database(conn: &Connection) -> SqliteResult<()> {
             [],
         )?;
         
-        conn.execute(
+        tx.execute(
             "INSERT INTO schema_version (version) VALUES (1)",
             [],
         )?;
+        tx.commit()?;
     }
     
     // 执行迁移
-    // 这里可以添加更多的迁移逻辑
+    // 版本 2：监控指标时序表（resolution 为聚合粒度，单位秒）
+    if version < 2 {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS metric_samples (
+                server_id TEXT NOT NULL,
+                resolution INTEGER NOT NULL,
+                ts INTEGER NOT NULL,
+                cpu REAL NOT NULL,
+                memory REAL NOT NULL,
+                load1 REAL NOT NULL,
+                download REAL NOT NULL,
+                upload REAL NOT NULL,
+                disk REAL NOT NULL,
+                samples INTEGER NOT NULL,
+                PRIMARY KEY (server_id, resolution, ts)
+            )",
+            [],
+        )?;
+
+        tx.execute(
+            "INSERT INTO schema_version (version) VALUES (2)",
+            [],
+        )?;
+        tx.commit()?;
+    }
+
+    // 版本 3：服务器分组、告警规则和告警记录
+    if version < 3 {
+        let tx = conn.unchecked_transaction()?;
+        // 旧版本可能已添加过该列但未记录版本号
+        if !column_exists(&tx, "servers", "group_name")? {
+            tx.execute(
+                "ALTER TABLE servers ADD COLUMN group_name TEXT",
+                [],
+            )?;
+        }
+
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS alert_rules (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                server_id TEXT,
+                group_name TEXT,
+                metric TEXT NOT NULL,
+                mount TEXT,
+                operator TEXT NOT NULL,
+                threshold REAL NOT NULL,
+                clear_threshold REAL,
+                duration_secs INTEGER NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS alert_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                rule_id TEXT NOT NULL,
+                rule_name TEXT NOT NULL,
+                server_id TEXT NOT NULL,
+                state TEXT NOT NULL,
+                value REAL NOT NULL,
+                threshold REAL NOT NULL,
+                message TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        tx.execute(
+            "CREATE INDEX IF NOT EXISTS idx_alert_events_server ON alert_events(server_id, created_at)",
+            [],
+        )?;
+
+        tx.execute(
+            "INSERT INTO schema_version (version) VALUES (3)",
+            [],
+        )?;
+        tx.commit()?;
+    }
+
+    // 版本 4：应用设置（键值对）
+    if version < 4 {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS app_settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+
+        tx.execute(
+            "INSERT INTO schema_version (version) VALUES (4)",
+            [],
+        )?;
+        tx.commit()?;
+    }
     
     Ok(())
 }
 
+/// 检查表中是否存在指定列
+fn column_exists(conn: &Connection, table: &str, column: &str) -> SqliteResult<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    for name in names {
+        if name? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 读取应用设置（不存在时返回 None）
+pub fn get_setting(conn: &Connection, key: &str) -> SqliteResult<Option<String>> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// 保存应用设置
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = datetime('now')",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+/// 删除应用设置
+pub fn delete_setting(conn: &Connection, key: &str) -> SqliteResult<()> {
+    conn.execute("DELETE FROM app_settings WHERE key = ?1", [key])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema_version(conn: &Connection) -> i32 {
+        conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn migrates_fresh_database_and_is_repeatable() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        migrate_database(&conn).unwrap();
+        migrate_database(&conn).unwrap();
+
+        assert_eq!(schema_version(&conn), 4);
+        assert!(column_exists(&conn, "servers", "group_name").unwrap());
+    }
+
+    #[test]
+    fn tolerates_group_column_added_without_version_record() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        migrate_database(&conn).unwrap();
+        conn.execute("DELETE FROM schema_version WHERE version >= 3", []).unwrap();
+
+        migrate_database(&conn).unwrap();
+        assert_eq!(schema_version(&conn), 4);
+    }
+}
@@ -0,0 +1,433 @@
+/**
+ * 远程磁盘占用分析相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::file::parent_remote_path;
+use crate::ssh::{exec_command, get_disk_usage_cache, shell_quote};
+use crate::util::unix_now;
+
+/// 默认扫描深度
+const DEFAULT_MAX_DEPTH: u32 = 3;
+/// 默认超时时间（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+/// 远程 timeout 命令超时退出码
+const TIMEOUT_EXIT_CODE: u32 = 124;
+
+/// 目录占用节点
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsageNode {
+    pub path: String,
+    pub name: String,
+    pub size: u64,                   // 占用空间（字节）
+    pub children: Vec<DiskUsageNode>, // 子目录，按占用从大到小排序
+    pub truncated: bool,             // 是否处于扫描深度上限（子目录未展开）
+}
+
+impl DiskUsageNode {
+    /// 在树中查找指定路径的节点
+    fn find(&self, path: &str) -> Option<&DiskUsageNode> {
+        if self.path == path {
+            return Some(self);
+        }
+        self.children
+            .iter()
+            .filter(|child| is_same_or_descendant(path, &child.path))
+            .find_map(|child| child.find(path))
+    }
+
+    /// 复制节点，只保留 `depth` 层子目录
+    fn limited(&self, depth: u32) -> DiskUsageNode {
+        DiskUsageNode {
+            path: self.path.clone(),
+            name: self.name.clone(),
+            size: self.size,
+            children: if depth == 0 {
+                Vec::new()
+            } else {
+                self.children.iter().map(|child| child.limited(depth - 1)).collect()
+            },
+            truncated: self.truncated || depth == 0,
+        }
+    }
+}
+
+/// 一次扫描的结果
+#[derive(Debug, Clone)]
+struct DiskUsageScan {
+    tree: DiskUsageNode,
+    max_depth: u32,
+    one_file_system: bool,
+    scanned_at: u64,
+    partial: bool,
+}
+
+/// 单个连接的磁盘占用分析缓存
+#[derive(Debug, Default)]
+pub struct DiskUsageCache {
+    scans: Vec<DiskUsageScan>,
+}
+
+impl DiskUsageCache {
+    /// 查找已按至少 `max_depth` 层扫描过的目录，返回（节点，扫描时间，是否有目录无法读取）
+    ///
+    /// 返回的节点裁剪为 `max_depth` 层，与重新扫描的结果一致。
+    fn lookup(&self, path: &str, one_file_system: bool, max_depth: u32) -> Option<(DiskUsageNode, u64, bool)> {
+        self.scans
+            .iter()
+            .rev()
+            .filter(|scan| scan.one_file_system == one_file_system)
+            .filter(|scan| scan.depth_below(path).map_or(false, |depth| depth >= max_depth))
+            .find_map(|scan| scan.tree.find(path).map(|node| (node.limited(max_depth), scan.scanned_at, scan.partial)))
+    }
+
+    /// 保存新的扫描结果，被新结果完全覆盖的旧扫描一并移除
+    fn insert(&mut self, scan: DiskUsageScan) {
+        self.scans.retain(|old| {
+            old.one_file_system != scan.one_file_system
+                || scan.depth_below(&old.tree.path).map_or(true, |depth| depth < old.max_depth)
+        });
+        self.scans.push(scan);
+    }
+}
+
+impl DiskUsageScan {
+    /// 本次扫描在 `path` 之下展开的层数，`path` 不在扫描范围内时返回 `None`
+    fn depth_below(&self, path: &str) -> Option<u32> {
+        let root = &self.tree.path;
+        if !is_same_or_descendant(path, root) {
+            return None;
+        }
+        let rest = if root == "/" { path } else { &path[root.len()..] };
+        let depth = rest.split('/').filter(|part| !part.is_empty()).count() as u32;
+        self.max_depth.checked_sub(depth)
+    }
+}
+
+/// 分析磁盘占用参数
+#[derive(Debug, Deserialize)]
+pub struct AnalyzeDiskUsageParams {
+    pub server_id: String,
+    pub path: String,                  // 要分析的目录
+    pub max_depth: Option<u32>,        // 扫描深度（默认 3）
+    pub one_file_system: Option<bool>, // 是否只统计同一文件系统（默认是）
+    pub timeout_secs: Option<u64>,     // 超时时间（默认 60 秒）
+    pub refresh: Option<bool>,         // 是否忽略缓存重新扫描
+}
+
+/// 分析磁盘占用返回
+#[derive(Debug, Serialize)]
+pub struct AnalyzeDiskUsageResult {
+    pub tree: DiskUsageNode,
+    pub cached: bool,      // 是否来自缓存
+    pub scanned_at: u64,   // 扫描时间（Unix 时间戳，秒）
+    pub partial: bool,     // 是否有目录因权限等原因未能统计
+}
+
+/// 分析远程目录的磁盘占用
+///
+/// 在远程服务器上执行 `du`，返回按占用排序的目录树。结果缓存在当前连接上，
+/// 已扫描范围足以覆盖请求的目录和深度时直接从缓存返回，断开连接后缓存随之释放。
+///
+/// # 命令名称
+/// `analyze_disk_usage`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `path`: 要分析的目录
+/// - `max_depth`: 扫描深度（可选）
+/// - `one_file_system`: 是否只统计同一文件系统（可选）
+/// - `timeout_secs`: 超时时间，单位秒（可选）
+/// - `refresh`: 是否重新扫描（可选）
+///
+/// # 返回
+/// - `tree`: 目录占用树
+/// - `cached`: 是否来自缓存
+/// - `scanned_at`: 扫描时间
+/// - `partial`: 是否有目录未能统计
+#[tauri::command]
+pub async fn analyze_disk_usage(params: AnalyzeDiskUsageParams) -> Result<AnalyzeDiskUsageResult, String> {
+    let path = normalize_path(&params.path);
+    if path.is_empty() {
+        return Err("分析路径不能为空".to_string());
+    }
+
+    let one_file_system = params.one_file_system.unwrap_or(true);
+    let max_depth = params.max_depth.unwrap_or(DEFAULT_MAX_DEPTH).max(1);
+    let cache = get_disk_usage_cache(&params.server_id)?;
+
+    if !params.refresh.unwrap_or(false) {
+        if let Some((tree, scanned_at, partial)) = cache.lock().unwrap().lookup(&path, one_file_system, max_depth) {
+            return Ok(AnalyzeDiskUsageResult {
+                tree,
+                cached: true,
+                scanned_at,
+                partial,
+            });
+        }
+    }
+
+    let timeout_secs = params.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS).max(1);
+    let command = build_du_command(&path, max_depth, one_file_system, timeout_secs);
+
+    // 远程 timeout 负责终止 du，本地超时用于兜底（远程没有 timeout 命令或连接无响应）
+    let output = tokio::time::timeout(
+        Duration::from_secs(timeout_secs + 5),
+        exec_command(&params.server_id, &command),
+    )
+    .await
+    .map_err(|_| format!("扫描超时（{} 秒），请减小扫描深度或选择更小的目录", timeout_secs))??;
+
+    if output.exit_code == TIMEOUT_EXIT_CODE {
+        return Err(format!("扫描超时（{} 秒），请减小扫描深度或选择更小的目录", timeout_secs));
+    }
+
+    let tree = build_usage_tree(&path, &output.stdout, max_depth).ok_or_else(|| {
+        let message = output.stderr.lines().next().unwrap_or("").trim().to_string();
+        if message.is_empty() {
+            format!("无法分析目录: {}", path)
+        } else {
+            format!("无法分析目录: {}", message)
+        }
+    })?;
+
+    let scanned_at = unix_now() as u64;
+    // du 在部分目录无法读取时返回非零退出码，但已统计的结果仍然有效
+    let partial = output.exit_code != 0;
+
+    cache.lock().unwrap().insert(DiskUsageScan {
+        tree: tree.clone(),
+        max_depth,
+        one_file_system,
+        scanned_at,
+        partial,
+    });
+
+    Ok(AnalyzeDiskUsageResult {
+        tree,
+        cached: false,
+        scanned_at,
+        partial,
+    })
+}
+
+/// 构建 du 命令（`-d`、`-x`、`-k` 在 GNU 和 BSD 的 du 中均可用）
+fn build_du_command(path: &str, max_depth: u32, one_file_system: bool, timeout_secs: u64) -> String {
+    let du = format!(
+        "du -k -d {}{} {}",
+        max_depth,
+        if one_file_system { " -x" } else { "" },
+        shell_quote(path)
+    );
+    format!(
+        "if command -v timeout >/dev/null 2>&1; then timeout {secs} {du}; else {du}; fi",
+        secs = timeout_secs,
+        du = du
+    )
+}
+
+/// 去掉路径末尾的 `/`（根目录除外）
+fn normalize_path(path: &str) -> String {
+    let path = path.trim();
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() && path.starts_with('/') {
+        "/".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// 判断 `path` 是否为 `ancestor` 本身或其子路径
+fn is_same_or_descendant(path: &str, ancestor: &str) -> bool {
+    if path == ancestor {
+        return true;
+    }
+    if ancestor == "/" {
+        return path.starts_with('/');
+    }
+    path.strip_prefix(ancestor).map_or(false, |rest| rest.starts_with('/'))
+}
+
+/// 解析 `du -k` 输出（`<KB>\t<路径>`）并构建以 `root` 为根的目录树
+fn build_usage_tree(root: &str, output: &str, max_depth: u32) -> Option<DiskUsageNode> {
+    let root = normalize_path(root);
+    let mut sizes: HashMap<String, u64> = HashMap::new();
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+
+    for line in output.lines() {
+        let (size, path) = match line.split_once('\t') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let size: u64 = match size.trim().parse() {
+            Ok(size) => size,
+            Err(_) => continue,
+        };
+        let path = normalize_path(path);
+        if !is_same_or_descendant(&path, &root) {
+            continue;
+        }
+
+        if path != root {
+            children.entry(parent_remote_path(&path)).or_default().push(path.clone());
+        }
+        sizes.insert(path, size * 1024);
+    }
+
+    let size = *sizes.get(&root)?;
+    Some(build_node(&root, size, 0, max_depth, &sizes, &children))
+}
+
+/// 递归构建目录节点
+fn build_node(
+    path: &str,
+    size: u64,
+    depth: u32,
+    max_depth: u32,
+    sizes: &HashMap<String, u64>,
+    children: &HashMap<String, Vec<String>>,
+) -> DiskUsageNode {
+    let mut nodes: Vec<DiskUsageNode> = children
+        .get(path)
+        .map(|paths| {
+            paths
+                .iter()
+                .filter_map(|child| {
+                    let size = *sizes.get(child)?;
+                    Some(build_node(child, size, depth + 1, max_depth, sizes, children))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    nodes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    let name = if path == "/" {
+        "/".to_string()
+    } else {
+        path.rsplit('/').next().unwrap_or(path).to_string()
+    };
+
+    DiskUsageNode {
+        path: path.to_string(),
+        name,
+        size,
+        children: nodes,
+        truncated: depth >= max_depth,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `du -k -d 2 /srv` 输出
+    const DU_OUTPUT: &str = "\
+12\t/srv/www/static files
+40\t/srv/www/app
+60\t/srv/www
+8\t/srv/backup 2024/jan
+100\t/srv/backup 2024
+4\t/srv/empty
+164\t/srv
+";
+
+    fn names(node: &DiskUsageNode) -> Vec<&str> {
+        node.children.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    fn scan(output: &str, root: &str, max_depth: u32, one_file_system: bool, scanned_at: u64) -> DiskUsageScan {
+        DiskUsageScan {
+            tree: build_usage_tree(root, output, max_depth).unwrap(),
+            max_depth,
+            one_file_system,
+            scanned_at,
+            partial: false,
+        }
+    }
+
+    #[test]
+    fn nests_du_rows_into_sorted_tree() {
+        let tree = build_usage_tree("/srv/", DU_OUTPUT, 2).unwrap();
+        assert_eq!((tree.path.as_str(), tree.name.as_str(), tree.size), ("/srv", "srv", 164 * 1024));
+        assert_eq!(names(&tree), vec!["backup 2024", "www", "empty"]);
+        assert!(!tree.truncated);
+
+        let backup = &tree.children[0];
+        assert_eq!(backup.path, "/srv/backup 2024");
+        assert_eq!(names(backup), vec!["jan"]);
+        assert!(backup.children[0].truncated);
+
+        let www = &tree.children[1];
+        assert_eq!(names(www), vec!["app", "static files"]);
+        assert_eq!(www.children[1].path, "/srv/www/static files");
+        assert!(!tree.children[2].truncated);
+    }
+
+    #[test]
+    fn ignores_rows_outside_root_and_requires_root_row() {
+        let tree = build_usage_tree("/srv/www", "du: cannot read directory\n60\t/srv/www\n12\t/srv/wwwdata\nbad\t/srv/www/x\n", 1).unwrap();
+        assert!(tree.children.is_empty());
+        assert!(build_usage_tree("/srv", "12\t/srv/www\n", 1).is_none());
+
+        let root = build_usage_tree("/", "20\t/var\n30\t/\n", 1).unwrap();
+        assert_eq!((root.name.as_str(), names(&root)), ("/", vec!["var"]));
+    }
+
+    #[test]
+    fn cache_serves_fully_scanned_subdirectories() {
+        let mut cache = DiskUsageCache::default();
+        cache.insert(scan(DU_OUTPUT, "/srv", 2, true, 100));
+
+        let (node, scanned_at, partial) = cache.lookup("/srv/www", true, 1).unwrap();
+        assert_eq!((node.size, scanned_at, partial), (60 * 1024, 100, false));
+        // 处于深度上限的目录没有展开子目录，需要重新扫描
+        assert!(cache.lookup("/srv/backup 2024/jan", true, 1).is_none());
+        assert!(cache.lookup("/srv/missing", true, 1).is_none());
+        assert!(cache.lookup("/srv", false, 1).is_none());
+    }
+
+    #[test]
+    fn cache_requires_enough_remaining_depth() {
+        let mut cache = DiskUsageCache::default();
+        cache.insert(scan(DU_OUTPUT, "/srv", 2, true, 100));
+
+        // 扫描深度 2 减去 /srv/www 所处深度 1，只剩 1 层
+        assert!(cache.lookup("/srv/www", true, 2).is_none());
+        assert!(cache.lookup("/srv", true, 3).is_none());
+
+        // 请求较浅的深度时裁剪缓存的树
+        let (node, _, _) = cache.lookup("/srv", true, 1).unwrap();
+        assert_eq!(names(&node), vec!["backup 2024", "www", "empty"]);
+        assert!(node.children.iter().all(|child| child.children.is_empty() && child.truncated));
+
+        let (node, _, _) = cache.lookup("/srv", true, 2).unwrap();
+        assert_eq!(names(&node.children[1]), vec!["app", "static files"]);
+    }
+
+    #[test]
+    fn cache_insert_replaces_covered_scans() {
+        let mut cache = DiskUsageCache::default();
+        cache.insert(scan("60\t/srv/www\n40\t/srv/www/app\n", "/srv/www", 2, true, 100));
+        cache.insert(scan("60\t/srv/www\n", "/srv/www", 1, false, 150));
+        cache.insert(scan(DU_OUTPUT, "/srv", 2, true, 200));
+
+        // /srv/www 的 2 层扫描未被 /srv 的 2 层扫描完全覆盖，保留
+        assert_eq!(cache.scans.len(), 3);
+        assert_eq!(cache.lookup("/srv/www", true, 1).unwrap().1, 200);
+        assert_eq!(cache.lookup("/srv/www", true, 2).unwrap().1, 100);
+        assert_eq!(cache.lookup("/srv/www", false, 1).unwrap().1, 150);
+
+        // 更深的扫描覆盖旧扫描后将其移除
+        cache.insert(scan(DU_OUTPUT, "/srv", 3, true, 250));
+        assert_eq!(cache.scans.len(), 2);
+        assert_eq!(cache.lookup("/srv/www", true, 2).unwrap().1, 250);
+
+        // 子目录的新扫描不会移除覆盖它的旧扫描，但查找时优先使用
+        cache.insert(scan("70\t/srv/www\n", "/srv/www", 1, true, 300));
+        assert_eq!(cache.scans.len(), 3);
+        assert_eq!(cache.lookup("/srv/www", true, 1).unwrap().1, 300);
+        assert_eq!(cache.lookup("/srv", true, 1).unwrap().1, 250);
+    }
+}
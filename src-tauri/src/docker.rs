@@ -0,0 +1,709 @@
+/**
+ * Docker 容器管理相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use russh::ChannelMsg;
+use tauri::Window;
+use crate::log_tail::take_complete_lines;
+use crate::ssh::{exec_command, shell_quote, RemoteProcess};
+use crate::util::TaskRegistry;
+
+/// 默认初始读取的日志行数
+const DEFAULT_LOG_TAIL: u32 = 200;
+
+/// 容器操作
+const CONTAINER_ACTIONS: &[&str] = &["start", "stop", "restart", "pause", "unpause", "remove"];
+
+/// 容器日志事件名
+const DOCKER_LOG_LINES_EVENT: &str = "docker-log-lines";
+/// 容器日志结束事件名
+const DOCKER_LOG_STOPPED_EVENT: &str = "docker-log-stopped";
+
+lazy_static::lazy_static! {
+    /// 正在运行的容器日志任务（logs_id -> 停止信号）
+    static ref LOG_TASKS: TaskRegistry = TaskRegistry::new();
+}
+
+/// `docker ps --format '{{json .}}'` 的单行输出
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct DockerPsRow {
+    #[serde(rename = "ID")]
+    id: String,
+    names: String,
+    image: String,
+    command: String,
+    created_at: String,
+    running_for: String,
+    ports: String,
+    state: String,
+    status: String,
+    networks: String,
+}
+
+/// `docker images --format '{{json .}}'` 的单行输出
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct DockerImageRow {
+    #[serde(rename = "ID")]
+    id: String,
+    repository: String,
+    tag: String,
+    created_at: String,
+    created_since: String,
+    size: String,
+}
+
+/// `docker stats --no-stream --format '{{json .}}'` 的单行输出
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct DockerStatsRow {
+    #[serde(rename = "ID")]
+    id: String,
+    name: String,
+    #[serde(rename = "CPUPerc")]
+    cpu_perc: String,
+    mem_usage: String,
+    mem_perc: String,
+    #[serde(rename = "NetIO")]
+    net_io: String,
+    #[serde(rename = "BlockIO")]
+    block_io: String,
+    #[serde(rename = "PIDs")]
+    pids: String,
+}
+
+/// 容器信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub command: String,
+    pub created_at: String,
+    pub running_for: String, // 创建至今的时长，如 "2 hours ago"
+    pub state: String,       // 状态，如 "running"、"exited"、"paused"
+    pub status: String,      // 状态描述，如 "Up 2 hours (healthy)"
+    pub ports: String,
+    pub networks: Vec<String>,
+}
+
+/// 镜像信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageInfo {
+    pub id: String,
+    pub repository: String,
+    pub tag: String,
+    pub created_at: String,
+    pub created_since: String,
+    pub size: u64, // 镜像大小（字节）
+}
+
+/// 容器资源占用
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerStats {
+    pub id: String,
+    pub name: String,
+    pub cpu: f64,          // CPU 使用率（%，多核时可能超过 100）
+    pub memory_used: u64,  // 内存占用（字节）
+    pub memory_limit: u64, // 内存上限（字节）
+    pub memory: f64,       // 内存使用率（%）
+    pub net_rx: u64,       // 累计接收（字节）
+    pub net_tx: u64,       // 累计发送（字节）
+    pub block_read: u64,   // 累计磁盘读取（字节）
+    pub block_write: u64,  // 累计磁盘写入（字节）
+    pub pids: u64,
+}
+
+/// 列出容器参数
+#[derive(Debug, Deserialize)]
+pub struct ListContainersParams {
+    pub server_id: String,
+    pub all: Option<bool>, // 是否包含已停止的容器（默认是）
+}
+
+/// 列出容器返回
+#[derive(Debug, Serialize)]
+pub struct ListContainersResult {
+    pub containers: Vec<ContainerInfo>,
+}
+
+/// 容器详情参数
+#[derive(Debug, Deserialize)]
+pub struct InspectContainerParams {
+    pub server_id: String,
+    pub container: String, // 容器 ID 或名称
+}
+
+/// 容器详情返回
+#[derive(Debug, Serialize)]
+pub struct InspectContainerResult {
+    pub details: serde_json::Value, // `docker inspect` 的原始 JSON
+}
+
+/// 列出镜像参数
+#[derive(Debug, Deserialize)]
+pub struct ListImagesParams {
+    pub server_id: String,
+}
+
+/// 列出镜像返回
+#[derive(Debug, Serialize)]
+pub struct ListImagesResult {
+    pub images: Vec<ImageInfo>,
+}
+
+/// 容器操作参数
+#[derive(Debug, Deserialize)]
+pub struct ContainerActionParams {
+    pub server_id: String,
+    pub container: String,
+    pub action: String,       // start | stop | restart | pause | unpause | remove
+    pub force: Option<bool>,  // 删除时是否强制删除运行中的容器
+}
+
+/// 容器操作返回
+#[derive(Debug, Serialize)]
+pub struct ContainerActionResult {
+    pub success: bool,
+    pub container: String,
+    pub action: String,
+    pub message: Option<String>,
+}
+
+/// 容器资源占用参数
+#[derive(Debug, Deserialize)]
+pub struct GetContainerStatsParams {
+    pub server_id: String,
+}
+
+/// 容器资源占用返回
+#[derive(Debug, Serialize)]
+pub struct GetContainerStatsResult {
+    pub stats: Vec<ContainerStats>,
+}
+
+/// 开始读取容器日志参数
+#[derive(Debug, Deserialize)]
+pub struct StartContainerLogsParams {
+    pub server_id: String,
+    pub logs_id: String,           // 日志任务ID（由前端生成，用于匹配事件）
+    pub container: String,
+    pub tail: Option<u32>,         // 先输出最后 N 行（默认 200）
+    pub timestamps: Option<bool>,  // 是否附带时间戳
+}
+
+/// 开始读取容器日志返回
+#[derive(Debug, Serialize)]
+pub struct StartContainerLogsResult {
+    pub success: bool,
+    pub logs_id: String,
+}
+
+/// 停止读取容器日志参数
+#[derive(Debug, Deserialize)]
+pub struct StopContainerLogsParams {
+    pub logs_id: String,
+}
+
+/// 停止读取容器日志返回
+#[derive(Debug, Serialize)]
+pub struct StopContainerLogsResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 容器日志事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct DockerLogLinesEvent {
+    pub logs_id: String,
+    pub stream: String, // "stdout" | "stderr"
+    pub lines: Vec<String>,
+}
+
+/// 容器日志结束事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct DockerLogStoppedEvent {
+    pub logs_id: String,
+    pub stopped: bool, // 是否由用户停止（否则为容器退出或连接断开）
+}
+
+/// 列出容器
+///
+/// # 命令名称
+/// `list_containers`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `all`: 是否包含已停止的容器（可选）
+///
+/// # 返回
+/// - `containers`: 容器列表
+#[tauri::command]
+pub async fn list_containers(params: ListContainersParams) -> Result<ListContainersResult, String> {
+    let command = format!(
+        "docker ps {}--no-trunc --format '{{{{json .}}}}'",
+        if params.all.unwrap_or(true) { "-a " } else { "" }
+    );
+    let rows: Vec<DockerPsRow> = run_json_lines(&params.server_id, &command).await?;
+
+    Ok(ListContainersResult {
+        containers: rows.into_iter().map(container_from_row).collect(),
+    })
+}
+
+/// 获取容器详情
+///
+/// # 命令名称
+/// `inspect_container`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `container`: 容器 ID 或名称
+///
+/// # 返回
+/// - `details`: `docker inspect` 返回的容器详情
+#[tauri::command]
+pub async fn inspect_container(params: InspectContainerParams) -> Result<InspectContainerResult, String> {
+    validate_container(&params.container)?;
+
+    let command = format!("docker inspect --type container {}", shell_quote(&params.container));
+    let output = exec_command(&params.server_id, &command).await?;
+    if output.exit_code != 0 {
+        return Err(docker_error(&output.stderr));
+    }
+
+    let value: serde_json::Value = serde_json::from_str(&output.stdout)
+        .map_err(|e| format!("解析容器详情失败: {}", e))?;
+    let details = match value {
+        serde_json::Value::Array(mut items) if !items.is_empty() => items.remove(0),
+        _ => return Err(format!("未找到容器: {}", params.container)),
+    };
+
+    Ok(InspectContainerResult { details })
+}
+
+/// 列出镜像
+///
+/// # 命令名称
+/// `list_images`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+///
+/// # 返回
+/// - `images`: 镜像列表
+#[tauri::command]
+pub async fn list_images(params: ListImagesParams) -> Result<ListImagesResult, String> {
+    let rows: Vec<DockerImageRow> =
+        run_json_lines(&params.server_id, "docker images --no-trunc --format '{{json .}}'").await?;
+
+    let images = rows
+        .into_iter()
+        .map(|row| ImageInfo {
+            id: row.id,
+            repository: row.repository,
+            tag: row.tag,
+            created_at: row.created_at,
+            created_since: row.created_since,
+            size: parse_size(&row.size),
+        })
+        .collect();
+
+    Ok(ListImagesResult { images })
+}
+
+/// 启动、停止、重启、暂停、恢复或删除容器
+///
+/// # 命令名称
+/// `container_action`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `container`: 容器 ID 或名称
+/// - `action`: 操作类型
+/// - `force`: 删除时是否强制（可选）
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `container` / `action`: 容器和操作
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn container_action(params: ContainerActionParams) -> Result<ContainerActionResult, String> {
+    validate_container(&params.container)?;
+    if !CONTAINER_ACTIONS.contains(&params.action.as_str()) {
+        return Err(format!("不支持的操作: {}", params.action));
+    }
+
+    let subcommand = match params.action.as_str() {
+        "remove" if params.force.unwrap_or(false) => "rm -f",
+        "remove" => "rm",
+        action => action,
+    };
+    let command = format!("docker {} {}", subcommand, shell_quote(&params.container));
+    let output = exec_command(&params.server_id, &command).await?;
+
+    let success = output.exit_code == 0;
+    Ok(ContainerActionResult {
+        success,
+        container: params.container,
+        action: params.action,
+        message: if success { None } else { Some(docker_error(&output.stderr)) },
+    })
+}
+
+/// 获取运行中容器的资源占用
+///
+/// # 命令名称
+/// `get_container_stats`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+///
+/// # 返回
+/// - `stats`: 各容器的 CPU、内存、网络、磁盘 IO 和进程数
+#[tauri::command]
+pub async fn get_container_stats(params: GetContainerStatsParams) -> Result<GetContainerStatsResult, String> {
+    let rows: Vec<DockerStatsRow> = run_json_lines(
+        &params.server_id,
+        "docker stats --no-stream --no-trunc --format '{{json .}}'",
+    )
+    .await?;
+
+    Ok(GetContainerStatsResult {
+        stats: rows.into_iter().map(stats_from_row).collect(),
+    })
+}
+
+/// 开始读取容器日志
+///
+/// 在远程服务器上执行 `docker logs -f`，新行通过 `docker-log-lines` 事件推送（区分 stdout 和 stderr），
+/// 结束时推送 `docker-log-stopped` 事件。
+///
+/// # 命令名称
+/// `start_container_logs`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `logs_id`: 日志任务ID
+/// - `container`: 容器 ID 或名称
+/// - `tail`: 初始读取的末尾行数（可选）
+/// - `timestamps`: 是否附带时间戳（可选）
+///
+/// # 返回
+/// - `success`: 是否成功启动
+/// - `logs_id`: 日志任务ID
+#[tauri::command]
+pub async fn start_container_logs(window: Window, params: StartContainerLogsParams) -> Result<StartContainerLogsResult, String> {
+    validate_container(&params.container)?;
+
+    let (generation, mut stop_rx) = LOG_TASKS
+        .register(&params.logs_id)
+        .ok_or_else(|| "日志任务已存在".to_string())?;
+
+    let command = format!(
+        "docker logs -f --tail {}{} {}",
+        params.tail.unwrap_or(DEFAULT_LOG_TAIL),
+        if params.timestamps.unwrap_or(false) { " -t" } else { "" },
+        shell_quote(&params.container)
+    );
+    let mut process = match RemoteProcess::spawn(&params.server_id, &command).await {
+        Ok(process) => process,
+        Err(e) => {
+            LOG_TASKS.finish(&params.logs_id, generation);
+            return Err(e);
+        }
+    };
+
+    let logs_id = params.logs_id.clone();
+    tokio::spawn(async move {
+        let mut stdout: Vec<u8> = Vec::new();
+        let mut stderr: Vec<u8> = Vec::new();
+        let mut stopped = false;
+
+        let emit = |stream: &str, lines: Vec<String>| {
+            if !lines.is_empty() {
+                let _ = window.emit(DOCKER_LOG_LINES_EVENT, DockerLogLinesEvent {
+                    logs_id: logs_id.clone(),
+                    stream: stream.to_string(),
+                    lines,
+                });
+            }
+        };
+
+        loop {
+            let msg = tokio::select! {
+                msg = process.wait() => msg,
+                _ = &mut stop_rx => {
+                    stopped = true;
+                    break;
+                }
+            };
+
+            match msg {
+                Some(ChannelMsg::Data { data }) => {
+                    stdout.extend_from_slice(&data);
+                    emit("stdout", take_complete_lines(&mut stdout));
+                }
+                Some(ChannelMsg::ExtendedData { data, ext: 1 }) => {
+                    stderr.extend_from_slice(&data);
+                    emit("stderr", take_complete_lines(&mut stderr));
+                }
+                Some(ChannelMsg::Close) | None => {
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        // 推送剩余的不完整行
+        if !stopped {
+            for (stream, buffer) in [("stdout", &stdout), ("stderr", &stderr)] {
+                if !buffer.is_empty() {
+                    emit(stream, vec![String::from_utf8_lossy(buffer).to_string()]);
+                }
+            }
+        }
+
+        // 用户停止时结束远程 docker logs，仅关闭通道的话没有新日志时它会一直残留
+        process.close(stopped).await;
+        LOG_TASKS.finish(&logs_id, generation);
+
+        let _ = window.emit(DOCKER_LOG_STOPPED_EVENT, DockerLogStoppedEvent {
+            logs_id: logs_id.clone(),
+            stopped,
+        });
+    });
+
+    Ok(StartContainerLogsResult {
+        success: true,
+        logs_id: params.logs_id,
+    })
+}
+
+/// 停止读取容器日志
+///
+/// # 命令名称
+/// `stop_container_logs`
+///
+/// # 参数
+/// - `logs_id`: 日志任务ID
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn stop_container_logs(params: StopContainerLogsParams) -> Result<StopContainerLogsResult, String> {
+    let message = if LOG_TASKS.stop(&params.logs_id) { "已停止读取日志" } else { "日志任务已结束" };
+    Ok(StopContainerLogsResult {
+        success: true,
+        message: Some(message.to_string()),
+    })
+}
+
+/// 执行输出为每行一个 JSON 对象的 docker 命令并逐行解析
+async fn run_json_lines<T: serde::de::DeserializeOwned>(server_id: &str, command: &str) -> Result<Vec<T>, String> {
+    let output = exec_command(server_id, command).await?;
+    if output.exit_code != 0 {
+        return Err(docker_error(&output.stderr));
+    }
+
+    output
+        .stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| format!("解析 docker 输出失败: {}", e)))
+        .collect()
+}
+
+/// 容器 ID 或名称只允许字母、数字及 `_`、`.`、`-`
+fn validate_container(container: &str) -> Result<(), String> {
+    let valid = !container.is_empty()
+        && !container.starts_with('-')
+        && container
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的容器名称: {}", container))
+    }
+}
+
+/// 将 docker 的常见错误转换为易读的提示
+fn docker_error(stderr: &str) -> String {
+    let message = stderr.lines().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+    if message.contains("command not found") || message.contains("docker: not found") {
+        "目标服务器未安装 Docker".to_string()
+    } else if message.contains("permission denied") && message.contains("docker") {
+        "当前用户无权访问 Docker（需要 root 权限或加入 docker 组）".to_string()
+    } else if message.contains("Cannot connect to the Docker daemon") {
+        "无法连接 Docker 服务，请确认 Docker 正在运行".to_string()
+    } else if message.is_empty() {
+        "docker 命令执行失败".to_string()
+    } else {
+        message.to_string()
+    }
+}
+
+/// 转换 `docker ps` 行
+fn container_from_row(row: DockerPsRow) -> ContainerInfo {
+    // 较老的 docker 没有 State 字段，从 Status 推断
+    let state = if !row.state.is_empty() {
+        row.state
+    } else if row.status.contains("(Paused)") {
+        "paused".to_string()
+    } else if row.status.starts_with("Up") {
+        "running".to_string()
+    } else if row.status.starts_with("Created") {
+        "created".to_string()
+    } else {
+        "exited".to_string()
+    };
+
+    ContainerInfo {
+        id: row.id,
+        name: row.names,
+        image: row.image,
+        command: row.command.trim_matches('"').to_string(),
+        created_at: row.created_at,
+        running_for: row.running_for,
+        state,
+        status: row.status,
+        ports: row.ports,
+        networks: row
+            .networks
+            .split(',')
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .collect(),
+    }
+}
+
+/// 转换 `docker stats` 行
+fn stats_from_row(row: DockerStatsRow) -> ContainerStats {
+    let (memory_used, memory_limit) = parse_size_pair(&row.mem_usage);
+    let (net_rx, net_tx) = parse_size_pair(&row.net_io);
+    let (block_read, block_write) = parse_size_pair(&row.block_io);
+
+    ContainerStats {
+        id: row.id,
+        name: row.name,
+        cpu: parse_percent(&row.cpu_perc),
+        memory_used,
+        memory_limit,
+        memory: parse_percent(&row.mem_perc),
+        net_rx,
+        net_tx,
+        block_read,
+        block_write,
+        pids: row.pids.trim().parse().unwrap_or(0),
+    }
+}
+
+/// 解析百分比，如 "12.5%"
+fn parse_percent(value: &str) -> f64 {
+    value.trim().trim_end_matches('%').parse().unwrap_or(0.0)
+}
+
+/// 解析 "已用 / 总量" 格式的大小，如 "10.5MiB / 1.94GiB"
+fn parse_size_pair(value: &str) -> (u64, u64) {
+    match value.split_once('/') {
+        Some((first, second)) => (parse_size(first), parse_size(second)),
+        None => (parse_size(value), 0),
+    }
+}
+
+/// 解析 docker 输出的可读大小，如 "512B"、"1.5kB"、"10MiB"、"2.3GB"，无法识别时返回 0
+fn parse_size(value: &str) -> u64 {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = match number.parse() {
+        Ok(number) => number,
+        Err(_) => return 0,
+    };
+
+    let multiplier: f64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        "pb" => 1e15,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "pib" => 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return 0,
+    };
+
+    (number * multiplier) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ps_row(line: &str) -> ContainerInfo {
+        container_from_row(serde_json::from_str(line).unwrap())
+    }
+
+    #[test]
+    fn converts_docker_ps_rows() {
+        let info = ps_row(r#"{"Command":"\"/docker-entrypoint.…\"","CreatedAt":"2023-11-14 10:00:00 +0000 UTC","ID":"3f2a1b9c8d7e","Image":"nginx:1.25","Labels":"","LocalVolumes":"0","Mounts":"","Names":"web","Networks":"bridge,frontend","Ports":"0.0.0.0:8080->80/tcp","RunningFor":"2 hours ago","Size":"1.09kB","State":"running","Status":"Up 2 hours"}"#);
+        assert_eq!((info.id.as_str(), info.name.as_str(), info.image.as_str()), ("3f2a1b9c8d7e", "web", "nginx:1.25"));
+        assert_eq!(info.command, "/docker-entrypoint.…");
+        assert_eq!(info.state, "running");
+        assert_eq!(info.ports, "0.0.0.0:8080->80/tcp");
+        assert_eq!(info.networks, vec!["bridge", "frontend"]);
+    }
+
+    #[test]
+    fn infers_state_from_status_on_old_docker() {
+        let state = |status: &str| {
+            ps_row(&format!(r#"{{"ID":"abc","Names":"app","Status":"{}","Networks":""}}"#, status)).state
+        };
+        assert_eq!(state("Up 3 minutes (Paused)"), "paused");
+        assert_eq!(state("Up 5 days (healthy)"), "running");
+        assert_eq!(state("Created"), "created");
+        assert_eq!(state("Exited (0) 2 weeks ago"), "exited");
+        assert!(ps_row(r#"{"ID":"abc","Status":"Up 1 second"}"#).networks.is_empty());
+    }
+
+    #[test]
+    fn converts_docker_stats_rows() {
+        let row: DockerStatsRow = serde_json::from_str(r#"{"BlockIO":"12.3MB / 4.1kB","CPUPerc":"12.50%","Container":"3f2a1b9c8d7e","ID":"3f2a1b9c8d7e","MemPerc":"0.53%","MemUsage":"10.5MiB / 1.94GiB","Name":"web","NetIO":"1.2kB / 648B","PIDs":"5"}"#).unwrap();
+        let stats = stats_from_row(row);
+        assert_eq!(stats.name, "web");
+        assert_eq!(stats.cpu, 12.5);
+        assert_eq!(stats.memory, 0.53);
+        assert_eq!(stats.memory_used, (10.5 * 1024.0 * 1024.0) as u64);
+        assert_eq!(stats.memory_limit, (1.94 * 1024.0 * 1024.0 * 1024.0) as u64);
+        assert_eq!((stats.net_rx, stats.net_tx), (1200, 648));
+        assert_eq!((stats.block_read, stats.block_write), (12_300_000, 4100));
+        assert_eq!(stats.pids, 5);
+    }
+
+    #[test]
+    fn parses_sizes_and_percentages() {
+        assert_eq!(parse_size("512B"), 512);
+        assert_eq!(parse_size(" 1.5kB "), 1500);
+        assert_eq!(parse_size("10MiB"), 10 * 1024 * 1024);
+        assert_eq!(parse_size("2TB"), 2_000_000_000_000);
+        assert_eq!(parse_size("1.5PB"), 1_500_000_000_000_000);
+        assert_eq!(parse_size("0B"), 0);
+        // 无法识别的单位和数值不当作字节数
+        assert_eq!(parse_size("1.5XB"), 0);
+        assert_eq!(parse_size("--"), 0);
+
+        assert_eq!(parse_size_pair("1.2kB / 648B"), (1200, 648));
+        assert_eq!(parse_size_pair("100MB"), (100_000_000, 0));
+        assert_eq!(parse_percent("99.9%"), 99.9);
+        assert_eq!(parse_percent("--"), 0.0);
+    }
+}
@@ -3,6 +3,44 @@
  */
 
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use russh::ChannelMsg;
+use russh_sftp::client::SftpSession;
+use russh_sftp::protocol::FileAttributes;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::checksum::{local_sha256, remote_sha256};
+use crate::scp::{scp_download, scp_upload};
+use crate::ssh::{exec_command, get_session, shell_quote};
+use crate::throttle::TransferThrottle;
+
+/// 传输块大小
+const TRANSFER_CHUNK_SIZE: usize = 32 * 1024;
+/// 开启校验时，校验不一致后最多传输的次数（含首次）
+const VERIFY_TRANSFER_ATTEMPTS: u32 = 3;
+
+/// 文件传输错误
+///
+/// 序列化为带 `kind` 标签的对象，`message` 字段与普通错误字符串含义相同，
+/// 前端可根据 `kind` 区分校验失败和其他错误。
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TransferError {
+    /// 传输完成后校验和不一致（已自动重试）
+    ChecksumMismatch {
+        message: String,
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    /// 其他传输错误
+    Failed { message: String },
+}
+
+impl From<String> for TransferError {
+    fn from(message: String) -> Self {
+        TransferError::Failed { message }
+    }
+}
 
 /// 文件信息
 #[derive(Debug, Serialize)]
@@ -34,6 +72,9 @@ pub struct UploadFileParams {
     pub server_id: String,
     pub local_path: String,
     pub remote_path: String,
+    pub verify: Option<bool>,  // 传输完成后是否校验 SHA-256
+    pub transfer_id: Option<String>,  // 传输ID（用于传输过程中调整限速）
+    pub bandwidth_limit: Option<u64>,  // 限速（字节/秒，可选）
 }
 
 /// 上传文件返回
@@ -49,6 +90,9 @@ pub struct DownloadFileParams {
     pub server_id: String,
     pub remote_path: String,
     pub local_path: String,
+    pub verify: Option<bool>,  // 传输完成后是否校验 SHA-256
+    pub transfer_id: Option<String>,  // 传输ID（用于传输过程中调整限速）
+    pub bandwidth_limit: Option<u64>,  // 限速（字节/秒，可选）
 }
 
 /// 下载文件返回
@@ -129,28 +173,52 @@ pub struct ChangeFileModeResult {
 /// - `files`: 文件列表
 #[tauri::command]
 pub async fn list_remote_directory(params: ListRemoteDirectoryParams) -> Result<ListRemoteDirectoryResult, String> {
-    // TODO: 实现实际的 SFTP 文件列表获取逻辑
-    // 这里需要使用 ssh2 或类似库来获取文件列表
-    
-    // 模拟返回
-    Ok(ListRemoteDirectoryResult {
-        files: vec![
-            FileInfo {
-                name: ".".to_string(),
-                file_type: "directory".to_string(),
-                size: 0,
-                modified: "2024-01-01T00:00:00Z".to_string(),
-                path: params.path.clone(),
-            },
-            FileInfo {
-                name: "..".to_string(),
-                file_type: "directory".to_string(),
-                size: 0,
-                modified: "2024-01-01T00:00:00Z".to_string(),
-                path: format!("{}/..", params.path),
-            },
-        ],
-    })
+    let sftp = match try_open_sftp(&params.server_id).await? {
+        Some(sftp) => sftp,
+        None => {
+            return Ok(ListRemoteDirectoryResult {
+                files: list_directory_by_ls(&params.server_id, &params.path).await?,
+            });
+        }
+    };
+
+    let entries = sftp
+        .read_dir(params.path.as_str())
+        .await
+        .map_err(|e| format!("读取目录失败: {}", e))?;
+
+    let mut files = Vec::new();
+
+    // 保留返回上级目录的条目
+    if params.path != "/" {
+        files.push(FileInfo {
+            name: "..".to_string(),
+            file_type: "directory".to_string(),
+            size: 0,
+            modified: String::new(),
+            path: parent_remote_path(&params.path),
+        });
+    }
+
+    for entry in entries {
+        let name = entry.file_name();
+        if name == "." || name == ".." {
+            continue;
+        }
+
+        let metadata = entry.metadata();
+        files.push(FileInfo {
+            path: join_remote_path(&params.path, &name),
+            name,
+            file_type: if metadata.is_dir() { "directory" } else { "file" }.to_string(),
+            size: metadata.len(),
+            modified: format_timestamp(metadata.mtime.unwrap_or(0) as u64),
+        });
+    }
+
+    let _ = sftp.close().await;
+
+    Ok(ListRemoteDirectoryResult { files })
 }
 
 /// 上传文件到远程服务器
@@ -162,15 +230,47 @@ pub async fn list_remote_directory(params: ListRemoteDirectoryParams) -> Result<
 /// - `server_id`: 服务器ID
 /// - `local_path`: 本地文件路径
 /// - `remote_path`: 远程保存路径（目录）
+/// - `verify`: 传输完成后是否校验 SHA-256（可选）
+/// - `transfer_id`: 传输ID（可选）
+/// - `bandwidth_limit`: 限速，字节/秒（可选）
 /// 
 /// # 返回
 /// - `success`: 是否成功
 /// - `message`: 消息（可选）
+/// 
+/// 校验不一致时自动重新传输，重试后仍不一致则返回 `kind` 为 `checksum_mismatch` 的错误
 #[tauri::command]
-pub async fn upload_file(params: UploadFileParams) -> Result<UploadFileResult, String> {
-    // TODO: 实现实际的 SFTP 文件上传逻辑
-    // 这里需要使用 ssh2 或类似库来上传文件
-    
+pub async fn upload_file(params: UploadFileParams) -> Result<UploadFileResult, TransferError> {
+    let local_path = Path::new(&params.local_path);
+    let file_name = local_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| "无效的本地文件路径".to_string())?;
+    let remote_path = join_remote_path(&params.remote_path, &file_name);
+    let throttle = transfer_throttle(params.transfer_id.as_deref(), params.bandwidth_limit);
+
+    let mut attempt = 1;
+    loop {
+        match try_open_sftp(&params.server_id).await? {
+            Some(sftp) => {
+                let result = sftp_upload(&sftp, local_path, &remote_path, &throttle, |_| {}).await;
+                let _ = sftp.close().await;
+                result?;
+            }
+            None => {
+                scp_upload(&params.server_id, local_path, &remote_path, &throttle, |_| {}).await?;
+            }
+        }
+
+        if !params.verify.unwrap_or(false) {
+            break;
+        }
+        match verify_transfer(&params.server_id, local_path, &remote_path).await {
+            Err(TransferError::ChecksumMismatch { .. }) if attempt < VERIFY_TRANSFER_ATTEMPTS => attempt += 1,
+            result => break result?,
+        }
+    }
+
     Ok(UploadFileResult {
         success: true,
         message: Some("上传成功".to_string()),
@@ -186,15 +286,42 @@ pub async fn upload_file(params: UploadFileParams) -> Result<UploadFileResult, S
 /// - `server_id`: 服务器ID
 /// - `remote_path`: 远程文件路径
 /// - `local_path`: 本地保存路径
+/// - `verify`: 传输完成后是否校验 SHA-256（可选）
+/// - `transfer_id`: 传输ID（可选）
+/// - `bandwidth_limit`: 限速，字节/秒（可选）
 /// 
 /// # 返回
 /// - `success`: 是否成功
 /// - `message`: 消息（可选）
+/// 
+/// 校验不一致时自动重新传输，重试后仍不一致则返回 `kind` 为 `checksum_mismatch` 的错误
 #[tauri::command]
-pub async fn download_file(params: DownloadFileParams) -> Result<DownloadFileResult, String> {
-    // TODO: 实现实际的 SFTP 文件下载逻辑
-    // 这里需要使用 ssh2 或类似库来下载文件
-    
+pub async fn download_file(params: DownloadFileParams) -> Result<DownloadFileResult, TransferError> {
+    let local_path = Path::new(&params.local_path);
+    let throttle = transfer_throttle(params.transfer_id.as_deref(), params.bandwidth_limit);
+
+    let mut attempt = 1;
+    loop {
+        match try_open_sftp(&params.server_id).await? {
+            Some(sftp) => {
+                let result = sftp_download(&sftp, &params.remote_path, local_path, &throttle, |_| {}).await;
+                let _ = sftp.close().await;
+                result?;
+            }
+            None => {
+                scp_download(&params.server_id, &params.remote_path, local_path, &throttle, |_| {}).await?;
+            }
+        }
+
+        if !params.verify.unwrap_or(false) {
+            break;
+        }
+        match verify_transfer(&params.server_id, local_path, &params.remote_path).await {
+            Err(TransferError::ChecksumMismatch { .. }) if attempt < VERIFY_TRANSFER_ATTEMPTS => attempt += 1,
+            result => break result?,
+        }
+    }
+
     Ok(DownloadFileResult {
         success: true,
         message: Some("下载成功".to_string()),
@@ -215,8 +342,23 @@ pub async fn download_file(params: DownloadFileParams) -> Result<DownloadFileRes
 /// - `message`: 消息（可选）
 #[tauri::command]
 pub async fn create_directory(params: CreateDirectoryParams) -> Result<CreateDirectoryResult, String> {
-    // TODO: 实现实际的 SFTP 目录创建逻辑
-    
+    let sftp = match try_open_sftp(&params.server_id).await? {
+        Some(sftp) => sftp,
+        None => {
+            run_file_command(&params.server_id, &format!("mkdir {}", shell_quote(&params.path)), "创建目录失败").await?;
+            return Ok(CreateDirectoryResult {
+                success: true,
+                message: Some("创建目录成功".to_string()),
+            });
+        }
+    };
+    let result = sftp
+        .create_dir(params.path.as_str())
+        .await
+        .map_err(|e| format!("创建目录失败: {}", e));
+    let _ = sftp.close().await;
+    result?;
+
     Ok(CreateDirectoryResult {
         success: true,
         message: Some("创建目录成功".to_string()),
@@ -237,8 +379,28 @@ pub async fn create_directory(params: CreateDirectoryParams) -> Result<CreateDir
 /// - `message`: 消息（可选）
 #[tauri::command]
 pub async fn delete_files(params: DeleteFilesParams) -> Result<DeleteFilesResult, String> {
-    // TODO: 实现实际的 SFTP 文件删除逻辑
-    
+    let sftp = match try_open_sftp(&params.server_id).await? {
+        Some(sftp) => sftp,
+        None => {
+            let quoted: Vec<String> = params.paths.iter().map(|p| shell_quote(p)).collect();
+            run_file_command(&params.server_id, &format!("rm -rf -- {}", quoted.join(" ")), "删除失败").await?;
+            return Ok(DeleteFilesResult {
+                success: true,
+                message: Some("删除成功".to_string()),
+            });
+        }
+    };
+
+    let mut result = Ok(());
+    for path in &params.paths {
+        result = remove_remote_path(&sftp, path).await;
+        if result.is_err() {
+            break;
+        }
+    }
+    let _ = sftp.close().await;
+    result?;
+
     Ok(DeleteFilesResult {
         success: true,
         message: Some("删除成功".to_string()),
@@ -260,8 +422,24 @@ pub async fn delete_files(params: DeleteFilesParams) -> Result<DeleteFilesResult
 /// - `message`: 消息（可选）
 #[tauri::command]
 pub async fn rename_file(params: RenameFileParams) -> Result<RenameFileResult, String> {
-    // TODO: 实现实际的 SFTP 文件重命名逻辑
-    
+    let sftp = match try_open_sftp(&params.server_id).await? {
+        Some(sftp) => sftp,
+        None => {
+            let command = format!("mv -- {} {}", shell_quote(&params.old_path), shell_quote(&params.new_path));
+            run_file_command(&params.server_id, &command, "重命名失败").await?;
+            return Ok(RenameFileResult {
+                success: true,
+                message: Some("重命名成功".to_string()),
+            });
+        }
+    };
+    let result = sftp
+        .rename(params.old_path.as_str(), params.new_path.as_str())
+        .await
+        .map_err(|e| format!("重命名失败: {}", e));
+    let _ = sftp.close().await;
+    result?;
+
     Ok(RenameFileResult {
         success: true,
         message: Some("重命名成功".to_string()),
@@ -283,11 +461,428 @@ pub async fn rename_file(params: RenameFileParams) -> Result<RenameFileResult, S
 /// - `message`: 消息（可选）
 #[tauri::command]
 pub async fn change_file_mode(params: ChangeFileModeParams) -> Result<ChangeFileModeResult, String> {
-    // TODO: 实现实际的 chmod 逻辑
-    
+    let mode = u32::from_str_radix(params.mode.trim(), 8)
+        .ok()
+        .filter(|m| *m <= 0o7777)
+        .ok_or_else(|| format!("无效的权限模式: {}", params.mode))?;
+
+    let sftp = match try_open_sftp(&params.server_id).await? {
+        Some(sftp) => sftp,
+        None => {
+            let command = format!("chmod {:o} -- {}", mode, shell_quote(&params.path));
+            run_file_command(&params.server_id, &command, "修改权限失败").await?;
+            return Ok(ChangeFileModeResult {
+                success: true,
+                message: Some("修改权限成功".to_string()),
+            });
+        }
+    };
+    let attributes = FileAttributes {
+        permissions: Some(mode),
+        ..FileAttributes::empty()
+    };
+    let result = sftp
+        .set_metadata(params.path.as_str(), attributes)
+        .await
+        .map_err(|e| format!("修改权限失败: {}", e));
+    let _ = sftp.close().await;
+    result?;
+
     Ok(ChangeFileModeResult {
         success: true,
         message: Some("修改权限成功".to_string()),
     })
 }
 
+/// 打开 SFTP 会话
+pub(crate) async fn open_sftp(server_id: &str) -> Result<SftpSession, String> {
+    try_open_sftp(server_id)
+        .await?
+        .ok_or_else(|| "服务器不支持 SFTP 子系统".to_string())
+}
+
+/// 尝试打开 SFTP 会话，服务器拒绝 SFTP 子系统时返回 `None`
+pub(crate) async fn try_open_sftp(server_id: &str) -> Result<Option<SftpSession>, String> {
+    let session = get_session(server_id)?;
+    let mut channel = {
+        let handle = session.lock().await;
+        handle
+            .channel_open_session()
+            .await
+            .map_err(|e| format!("打开通道失败: {}，连接可能已断开", e))?
+    };
+
+    channel
+        .request_subsystem(true, "sftp")
+        .await
+        .map_err(|e| format!("请求 SFTP 子系统失败: {}", e))?;
+
+    // 等待服务器确认子系统请求
+    loop {
+        match channel.wait().await {
+            Some(ChannelMsg::Success) => break,
+            Some(ChannelMsg::Failure) | Some(ChannelMsg::Close) | None => {
+                let _ = channel.close().await;
+                return Ok(None);
+            }
+            _ => {}
+        }
+    }
+
+    SftpSession::new(channel.into_stream())
+        .await
+        .map(Some)
+        .map_err(|e| format!("初始化 SFTP 会话失败: {}", e))
+}
+
+/// 执行文件操作命令（SFTP 不可用时使用），失败时返回带错误输出的信息
+async fn run_file_command(server_id: &str, command: &str, error_prefix: &str) -> Result<(), String> {
+    let output = exec_command(server_id, command).await?;
+    if output.exit_code != 0 {
+        return Err(format!("{}: {}", error_prefix, output.stderr.trim()));
+    }
+    Ok(())
+}
+
+/// 通过 `ls` 获取目录列表（SFTP 不可用时使用）
+async fn list_directory_by_ls(server_id: &str, path: &str) -> Result<Vec<FileInfo>, String> {
+    let command = format!("LC_ALL=C ls -la --time-style=full-iso -- {}", shell_quote(path));
+    let output = exec_command(server_id, &command).await?;
+    if output.exit_code != 0 {
+        return Err(format!("读取目录失败: {}", output.stderr.trim()));
+    }
+
+    let mut files = Vec::new();
+    if path != "/" {
+        files.push(FileInfo {
+            name: "..".to_string(),
+            file_type: "directory".to_string(),
+            size: 0,
+            modified: String::new(),
+            path: parent_remote_path(path),
+        });
+    }
+
+    files.extend(
+        output
+            .stdout
+            .lines()
+            .filter_map(parse_ls_line)
+            .filter(|(name, _, _, _)| name != "." && name != "..")
+            .map(|(name, is_dir, size, modified)| FileInfo {
+                path: join_remote_path(path, &name),
+                name,
+                file_type: if is_dir { "directory" } else { "file" }.to_string(),
+                size,
+                modified,
+            }),
+    );
+
+    Ok(files)
+}
+
+/// 解析 `ls -la --time-style=full-iso` 的一行输出
+///
+/// 格式：`drwxr-xr-x 2 root root 4096 2024-01-01 10:00:00.000000000 +0800 name`，
+/// 返回（文件名，是否目录，大小，ISO 8601 修改时间）。
+/// 与 SFTP 列表一致，符号链接（包括指向目录的链接）按文件返回，文件名不含 ` -> 目标`。
+fn parse_ls_line(line: &str) -> Option<(String, bool, u64, String)> {
+    let mut rest = line;
+    let mut fields = Vec::new();
+
+    // 依次取出 权限、链接数、用户、组、大小、日期、时间、时区，剩余部分为文件名（可能包含空格）
+    while fields.len() < 8 {
+        rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace)?;
+        let field = &rest[..end];
+        rest = &rest[end..];
+
+        // 设备文件的大小字段为 "主设备号, 次设备号"
+        if fields.len() == 4 && field.ends_with(',') {
+            rest = rest.trim_start();
+            let end = rest.find(char::is_whitespace)?;
+            rest = &rest[end..];
+            fields.push("0");
+            continue;
+        }
+        fields.push(field);
+    }
+
+    let permissions = fields[0];
+    if permissions.len() < 10 {
+        return None; // 跳过 "total 12" 等非条目行
+    }
+
+    let name = rest.strip_prefix(' ').unwrap_or(rest);
+    let name = match name.find(" -> ") {
+        Some(pos) if permissions.starts_with('l') => &name[..pos],
+        _ => name,
+    };
+
+    let size = fields[4].parse().unwrap_or(0);
+    let time = fields[6].split('.').next().unwrap_or(fields[6]);
+    let zone = fields[7];
+    let modified = if zone.len() == 5 {
+        format!("{}T{}{}:{}", fields[5], time, &zone[..3], &zone[3..])
+    } else {
+        format!("{}T{}", fields[5], time)
+    };
+
+    Some((name.to_string(), permissions.starts_with('d'), size, modified))
+}
+
+/// 创建传输限速句柄：有传输ID时注册单独的限速器，否则只受全局限速约束
+fn transfer_throttle(transfer_id: Option<&str>, bandwidth_limit: Option<u64>) -> TransferThrottle {
+    match transfer_id {
+        Some(transfer_id) => TransferThrottle::for_transfer(transfer_id, bandwidth_limit),
+        None => TransferThrottle::global(),
+    }
+}
+
+/// 分块复制数据流，每写入一块回调一次已传输的总字节数
+pub(crate) async fn copy_with_progress<R, W, F>(reader: &mut R, writer: &mut W, throttle: &TransferThrottle, mut on_progress: F) -> Result<u64, String>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+    F: FnMut(u64),
+{
+    let mut buffer = vec![0u8; TRANSFER_CHUNK_SIZE];
+    let mut transferred = 0u64;
+
+    loop {
+        let n = reader
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("读取数据失败: {}", e))?;
+        if n == 0 {
+            break;
+        }
+
+        throttle.consume(n as u64).await;
+        writer
+            .write_all(&buffer[..n])
+            .await
+            .map_err(|e| format!("写入数据失败: {}", e))?;
+        transferred += n as u64;
+        on_progress(transferred);
+    }
+
+    writer.flush().await.map_err(|e| format!("写入数据失败: {}", e))?;
+    Ok(transferred)
+}
+
+/// 通过 SFTP 上传本地文件到远程完整路径
+pub(crate) async fn sftp_upload<F: FnMut(u64)>(sftp: &SftpSession, local_path: &Path, remote_path: &str, throttle: &TransferThrottle, on_progress: F) -> Result<u64, String> {
+    let mut local = tokio::fs::File::open(local_path)
+        .await
+        .map_err(|e| format!("打开本地文件失败: {}", e))?;
+    let mut remote = sftp
+        .create(remote_path)
+        .await
+        .map_err(|e| format!("创建远程文件失败: {}", e))?;
+
+    let transferred = copy_with_progress(&mut local, &mut remote, throttle, on_progress).await?;
+    // 服务器在关闭文件时才可能报告配额不足等写入错误
+    remote
+        .shutdown()
+        .await
+        .map_err(|e| format!("写入远程文件失败: {}", e))?;
+
+    Ok(transferred)
+}
+
+/// 通过 SFTP 下载远程文件到本地完整路径
+pub(crate) async fn sftp_download<F: FnMut(u64)>(sftp: &SftpSession, remote_path: &str, local_path: &Path, throttle: &TransferThrottle, on_progress: F) -> Result<u64, String> {
+    let mut remote = sftp
+        .open(remote_path)
+        .await
+        .map_err(|e| format!("打开远程文件失败: {}", e))?;
+
+    if let Some(parent) = local_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("创建本地目录失败: {}", e))?;
+    }
+    let mut local = tokio::fs::File::create(local_path)
+        .await
+        .map_err(|e| format!("创建本地文件失败: {}", e))?;
+
+    let transferred = copy_with_progress(&mut remote, &mut local, throttle, on_progress).await?;
+    remote
+        .shutdown()
+        .await
+        .map_err(|e| format!("关闭远程文件失败: {}", e))?;
+
+    Ok(transferred)
+}
+
+/// 比较本地文件与远程文件的 SHA-256，不一致时返回可重试的校验错误
+pub(crate) async fn verify_transfer(server_id: &str, local_path: &Path, remote_path: &str) -> Result<(), TransferError> {
+    let expected = local_sha256(local_path).await?;
+    let actual = remote_sha256(server_id, remote_path).await?;
+
+    if expected != actual {
+        return Err(TransferError::ChecksumMismatch {
+            message: format!("文件校验失败: {}", remote_path),
+            path: remote_path.to_string(),
+            expected,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+/// 删除远程文件或目录（目录会递归删除）
+pub(crate) async fn remove_remote_path(sftp: &SftpSession, path: &str) -> Result<(), String> {
+    let metadata = sftp
+        .symlink_metadata(path)
+        .await
+        .map_err(|e| format!("删除失败: {}: {}", path, e))?;
+
+    if !metadata.is_dir() {
+        return sftp
+            .remove_file(path)
+            .await
+            .map_err(|e| format!("删除失败: {}: {}", path, e));
+    }
+
+    // 先收集所有子项，再从最深层开始删除
+    let mut pending = vec![path.to_string()];
+    let mut dirs = Vec::new();
+    while let Some(dir) = pending.pop() {
+        let entries = sftp
+            .read_dir(dir.as_str())
+            .await
+            .map_err(|e| format!("读取目录失败: {}: {}", dir, e))?;
+
+        for entry in entries {
+            let name = entry.file_name();
+            if name == "." || name == ".." {
+                continue;
+            }
+
+            let child = join_remote_path(&dir, &name);
+            if entry.file_type().is_dir() {
+                pending.push(child);
+            } else {
+                sftp.remove_file(child.as_str())
+                    .await
+                    .map_err(|e| format!("删除失败: {}: {}", child, e))?;
+            }
+        }
+
+        dirs.push(dir);
+    }
+
+    for dir in dirs.iter().rev() {
+        sftp.remove_dir(dir.as_str())
+            .await
+            .map_err(|e| format!("删除失败: {}: {}", dir, e))?;
+    }
+
+    Ok(())
+}
+
+/// 拼接远程路径
+pub(crate) fn join_remote_path(dir: &str, name: &str) -> String {
+    if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// 获取远程路径的上级目录
+pub(crate) fn parent_remote_path(path: &str) -> String {
+    let trimmed = path.trim_end_matches('/');
+    match trimmed.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(pos) => trimmed[..pos].to_string(),
+    }
+}
+
+/// 将 Unix 时间戳格式化为 ISO 8601 字符串（UTC）
+pub(crate) fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // 公历日期换算（Howard Hinnant 的 civil_from_days 算法）
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `LC_ALL=C ls -la --time-style=full-iso /srv` 输出
+    const LS_OUTPUT: &str = "\
+total 24
+drwxr-xr-x  4 root root     4096 2024-01-01 10:00:00.123456789 +0800 .
+drwxr-xr-x 19 root root     4096 2023-12-31 09:30:00.000000000 +0800 ..
+-rw-r--r--  1 root root      220 2024-01-02 08:15:30.500000000 +0800 .bashrc
+drwxr-x---  2 www  www      4096 2024-01-03 12:00:00.000000000 -0500 my site
+-rw-r--r--  1 root root 10485760 2024-01-04 23:59:59.999999999 +0000 backup 2024.tar.gz
+lrwxrwxrwx  1 root root        9 2024-01-05 06:00:00.000000000 +0800 current -> releases/3
+lrwxrwxrwx  1 root root       12 2024-01-05 06:00:00.000000000 +0800 old link -> /tmp/a b
+crw-rw-rw-  1 root root   1,   3 2024-01-06 07:00:00.000000000 +0800 null
+brw-rw----  1 root disk 259,   0 2024-01-06 07:00:00.000000000 +0800 nvme0n1
+";
+
+    fn parse_all() -> Vec<(String, bool, u64, String)> {
+        LS_OUTPUT.lines().filter_map(parse_ls_line).collect()
+    }
+
+    #[test]
+    fn skips_total_line() {
+        assert_eq!(parse_ls_line("total 24"), None);
+        assert_eq!(parse_all().len(), 9);
+    }
+
+    #[test]
+    fn parses_regular_entries_and_names_with_spaces() {
+        let entries = parse_all();
+        assert_eq!(entries[0], (".".to_string(), true, 4096, "2024-01-01T10:00:00+08:00".to_string()));
+        assert_eq!(entries[2], (".bashrc".to_string(), false, 220, "2024-01-02T08:15:30+08:00".to_string()));
+        assert_eq!(entries[3], ("my site".to_string(), true, 4096, "2024-01-03T12:00:00-05:00".to_string()));
+        assert_eq!(entries[4].0, "backup 2024.tar.gz");
+        assert_eq!(entries[4].2, 10485760);
+    }
+
+    #[test]
+    fn strips_symlink_targets_and_reports_links_as_files() {
+        let entries = parse_all();
+        assert_eq!((entries[5].0.as_str(), entries[5].1), ("current", false));
+        assert_eq!((entries[6].0.as_str(), entries[6].1), ("old link", false));
+
+        // 普通文件名中的 " -> " 保留
+        let entry = parse_ls_line("-rw-r--r-- 1 root root 1 2024-01-01 10:00:00.000000000 +0800 a -> b").unwrap();
+        assert_eq!(entry.0, "a -> b");
+    }
+
+    #[test]
+    fn parses_device_files_with_major_minor_size() {
+        let entries = parse_all();
+        assert_eq!((entries[7].0.as_str(), entries[7].2), ("null", 0));
+        assert_eq!(entries[7].3, "2024-01-06T07:00:00+08:00");
+        assert_eq!((entries[8].0.as_str(), entries[8].2), ("nvme0n1", 0));
+    }
+}
@@ -0,0 +1,368 @@
+/**
+ * 多服务器概览相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use crate::db::get_db;
+use crate::monitor::{sample_system_monitor, SystemMonitorData};
+use crate::server::list_server_names;
+use crate::ssh::is_connected;
+
+/// 默认并发采样数
+const DEFAULT_CONCURRENCY: usize = 8;
+/// 最大并发采样数
+const MAX_CONCURRENCY: usize = 32;
+/// 默认单台服务器超时时间（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+/// 最小单台服务器超时时间（秒），需大于两次采样的间隔
+const MIN_TIMEOUT_SECS: u64 = 2;
+/// 两次采样的间隔（毫秒），CPU 使用率和网络速率按两次采样的差值计算
+const BASELINE_INTERVAL_MS: u64 = 1000;
+
+/// 单台服务器概览
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerSummary {
+    pub server_id: String,
+    pub name: String,
+    pub group_name: Option<String>,
+    pub status: String,            // "ok" | "disconnected" | "timeout" | "error"
+    pub reachable: bool,
+    pub cpu: Option<f64>,          // CPU 使用率 (0-100)
+    pub memory: Option<f64>,       // 内存使用率 (0-100)
+    pub disk: Option<f64>,         // 使用率最高的磁盘的使用率 (0-100)
+    pub disk_mount: Option<String>, // 使用率最高的磁盘的挂载点
+    pub load1: Option<f64>,        // 1 分钟负载
+    pub cores: Option<usize>,
+    pub latency_ms: Option<u64>,   // 采样耗时（毫秒）
+    pub error: Option<String>,
+}
+
+/// 分组汇总
+#[derive(Debug, Default, Serialize)]
+pub struct FleetTotals {
+    pub total: usize,
+    pub reachable: usize,
+    pub unreachable: usize,
+    pub avg_cpu: Option<f64>,
+    pub max_cpu: Option<f64>,
+    pub avg_memory: Option<f64>,
+    pub max_memory: Option<f64>,
+    pub max_disk: Option<f64>,
+}
+
+/// 获取多服务器概览参数
+#[derive(Debug, Deserialize)]
+pub struct GetFleetSummaryParams {
+    pub group_name: Option<String>,      // 服务器分组（可选，默认全部服务器）
+    pub server_ids: Option<Vec<String>>, // 指定服务器（可选，与分组同时指定时取交集）
+    pub concurrency: Option<usize>,      // 并发采样数（默认 8）
+    pub timeout_secs: Option<u64>,       // 单台服务器超时时间（默认 10 秒）
+}
+
+/// 获取多服务器概览返回
+#[derive(Debug, Serialize)]
+pub struct GetFleetSummaryResult {
+    pub servers: Vec<ServerSummary>, // 按异常程度排序：不可达在前，其余按资源使用率从高到低
+    pub totals: FleetTotals,
+}
+
+/// 并发采样多台服务器，返回精简的概览
+///
+/// 只采样已连接的服务器，未连接的服务器标记为 `disconnected`。并发数受 `concurrency` 限制，
+/// 单台服务器超过 `timeout_secs` 未返回时标记为 `timeout`，不影响其他服务器。
+/// 每台服务器间隔 1 秒采样两次，CPU 使用率和网络速率为这段时间内的值（间隔计入超时）；
+/// 概览采样不影响后台监控的采样记录、历史数据和告警状态。
+///
+/// # 命令名称
+/// `get_fleet_summary`
+///
+/// # 参数
+/// - `group_name`: 服务器分组（可选）
+/// - `server_ids`: 指定服务器（可选）
+/// - `concurrency`: 并发采样数（可选）
+/// - `timeout_secs`: 单台服务器超时时间（可选）
+///
+/// # 返回
+/// - `servers`: 各服务器概览
+/// - `totals`: 汇总
+#[tauri::command]
+pub async fn get_fleet_summary(params: GetFleetSummaryParams) -> Result<GetFleetSummaryResult, String> {
+    let servers = {
+        let db = get_db();
+        let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+        list_server_names(&conn, params.group_name.as_deref())
+            .map_err(|e| format!("查询服务器列表失败: {}", e))?
+    };
+    let servers: Vec<_> = match &params.server_ids {
+        Some(ids) => servers.into_iter().filter(|(id, _, _)| ids.contains(id)).collect(),
+        None => servers,
+    };
+
+    let concurrency = params.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+    let timeout = Duration::from_secs(params.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS).max(MIN_TIMEOUT_SECS));
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+
+    let mut tasks = JoinSet::new();
+    // 任务 ID -> 服务器，任务异常退出（panic）时据此生成错误概览
+    let mut task_servers = HashMap::new();
+    for (server_id, name, group_name) in servers {
+        let semaphore = semaphore.clone();
+        let summary = ServerSummary::new(server_id, name, group_name);
+        let fallback = summary.clone();
+        let handle = tasks.spawn(async move {
+            if !is_connected(&summary.server_id) {
+                return summary.failed("disconnected", None);
+            }
+
+            // 超时从获得并发许可后开始计算，排队时间不计入
+            let _permit = semaphore.acquire_owned().await;
+            match tokio::time::timeout(timeout, sample_twice(&summary.server_id)).await {
+                Ok(Ok((data, latency))) => summary.sampled(&data, latency),
+                Ok(Err(e)) => summary.failed("error", Some(e)),
+                Err(_) => summary.failed("timeout", Some(format!("采样超时（{} 秒）", timeout.as_secs()))),
+            }
+        });
+        task_servers.insert(handle.id(), fallback);
+    }
+
+    let mut summaries = Vec::new();
+    while let Some(result) = tasks.join_next_with_id().await {
+        match result {
+            Ok((_, summary)) => summaries.push(summary),
+            Err(e) => {
+                if let Some(fallback) = task_servers.remove(&e.id()) {
+                    summaries.push(fallback.failed("error", Some(format!("采样任务异常退出: {}", e))));
+                }
+            }
+        }
+    }
+    summaries.sort_by(|a, b| {
+        b.severity()
+            .partial_cmp(&a.severity())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let totals = fleet_totals(&summaries);
+    Ok(GetFleetSummaryResult {
+        servers: summaries,
+        totals,
+    })
+}
+
+/// 以本地基准连续采样两次，返回第二次的数据和第一次采样的耗时
+///
+/// 不使用也不更新后台监控的采样记录，不写入历史数据，也不评估告警规则。
+async fn sample_twice(server_id: &str) -> Result<(SystemMonitorData, Duration), String> {
+    let started = Instant::now();
+    let (_, baseline) = sample_system_monitor(server_id, None).await?;
+    let latency = started.elapsed();
+
+    tokio::time::sleep(Duration::from_millis(BASELINE_INTERVAL_MS)).await;
+    let (data, _) = sample_system_monitor(server_id, Some(&baseline)).await?;
+    Ok((data, latency))
+}
+
+impl ServerSummary {
+    fn new(server_id: String, name: String, group_name: Option<String>) -> Self {
+        ServerSummary {
+            server_id,
+            name,
+            group_name,
+            status: String::new(),
+            reachable: false,
+            cpu: None,
+            memory: None,
+            disk: None,
+            disk_mount: None,
+            load1: None,
+            cores: None,
+            latency_ms: None,
+            error: None,
+        }
+    }
+
+    /// 填入采样结果
+    fn sampled(mut self, data: &SystemMonitorData, elapsed: Duration) -> Self {
+        let worst_disk = data
+            .disk
+            .iter()
+            .max_by(|a, b| a.usage.partial_cmp(&b.usage).unwrap_or(std::cmp::Ordering::Equal));
+
+        self.status = "ok".to_string();
+        self.reachable = true;
+        self.cpu = Some(data.cpu.usage);
+        self.memory = (data.memory.total > 0).then(|| data.memory.used as f64 * 100.0 / data.memory.total as f64);
+        self.disk = worst_disk.map(|d| d.usage);
+        self.disk_mount = worst_disk.map(|d| d.mount.clone());
+        self.load1 = data.cpu.load_average.split(',').next().and_then(|v| v.trim().parse().ok());
+        self.cores = Some(data.cpu.cores);
+        self.latency_ms = Some(elapsed.as_millis() as u64);
+        self
+    }
+
+    /// 标记为采样失败
+    fn failed(mut self, status: &str, error: Option<String>) -> Self {
+        self.status = status.to_string();
+        self.error = error;
+        self
+    }
+
+    /// 异常程度：不可达的服务器最高，其余取 CPU、内存、磁盘使用率及每核负载中的最大值
+    fn severity(&self) -> f64 {
+        if !self.reachable {
+            return f64::MAX;
+        }
+        let load = match (self.load1, self.cores) {
+            (Some(load), Some(cores)) if cores > 0 => load * 100.0 / cores as f64,
+            _ => 0.0,
+        };
+        [self.cpu, self.memory, self.disk, Some(load)]
+            .iter()
+            .flatten()
+            .fold(0.0, |max, v| f64::max(max, *v))
+    }
+}
+
+/// 计算汇总数据
+fn fleet_totals(summaries: &[ServerSummary]) -> FleetTotals {
+    let reachable: Vec<&ServerSummary> = summaries.iter().filter(|s| s.reachable).collect();
+    let values = |f: fn(&ServerSummary) -> Option<f64>| reachable.iter().filter_map(|s| f(s)).collect::<Vec<f64>>();
+    let avg = |v: &[f64]| (!v.is_empty()).then(|| v.iter().sum::<f64>() / v.len() as f64);
+    let max = |v: &[f64]| v.iter().copied().reduce(f64::max);
+
+    let cpu = values(|s| s.cpu);
+    let memory = values(|s| s.memory);
+    let disk = values(|s| s.disk);
+
+    FleetTotals {
+        total: summaries.len(),
+        reachable: reachable.len(),
+        unreachable: summaries.len() - reachable.len(),
+        avg_cpu: avg(&cpu),
+        max_cpu: max(&cpu),
+        avg_memory: avg(&memory),
+        max_memory: max(&memory),
+        max_disk: max(&disk),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{CpuInfo, DiskInfo, MemoryInfo, NetworkInfo, SensorInfo};
+
+    fn disk(mount: &str, usage: f64) -> DiskInfo {
+        DiskInfo {
+            mount: mount.to_string(),
+            filesystem: "ext4".to_string(),
+            total: 100,
+            used: usage as u64,
+            available: 100 - usage as u64,
+            usage,
+        }
+    }
+
+    fn monitor_data(load_average: &str, disks: Vec<DiskInfo>) -> SystemMonitorData {
+        SystemMonitorData {
+            cpu: CpuInfo {
+                usage: 12.5,
+                cores: 4,
+                frequency: 2400.0,
+                load_average: load_average.to_string(),
+                cores_usage: vec![10.0, 15.0, 12.0, 13.0],
+            },
+            memory: MemoryInfo {
+                total: 1000,
+                used: 250,
+                cached: None,
+                available: 750,
+            },
+            disk: disks,
+            network: NetworkInfo {
+                download: 0,
+                upload: 0,
+                download_total: 0,
+                upload_total: 0,
+            },
+            interfaces: Vec::new(),
+            disk_io: Vec::new(),
+            sensors: SensorInfo::default(),
+        }
+    }
+
+    fn summary(name: &str) -> ServerSummary {
+        ServerSummary::new(format!("id-{}", name), name.to_string(), None)
+    }
+
+    #[test]
+    fn sampled_picks_worst_disk_and_parses_load() {
+        let data = monitor_data("1.50, 0.80, 0.30", vec![disk("/", 40.0), disk("/data", 91.0), disk("/boot", 12.0)]);
+        let s = summary("web").sampled(&data, Duration::from_millis(120));
+
+        assert_eq!(s.status, "ok");
+        assert!(s.reachable);
+        assert_eq!(s.cpu, Some(12.5));
+        assert_eq!(s.memory, Some(25.0));
+        assert_eq!(s.disk, Some(91.0));
+        assert_eq!(s.disk_mount.as_deref(), Some("/data"));
+        assert_eq!(s.load1, Some(1.5));
+        assert_eq!(s.cores, Some(4));
+        assert_eq!(s.latency_ms, Some(120));
+    }
+
+    #[test]
+    fn sampled_tolerates_missing_disks_and_bad_load() {
+        let mut data = monitor_data("", Vec::new());
+        data.memory.total = 0;
+        let s = summary("db").sampled(&data, Duration::ZERO);
+
+        assert_eq!(s.memory, None);
+        assert_eq!(s.disk, None);
+        assert_eq!(s.disk_mount, None);
+        assert_eq!(s.load1, None);
+    }
+
+    #[test]
+    fn severity_ranks_unreachable_first_then_worst_metric() {
+        let unreachable = summary("down").failed("timeout", None);
+        assert_eq!(unreachable.severity(), f64::MAX);
+
+        let data = monitor_data("6.0, 1.0, 1.0", vec![disk("/", 40.0)]);
+        // 每核负载 6.0 / 4 核 = 150%，高于其他指标
+        assert_eq!(summary("busy").sampled(&data, Duration::ZERO).severity(), 150.0);
+
+        let data = monitor_data("0.4, 1.0, 1.0", vec![disk("/", 40.0)]);
+        assert_eq!(summary("idle").sampled(&data, Duration::ZERO).severity(), 40.0);
+    }
+
+    #[test]
+    fn totals_only_count_reachable_servers() {
+        let mut a = summary("a").sampled(&monitor_data("0.1", vec![disk("/", 30.0)]), Duration::ZERO);
+        a.cpu = Some(20.0);
+        let mut b = summary("b").sampled(&monitor_data("0.1", vec![disk("/", 70.0)]), Duration::ZERO);
+        b.cpu = Some(60.0);
+        b.memory = Some(75.0);
+        let c = summary("c").failed("disconnected", None);
+
+        let totals = fleet_totals(&[a, b, c]);
+        assert_eq!(totals.total, 3);
+        assert_eq!(totals.reachable, 2);
+        assert_eq!(totals.unreachable, 1);
+        assert_eq!(totals.avg_cpu, Some(40.0));
+        assert_eq!(totals.max_cpu, Some(60.0));
+        assert_eq!(totals.avg_memory, Some(50.0));
+        assert_eq!(totals.max_memory, Some(75.0));
+        assert_eq!(totals.max_disk, Some(70.0));
+
+        let empty = fleet_totals(&[]);
+        assert_eq!(empty.total, 0);
+        assert_eq!(empty.avg_cpu, None);
+        assert_eq!(empty.max_disk, None);
+    }
+}
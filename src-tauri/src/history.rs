@@ -0,0 +1,367 @@
+/**
+ * 监控指标历史记录相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use rusqlite::{params, Connection};
+use std::sync::Mutex;
+use crate::db::get_db;
+use crate::monitor::SystemMonitorData;
+use crate::util::unix_now;
+
+/// 聚合粒度及保留时长（秒）：10 秒粒度保留 1 天，1 分钟粒度保留 7 天，1 小时粒度保留 90 天
+const RESOLUTIONS: &[(i64, i64)] = &[(10, 86400), (60, 7 * 86400), (3600, 90 * 86400)];
+/// 清理过期数据的最小间隔（秒）
+const RETENTION_INTERVAL_SECS: i64 = 600;
+/// 默认最大返回点数
+const DEFAULT_MAX_POINTS: i64 = 500;
+
+lazy_static::lazy_static! {
+    static ref LAST_RETENTION: Mutex<i64> = Mutex::new(0);
+}
+
+/// 单个时间点的指标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricPoint {
+    pub cpu: f64,       // CPU 使用率 (0-100)
+    pub memory: f64,    // 内存使用率 (0-100)
+    pub load1: f64,     // 1 分钟负载
+    pub download: f64,  // 下载速度 (字节/秒)
+    pub upload: f64,    // 上传速度 (字节/秒)
+    pub disk: f64,      // 使用率最高的磁盘的使用率 (0-100)
+}
+
+impl From<&SystemMonitorData> for MetricPoint {
+    fn from(data: &SystemMonitorData) -> Self {
+        let memory = if data.memory.total > 0 {
+            data.memory.used as f64 * 100.0 / data.memory.total as f64
+        } else {
+            0.0
+        };
+        let load1 = data
+            .cpu
+            .load_average
+            .split(',')
+            .next()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(0.0);
+        let disk = data.disk.iter().map(|d| d.usage).fold(0.0, f64::max);
+
+        MetricPoint {
+            cpu: data.cpu.usage,
+            memory,
+            load1,
+            download: data.network.download as f64,
+            upload: data.network.upload as f64,
+            disk,
+        }
+    }
+}
+
+/// 获取历史指标参数
+#[derive(Debug, Deserialize)]
+pub struct GetMetricHistoryParams {
+    pub server_id: String,
+    pub start: i64,               // 开始时间（Unix 时间戳，秒）
+    pub end: Option<i64>,         // 结束时间（默认当前时间）
+    pub max_points: Option<i64>,  // 最大返回点数（默认 500）
+}
+
+/// 历史指标序列（各数组与 `timestamps` 一一对应）
+#[derive(Debug, Default, Serialize)]
+pub struct MetricHistory {
+    pub resolution: i64,      // 数据粒度（秒）
+    pub timestamps: Vec<i64>, // 时间点（Unix 时间戳，秒）
+    pub cpu: Vec<f64>,
+    pub memory: Vec<f64>,
+    pub load1: Vec<f64>,
+    pub download: Vec<f64>,
+    pub upload: Vec<f64>,
+    pub disk: Vec<f64>,
+}
+
+/// 清除历史指标参数
+#[derive(Debug, Deserialize)]
+pub struct ClearMetricHistoryParams {
+    pub server_id: String,
+}
+
+/// 清除历史指标返回
+#[derive(Debug, Serialize)]
+pub struct ClearMetricHistoryResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 获取服务器的历史监控指标
+///
+/// 根据时间范围和最大点数自动选择数据粒度，时间跨度越大粒度越粗。
+///
+/// # 命令名称
+/// `get_metric_history`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `start`: 开始时间
+/// - `end`: 结束时间（可选）
+/// - `max_points`: 最大返回点数（可选）
+///
+/// # 返回
+/// - `resolution`: 数据粒度（秒）
+/// - `timestamps`: 时间点数组
+/// - `cpu` / `memory` / `load1` / `download` / `upload` / `disk`: 各指标数组
+#[tauri::command]
+pub async fn get_metric_history(params: GetMetricHistoryParams) -> Result<MetricHistory, String> {
+    let now = unix_now();
+    let end = params.end.unwrap_or(now);
+    if end <= params.start {
+        return Err("结束时间必须晚于开始时间".to_string());
+    }
+
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    query_history(
+        &conn,
+        &params.server_id,
+        params.start,
+        end,
+        params.max_points.unwrap_or(DEFAULT_MAX_POINTS).max(1),
+        now,
+    )
+    .map_err(|e| format!("查询历史数据失败: {}", e))
+}
+
+/// 清除服务器的历史监控指标
+///
+/// # 命令名称
+/// `clear_metric_history`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn clear_metric_history(params: ClearMetricHistoryParams) -> Result<ClearMetricHistoryResult, String> {
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    conn.execute("DELETE FROM metric_samples WHERE server_id = ?1", params![params.server_id])
+        .map_err(|e| format!("清除历史数据失败: {}", e))?;
+
+    Ok(ClearMetricHistoryResult {
+        success: true,
+        message: Some("已清除历史数据".to_string()),
+    })
+}
+
+/// 保存一次监控采样（失败只记录日志，不影响监控本身）
+pub(crate) fn record_monitor_sample(server_id: &str, data: &SystemMonitorData) {
+    let now = unix_now();
+    let db = get_db();
+    let conn = match db.lock() {
+        Ok(conn) => conn,
+        Err(_) => return,
+    };
+
+    if let Err(e) = record_sample(&conn, server_id, now, &MetricPoint::from(data)) {
+        eprintln!("保存监控数据失败: {}", e);
+    }
+
+    let mut last = LAST_RETENTION.lock().unwrap();
+    if now - *last >= RETENTION_INTERVAL_SECS {
+        *last = now;
+        if let Err(e) = apply_retention(&conn, now) {
+            eprintln!("清理过期监控数据失败: {}", e);
+        }
+    }
+}
+
+/// 写入一个采样点，同时累加到各粒度的时间桶中（桶内取平均值）
+pub(crate) fn record_sample(conn: &Connection, server_id: &str, ts: i64, point: &MetricPoint) -> rusqlite::Result<()> {
+    for (resolution, _) in RESOLUTIONS {
+        let bucket = ts - ts.rem_euclid(*resolution);
+        conn.execute(
+            "INSERT INTO metric_samples (server_id, resolution, ts, cpu, memory, load1, download, upload, disk, samples)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 1)
+             ON CONFLICT(server_id, resolution, ts) DO UPDATE SET
+                cpu = (cpu * samples + excluded.cpu) / (samples + 1),
+                memory = (memory * samples + excluded.memory) / (samples + 1),
+                load1 = (load1 * samples + excluded.load1) / (samples + 1),
+                download = (download * samples + excluded.download) / (samples + 1),
+                upload = (upload * samples + excluded.upload) / (samples + 1),
+                disk = (disk * samples + excluded.disk) / (samples + 1),
+                samples = samples + 1",
+            params![
+                server_id,
+                resolution,
+                bucket,
+                point.cpu,
+                point.memory,
+                point.load1,
+                point.download,
+                point.upload,
+                point.disk
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// 按各粒度的保留时长删除过期数据
+pub(crate) fn apply_retention(conn: &Connection, now: i64) -> rusqlite::Result<()> {
+    for (resolution, retention) in RESOLUTIONS {
+        conn.execute(
+            "DELETE FROM metric_samples WHERE resolution = ?1 AND ts < ?2",
+            params![resolution, now - retention],
+        )?;
+    }
+    Ok(())
+}
+
+/// 选择数据粒度：取保留时长覆盖开始时间、且点数不超过上限的最细粒度，都不满足时取最粗粒度
+pub(crate) fn choose_resolution(start: i64, end: i64, max_points: i64, now: i64) -> i64 {
+    RESOLUTIONS
+        .iter()
+        .find(|(resolution, retention)| start >= now - retention && (end - start) / resolution <= max_points)
+        .or_else(|| RESOLUTIONS.last())
+        .map(|(resolution, _)| *resolution)
+        .unwrap_or(60)
+}
+
+/// 查询时间范围内的历史指标
+pub(crate) fn query_history(conn: &Connection, server_id: &str, start: i64, end: i64, max_points: i64, now: i64) -> rusqlite::Result<MetricHistory> {
+    let resolution = choose_resolution(start, end, max_points, now);
+    let mut stmt = conn.prepare(
+        "SELECT ts, cpu, memory, load1, download, upload, disk FROM metric_samples
+         WHERE server_id = ?1 AND resolution = ?2 AND ts >= ?3 AND ts <= ?4
+         ORDER BY ts",
+    )?;
+    let mut rows = stmt.query(params![server_id, resolution, start - start.rem_euclid(resolution), end])?;
+
+    let mut history = MetricHistory {
+        resolution,
+        ..Default::default()
+    };
+    while let Some(row) = rows.next()? {
+        history.timestamps.push(row.get(0)?);
+        history.cpu.push(row.get(1)?);
+        history.memory.push(row.get(2)?);
+        history.load1.push(row.get(3)?);
+        history.download.push(row.get(4)?);
+        history.upload.push(row.get(5)?);
+        history.disk.push(row.get(6)?);
+    }
+
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    fn test_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE metric_samples (
+                server_id TEXT NOT NULL,
+                resolution INTEGER NOT NULL,
+                ts INTEGER NOT NULL,
+                cpu REAL NOT NULL,
+                memory REAL NOT NULL,
+                load1 REAL NOT NULL,
+                download REAL NOT NULL,
+                upload REAL NOT NULL,
+                disk REAL NOT NULL,
+                samples INTEGER NOT NULL,
+                PRIMARY KEY (server_id, resolution, ts)
+            )",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn point(cpu: f64) -> MetricPoint {
+        MetricPoint { cpu, memory: cpu / 2.0, load1: 1.0, download: 100.0, upload: 10.0, disk: 50.0 }
+    }
+
+    /// 某一粒度下的 (ts, cpu, samples) 行
+    fn rows(conn: &Connection, resolution: i64) -> Vec<(i64, f64, i64)> {
+        let mut stmt = conn
+            .prepare("SELECT ts, cpu, samples FROM metric_samples WHERE resolution = ?1 ORDER BY ts")
+            .unwrap();
+        let rows = stmt
+            .query_map(params![resolution], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap();
+        rows.map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn averages_samples_within_each_bucket() {
+        let conn = test_conn();
+        record_sample(&conn, "srv", 100, &point(10.0)).unwrap();
+        record_sample(&conn, "srv", 105, &point(30.0)).unwrap();
+        record_sample(&conn, "srv", 112, &point(50.0)).unwrap();
+        record_sample(&conn, "other", 101, &point(90.0)).unwrap();
+
+        let history = query_history(&conn, "srv", 100, 119, 500, 200).unwrap();
+        assert_eq!(history.resolution, 10);
+        assert_eq!(history.timestamps, vec![100, 110]);
+        assert_eq!(history.cpu, vec![20.0, 50.0]);
+        assert_eq!(history.memory, vec![10.0, 25.0]);
+
+        // 1 分钟和 1 小时的桶包含全部三个采样
+        let minute = query_history(&conn, "srv", 60, 119, 1, 200).unwrap();
+        assert_eq!(minute.resolution, 60);
+        assert_eq!(minute.timestamps, vec![60]);
+        assert_eq!(minute.cpu, vec![30.0]);
+        assert!(rows(&conn, 3600).contains(&(0, 30.0, 3)));
+    }
+
+    #[test]
+    fn retention_drops_old_rows_per_resolution() {
+        let conn = test_conn();
+        let now = 100 * DAY;
+        for age in [DAY / 2, 2 * DAY, 10 * DAY, 100 * DAY] {
+            record_sample(&conn, "srv", now - age, &point(1.0)).unwrap();
+        }
+
+        apply_retention(&conn, now).unwrap();
+        let ages = |resolution: i64| -> Vec<i64> {
+            let mut ages: Vec<i64> = rows(&conn, resolution).iter().map(|r| now - r.0).collect();
+            ages.sort();
+            ages
+        };
+        assert_eq!(ages(10), vec![DAY / 2]);
+        assert_eq!(ages(60), vec![DAY / 2, 2 * DAY]);
+        assert_eq!(ages(3600), vec![DAY / 2, 2 * DAY, 10 * DAY]);
+    }
+
+    #[test]
+    fn chooses_finest_resolution_within_point_limit() {
+        let now = 100 * DAY;
+        // 500 个 10 秒点正好达到上限
+        assert_eq!(choose_resolution(now - 5000, now, 500, now), 10);
+        assert_eq!(choose_resolution(now - 5010, now, 500, now), 60);
+        assert_eq!(choose_resolution(now - 30_000, now, 500, now), 60);
+        assert_eq!(choose_resolution(now - 30_060, now, 500, now), 3600);
+        // 开始时间超出保留时长时改用更粗的粒度
+        assert_eq!(choose_resolution(now - 2 * DAY, now - 2 * DAY + 600, 500, now), 60);
+        assert_eq!(choose_resolution(now - 8 * DAY, now - 8 * DAY + 600, 500, now), 3600);
+        assert_eq!(choose_resolution(now - 200 * DAY, now, 500, now), 3600);
+    }
+
+    #[test]
+    fn query_aligns_start_to_bucket() {
+        let conn = test_conn();
+        record_sample(&conn, "srv", 100, &point(10.0)).unwrap();
+        record_sample(&conn, "srv", 123, &point(20.0)).unwrap();
+
+        let history = query_history(&conn, "srv", 105, 125, 500, 200).unwrap();
+        assert_eq!(history.timestamps, vec![100, 120]);
+        assert!(query_history(&conn, "missing", 0, 200, 500, 200).unwrap().timestamps.is_empty());
+    }
+}
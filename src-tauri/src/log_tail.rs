@@ -0,0 +1,288 @@
+/**
+ * 远程日志实时跟踪相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use russh::ChannelMsg;
+use tauri::Window;
+use crate::ssh::{shell_quote, RemoteProcess};
+use crate::util::TaskRegistry;
+
+/// 默认初始读取行数
+const DEFAULT_INITIAL_LINES: u32 = 200;
+
+/// 新日志行事件名
+const LOG_TAIL_LINES_EVENT: &str = "log-tail-lines";
+/// 日志提示事件名（文件轮转、截断、暂时不可访问等）
+const LOG_TAIL_NOTICE_EVENT: &str = "log-tail-notice";
+/// 跟踪结束事件名
+const LOG_TAIL_STOPPED_EVENT: &str = "log-tail-stopped";
+
+lazy_static::lazy_static! {
+    /// 正在运行的跟踪任务（tail_id -> 停止信号）
+    static ref TAIL_TASKS: TaskRegistry = TaskRegistry::new();
+}
+
+/// 新日志行事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct LogTailLinesEvent {
+    pub tail_id: String,
+    pub lines: Vec<String>,
+}
+
+/// 日志提示事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct LogTailNoticeEvent {
+    pub tail_id: String,
+    pub kind: String, // "rotated" | "truncated" | "unavailable" | "other"
+    pub message: String,
+}
+
+/// 跟踪结束事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct LogTailStoppedEvent {
+    pub tail_id: String,
+    pub stopped: bool,  // 是否由用户停止（否则为远程进程退出或连接断开）
+}
+
+/// 开始跟踪日志参数
+#[derive(Debug, Deserialize)]
+pub struct StartLogTailParams {
+    pub server_id: String,
+    pub tail_id: String,              // 跟踪ID（由前端生成，用于匹配事件）
+    pub path: String,                 // 远程日志文件路径
+    pub initial_lines: Option<u32>,   // 先输出文件末尾 N 行（默认 200）
+    pub filter: Option<String>,       // 服务端过滤正则（扩展正则）
+    pub case_sensitive: Option<bool>, // 过滤是否区分大小写（默认不区分）
+}
+
+/// 开始跟踪日志返回
+#[derive(Debug, Serialize)]
+pub struct StartLogTailResult {
+    pub success: bool,
+    pub tail_id: String,
+}
+
+/// 停止跟踪日志参数
+#[derive(Debug, Deserialize)]
+pub struct StopLogTailParams {
+    pub tail_id: String,
+}
+
+/// 停止跟踪日志返回
+#[derive(Debug, Serialize)]
+pub struct StopLogTailResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 开始实时跟踪远程日志文件
+///
+/// 在远程服务器上执行 `tail -F`，文件被轮转或重新创建后会自动跟踪新文件。
+/// 新行通过 `log-tail-lines` 事件推送，轮转等提示通过 `log-tail-notice` 事件推送，
+/// 结束时推送 `log-tail-stopped` 事件。每个跟踪使用独立的通道，同一连接上可同时运行多个。
+///
+/// # 命令名称
+/// `start_log_tail`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `tail_id`: 跟踪ID
+/// - `path`: 远程日志文件路径
+/// - `initial_lines`: 初始读取的末尾行数（可选）
+/// - `filter`: 过滤正则（可选）
+/// - `case_sensitive`: 过滤是否区分大小写（可选）
+///
+/// # 返回
+/// - `success`: 是否成功启动
+/// - `tail_id`: 跟踪ID
+#[tauri::command]
+pub async fn start_log_tail(window: Window, params: StartLogTailParams) -> Result<StartLogTailResult, String> {
+    if params.path.trim().is_empty() {
+        return Err("日志路径不能为空".to_string());
+    }
+
+    let (generation, mut stop_rx) = TAIL_TASKS
+        .register(&params.tail_id)
+        .ok_or_else(|| "跟踪任务已存在".to_string())?;
+
+    let command = build_tail_command(&params);
+    let mut process = match RemoteProcess::spawn(&params.server_id, &command).await {
+        Ok(process) => process,
+        Err(e) => {
+            TAIL_TASKS.finish(&params.tail_id, generation);
+            return Err(e);
+        }
+    };
+
+    let tail_id = params.tail_id.clone();
+    tokio::spawn(async move {
+        let mut stdout: Vec<u8> = Vec::new();
+        let mut stderr: Vec<u8> = Vec::new();
+        let mut stopped = false;
+
+        loop {
+            let msg = tokio::select! {
+                msg = process.wait() => msg,
+                _ = &mut stop_rx => {
+                    stopped = true;
+                    break;
+                }
+            };
+
+            match msg {
+                Some(ChannelMsg::Data { data }) => {
+                    stdout.extend_from_slice(&data);
+                    let lines = take_complete_lines(&mut stdout);
+                    if !lines.is_empty() {
+                        let _ = window.emit(LOG_TAIL_LINES_EVENT, LogTailLinesEvent {
+                            tail_id: tail_id.clone(),
+                            lines,
+                        });
+                    }
+                }
+                Some(ChannelMsg::ExtendedData { data, ext: 1 }) => {
+                    stderr.extend_from_slice(&data);
+                    for message in take_complete_lines(&mut stderr) {
+                        let _ = window.emit(LOG_TAIL_NOTICE_EVENT, LogTailNoticeEvent {
+                            tail_id: tail_id.clone(),
+                            kind: classify_tail_notice(&message).to_string(),
+                            message,
+                        });
+                    }
+                }
+                Some(ChannelMsg::Close) | None => {
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        // 推送剩余的不完整行
+        if !stopped && !stdout.is_empty() {
+            let _ = window.emit(LOG_TAIL_LINES_EVENT, LogTailLinesEvent {
+                tail_id: tail_id.clone(),
+                lines: vec![String::from_utf8_lossy(&stdout).to_string()],
+            });
+        }
+
+        // 用户停止时结束远程 tail（及 grep），仅关闭通道的话安静的日志会让它们一直残留
+        process.close(stopped).await;
+        TAIL_TASKS.finish(&tail_id, generation);
+
+        let _ = window.emit(LOG_TAIL_STOPPED_EVENT, LogTailStoppedEvent { tail_id, stopped });
+    });
+
+    Ok(StartLogTailResult {
+        success: true,
+        tail_id: params.tail_id,
+    })
+}
+
+/// 停止跟踪远程日志文件
+///
+/// # 命令名称
+/// `stop_log_tail`
+///
+/// # 参数
+/// - `tail_id`: 跟踪ID
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn stop_log_tail(params: StopLogTailParams) -> Result<StopLogTailResult, String> {
+    let message = if TAIL_TASKS.stop(&params.tail_id) { "已停止跟踪" } else { "跟踪已结束" };
+    Ok(StopLogTailResult {
+        success: true,
+        message: Some(message.to_string()),
+    })
+}
+
+/// 构建 tail 命令
+///
+/// `-F` 按文件名跟踪并在文件轮转后重新打开；过滤时 grep 必须按行刷新，否则结果会被缓冲。
+fn build_tail_command(params: &StartLogTailParams) -> String {
+    let lines = params.initial_lines.unwrap_or(DEFAULT_INITIAL_LINES);
+    let mut command = format!("tail -n {} -F {}", lines, shell_quote(&params.path));
+
+    if let Some(filter) = params.filter.as_deref().filter(|f| !f.is_empty()) {
+        let flags = if params.case_sensitive.unwrap_or(false) { "-E" } else { "-iE" };
+        command.push_str(&format!(" | grep --line-buffered {} -e {}", flags, shell_quote(filter)));
+    }
+
+    command
+}
+
+/// 从缓冲区取出所有完整的行，不完整的尾部数据保留在缓冲区中
+pub(crate) fn take_complete_lines(buffer: &mut Vec<u8>) -> Vec<String> {
+    let end = match buffer.iter().rposition(|b| *b == b'\n') {
+        Some(pos) => pos + 1,
+        None => return Vec::new(),
+    };
+
+    let complete: Vec<u8> = buffer.drain(..end).collect();
+    String::from_utf8_lossy(&complete[..complete.len() - 1])
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+        .collect()
+}
+
+/// 根据 tail 的提示信息判断事件类型
+fn classify_tail_notice(message: &str) -> &'static str {
+    if message.contains("has been replaced") || message.contains("has appeared") {
+        "rotated"
+    } else if message.contains("file truncated") {
+        "truncated"
+    } else if message.contains("has become inaccessible") || message.contains("cannot open") {
+        "unavailable"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_partial_line_in_buffer() {
+        let mut buffer = b"first\nsecond\nthi".to_vec();
+        assert_eq!(take_complete_lines(&mut buffer), vec!["first", "second"]);
+        assert_eq!(buffer, b"thi");
+
+        assert!(take_complete_lines(&mut buffer).is_empty());
+        buffer.extend_from_slice(b"rd\n");
+        assert_eq!(take_complete_lines(&mut buffer), vec!["third"]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn strips_crlf_and_keeps_empty_lines() {
+        let mut buffer = b"a\r\n\r\nb\n\n".to_vec();
+        assert_eq!(take_complete_lines(&mut buffer), vec!["a", "", "b", ""]);
+    }
+
+    #[test]
+    fn replaces_invalid_utf8_and_waits_for_split_characters() {
+        let mut buffer = b"bad \xff byte\n".to_vec();
+        assert_eq!(take_complete_lines(&mut buffer), vec!["bad \u{fffd} byte"]);
+
+        // 多字节字符被分在两个数据块中时，前半部分留在缓冲区等待后续数据
+        let text = "日志\n".as_bytes();
+        let mut buffer = text[..2].to_vec();
+        assert!(take_complete_lines(&mut buffer).is_empty());
+        buffer.extend_from_slice(&text[2..]);
+        assert_eq!(take_complete_lines(&mut buffer), vec!["日志"]);
+    }
+
+    #[test]
+    fn classifies_tail_notices() {
+        assert_eq!(classify_tail_notice("tail: '/var/log/app.log' has been replaced;  following new file"), "rotated");
+        assert_eq!(classify_tail_notice("tail: '/var/log/app.log' has appeared;  following new file"), "rotated");
+        assert_eq!(classify_tail_notice("tail: /var/log/app.log: file truncated"), "truncated");
+        assert_eq!(classify_tail_notice("tail: '/var/log/app.log' has become inaccessible: No such file or directory"), "unavailable");
+        assert_eq!(classify_tail_notice("tail: cannot open '/var/log/app.log' for reading: Permission denied"), "unavailable");
+        assert_eq!(classify_tail_notice("grep: invalid regular expression"), "other");
+    }
+}
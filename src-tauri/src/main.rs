@@ -4,9 +4,31 @@
 mod ssh;
 mod file;
 mod monitor;
+mod monitor_bsd;
 mod server;
 mod ai;
+mod ai_provider;
 mod db;
+mod search;
+mod archive;
+mod sync;
+mod checksum;
+mod remote_copy;
+mod scp;
+mod throttle;
+mod log_tail;
+mod disk_usage;
+mod history;
+mod alerts;
+mod process;
+mod system_info;
+mod systemd;
+mod docker;
+mod ports;
+mod fleet;
+mod metrics_exporter;
+mod secret;
+mod util;
 
 use tauri::Manager;
 
@@ -27,8 +49,71 @@ fn main() {
       file::delete_files,
       file::rename_file,
       file::change_file_mode,
+      // 文件搜索相关命令
+      search::search_remote_files,
+      search::cancel_file_search,
+      // 远程压缩/解压相关命令
+      archive::get_archive_tools,
+      archive::create_remote_archive,
+      archive::extract_remote_archive,
+      // 目录同步相关命令
+      sync::plan_directory_sync,
+      sync::apply_sync_plan,
+      sync::discard_sync_plan,
+      // 校验和相关命令
+      checksum::compute_remote_checksums,
+      // 服务器间复制相关命令
+      remote_copy::copy_between_servers,
+      // 传输限速相关命令
+      throttle::set_global_bandwidth_limit,
+      throttle::set_transfer_bandwidth_limit,
+      throttle::get_bandwidth_limits,
+      // 日志跟踪相关命令
+      log_tail::start_log_tail,
+      log_tail::stop_log_tail,
+      // 磁盘占用分析相关命令
+      disk_usage::analyze_disk_usage,
       // 系统监控相关命令
       monitor::get_system_monitor,
+      monitor::start_monitoring,
+      monitor::stop_monitoring,
+      // 多服务器概览相关命令
+      fleet::get_fleet_summary,
+      // 指标导出相关命令
+      metrics_exporter::get_metrics_exporter,
+      metrics_exporter::set_metrics_exporter,
+      // 监控历史数据相关命令
+      history::get_metric_history,
+      history::clear_metric_history,
+      // 监控告警相关命令
+      alerts::get_alert_rules,
+      alerts::save_alert_rule,
+      alerts::delete_alert_rule,
+      alerts::get_alert_events,
+      alerts::clear_alert_events,
+      // 进程管理相关命令
+      process::list_processes,
+      process::signal_processes,
+      process::renice_process,
+      // 主机信息相关命令
+      system_info::get_system_info,
+      // systemd 服务管理相关命令
+      systemd::list_systemd_units,
+      systemd::get_systemd_unit_status,
+      systemd::manage_systemd_unit,
+      // Docker 容器管理相关命令
+      docker::list_containers,
+      docker::inspect_container,
+      docker::list_images,
+      docker::container_action,
+      docker::get_container_stats,
+      docker::start_container_logs,
+      docker::stop_container_logs,
+      // 端口查看与转发相关命令
+      ports::list_listening_ports,
+      ports::start_port_forward,
+      ports::stop_port_forward,
+      ports::list_port_forwards,
       // 服务器配置 CRUD 命令
       server::get_servers,
       server::save_server,
@@ -37,7 +122,11 @@ fn main() {
       server::get_server,
       // AI 助手相关命令
       ai::chat_with_ai,
+      ai::start_ai_chat,
+      ai::cancel_ai_chat,
       ai::get_ai_quick_actions,
+      ai::get_ai_config,
+      ai::set_ai_config,
     ])
     .setup(|app| {
       // 初始化数据库
@@ -48,6 +137,10 @@ fn main() {
       db::migrate_database(&conn).unwrap_or_else(|e| {
         eprintln!("数据库迁移失败: {}", e);
       });
+      // 告警通知需要应用句柄
+      alerts::set_app_handle(app.handle());
+      // 按已保存的设置启动指标导出服务（默认不启用）
+      tauri::async_runtime::spawn(metrics_exporter::start_exporter_from_settings());
       Ok(())
     })
     .run(tauri::generate_context!())
@@ -0,0 +1,560 @@
+/**
+ * Prometheus 指标导出相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use crate::db::{get_db, get_setting, set_setting};
+use crate::monitor::SystemMonitorData;
+use crate::server::get_server_name_and_group;
+use crate::util::unix_now;
+
+/// 默认监听端口
+const DEFAULT_EXPORTER_PORT: u16 = 9464;
+/// 超过该时长（秒）未更新的采样不再导出
+const STALE_AFTER_SECS: i64 = 300;
+/// 请求头最大长度
+const MAX_REQUEST_BYTES: usize = 8192;
+/// 读取请求超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 设置项：是否启用
+const SETTING_ENABLED: &str = "metrics_exporter.enabled";
+/// 设置项：监听端口
+const SETTING_PORT: &str = "metrics_exporter.port";
+
+/// 正在运行的导出服务
+struct RunningExporter {
+    port: u16,
+    stop: oneshot::Sender<()>,
+    stopped: oneshot::Receiver<()>, // 监听端口关闭后收到信号
+}
+
+lazy_static::lazy_static! {
+    /// 各服务器最近一次采样（server_id -> (采样数据, 采样时间)）
+    static ref LATEST_SAMPLES: Mutex<HashMap<String, (SystemMonitorData, i64)>> = Mutex::new(HashMap::new());
+    static ref EXPORTER: Mutex<Option<RunningExporter>> = Mutex::new(None);
+}
+
+/// 导出服务状态
+#[derive(Debug, Serialize)]
+pub struct MetricsExporterStatus {
+    pub enabled: bool,       // 是否启用（已保存的设置）
+    pub port: u16,           // 监听端口
+    pub running: bool,       // 是否正在运行
+    pub url: Option<String>, // 抓取地址（运行时）
+}
+
+/// 设置导出服务参数
+#[derive(Debug, Deserialize)]
+pub struct SetMetricsExporterParams {
+    pub enabled: bool,
+    pub port: Option<u16>, // 监听端口（默认 9464）
+}
+
+/// 获取 Prometheus 导出服务状态
+///
+/// # 命令名称
+/// `get_metrics_exporter`
+///
+/// # 返回
+/// - `enabled`: 是否启用
+/// - `port`: 监听端口
+/// - `running`: 是否正在运行
+/// - `url`: 抓取地址（可选）
+#[tauri::command]
+pub async fn get_metrics_exporter() -> Result<MetricsExporterStatus, String> {
+    let (enabled, port) = load_settings()?;
+    Ok(exporter_status(enabled, port))
+}
+
+/// 启用或停用 Prometheus 导出服务
+///
+/// 启用后在 `127.0.0.1:<port>/metrics` 以 Prometheus 文本格式导出各服务器最近一次的监控采样，
+/// 标签包含服务器名称和分组。设置会被保存，下次启动时自动恢复。
+/// 已在相同端口运行时不会重启；新端口监听失败时保持原有服务和设置不变。
+///
+/// # 命令名称
+/// `set_metrics_exporter`
+///
+/// # 参数
+/// - `enabled`: 是否启用
+/// - `port`: 监听端口（可选）
+///
+/// # 返回
+/// 导出服务状态
+#[tauri::command]
+pub async fn set_metrics_exporter(params: SetMetricsExporterParams) -> Result<MetricsExporterStatus, String> {
+    let (_, saved_port) = load_settings()?;
+    let port = params.port.unwrap_or(saved_port);
+    if port == 0 {
+        return Err("端口无效".to_string());
+    }
+
+    let running_port = EXPORTER.lock().unwrap().as_ref().map(|exporter| exporter.port);
+    if !params.enabled {
+        stop_exporter().await;
+    } else if running_port != Some(port) {
+        // 先监听新端口再停止旧服务，监听失败时旧服务保持运行、设置保持不变
+        let listener = bind_listener(port).await?;
+        stop_exporter().await;
+        spawn_exporter(port, listener);
+    }
+
+    {
+        let db = get_db();
+        let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+        set_setting(&conn, SETTING_ENABLED, if params.enabled { "1" } else { "0" })
+            .and_then(|_| set_setting(&conn, SETTING_PORT, &port.to_string()))
+            .map_err(|e| format!("保存设置失败: {}", e))?;
+    }
+
+    Ok(exporter_status(params.enabled, port))
+}
+
+/// 应用启动时按已保存的设置启动导出服务（默认不启用）
+pub(crate) async fn start_exporter_from_settings() {
+    match load_settings() {
+        Ok((true, port)) => {
+            if let Err(e) = start_exporter(port).await {
+                eprintln!("启动指标导出服务失败: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("读取指标导出设置失败: {}", e),
+    }
+}
+
+/// 记录服务器最近一次的监控采样
+pub(crate) fn record_latest_sample(server_id: &str, data: &SystemMonitorData) {
+    LATEST_SAMPLES
+        .lock()
+        .unwrap()
+        .insert(server_id.to_string(), (data.clone(), unix_now()));
+}
+
+/// 读取设置（是否启用，端口）
+fn load_settings() -> Result<(bool, u16), String> {
+    let db = get_db();
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    let enabled = get_setting(&conn, SETTING_ENABLED)
+        .map_err(|e| format!("读取设置失败: {}", e))?
+        .map_or(false, |v| v == "1");
+    let port = get_setting(&conn, SETTING_PORT)
+        .map_err(|e| format!("读取设置失败: {}", e))?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_EXPORTER_PORT);
+    Ok((enabled, port))
+}
+
+/// 生成状态信息
+fn exporter_status(enabled: bool, port: u16) -> MetricsExporterStatus {
+    let running_port = EXPORTER.lock().unwrap().as_ref().map(|exporter| exporter.port);
+    MetricsExporterStatus {
+        enabled,
+        port: running_port.unwrap_or(port),
+        running: running_port.is_some(),
+        url: running_port.map(|port| format!("http://127.0.0.1:{}/metrics", port)),
+    }
+}
+
+/// 在 127.0.0.1 上启动导出服务
+async fn start_exporter(port: u16) -> Result<(), String> {
+    let listener = bind_listener(port).await?;
+    spawn_exporter(port, listener);
+    Ok(())
+}
+
+/// 监听 127.0.0.1 上的指定端口
+async fn bind_listener(port: u16) -> Result<TcpListener, String> {
+    TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("无法监听端口 {}: {}", port, e))
+}
+
+/// 在已监听的端口上运行导出服务并登记
+fn spawn_exporter(port: u16, listener: TcpListener) {
+    let (stop_tx, mut stop_rx) = oneshot::channel();
+    let (stopped_tx, stopped_rx) = oneshot::channel();
+    *EXPORTER.lock().unwrap() = Some(RunningExporter { port, stop: stop_tx, stopped: stopped_rx });
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut stop_rx => break,
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        tokio::spawn(handle_request(stream));
+                    }
+                }
+            }
+        }
+        drop(listener);
+        let _ = stopped_tx.send(());
+    });
+}
+
+/// 停止导出服务，等待监听端口关闭后返回
+async fn stop_exporter() {
+    let exporter = EXPORTER.lock().unwrap().take();
+    if let Some(exporter) = exporter {
+        let _ = exporter.stop.send(());
+        let _ = exporter.stopped.await;
+    }
+}
+
+/// 处理一次 HTTP 请求（只支持 `GET /metrics`，响应后关闭连接）
+async fn handle_request(mut stream: TcpStream) {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let read = tokio::time::timeout(REQUEST_TIMEOUT, async {
+        while !buffer.windows(4).any(|w| w == b"\r\n\r\n") && buffer.len() < MAX_REQUEST_BYTES {
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return false,
+                Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            }
+        }
+        true
+    })
+    .await;
+    if !matches!(read, Ok(true)) {
+        return;
+    }
+
+    let request = String::from_utf8_lossy(&buffer);
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("").split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => ("200 OK", "text/plain; version=0.0.4; charset=utf-8", metrics_body()),
+        ("GET", _) => ("404 Not Found", "text/plain; charset=utf-8", "Not Found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain; charset=utf-8", "Method Not Allowed\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// 生成当前所有服务器的指标文本
+fn metrics_body() -> String {
+    let now = unix_now();
+    let samples: Vec<(String, SystemMonitorData, i64)> = LATEST_SAMPLES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, (_, at))| now - at <= STALE_AFTER_SECS)
+        .map(|(id, (data, at))| (id.clone(), data.clone(), *at))
+        .collect();
+
+    // 服务器名称和分组从配置中读取，未保存的服务器使用 ID 作为名称
+    let labeled: Vec<(ServerLabels, SystemMonitorData, i64)> = {
+        let db = get_db();
+        let conn = db.lock().ok();
+        samples
+            .into_iter()
+            .map(|(server_id, data, at)| {
+                let (name, group) = conn
+                    .as_ref()
+                    .and_then(|conn| get_server_name_and_group(conn, &server_id))
+                    .unwrap_or_else(|| (server_id.clone(), None));
+                (ServerLabels { server_id, name, group: group.unwrap_or_default() }, data, at)
+            })
+            .collect()
+    };
+
+    render_metrics(&labeled)
+}
+
+/// 服务器标签
+struct ServerLabels {
+    server_id: String,
+    name: String,
+    group: String,
+}
+
+/// 指标族（同名指标共用 HELP 和 TYPE）
+struct MetricFamily {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    samples: Vec<String>,
+}
+
+/// 按首次出现顺序收集指标族
+#[derive(Default)]
+struct MetricsWriter {
+    families: Vec<MetricFamily>,
+}
+
+impl MetricsWriter {
+    /// 添加一个样本，`labels` 为服务器标签之外的附加标签
+    fn add(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        kind: &'static str,
+        server: &ServerLabels,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        let index = match self.families.iter().position(|family| family.name == name) {
+            Some(index) => index,
+            None => {
+                self.families.push(MetricFamily { name, help, kind, samples: Vec::new() });
+                self.families.len() - 1
+            }
+        };
+
+        let mut label_text = format!(
+            "server_id=\"{}\",server=\"{}\",group=\"{}\"",
+            escape_label(&server.server_id),
+            escape_label(&server.name),
+            escape_label(&server.group)
+        );
+        for (key, value) in labels {
+            let _ = write!(label_text, ",{}=\"{}\"", key, escape_label(value));
+        }
+        self.families[index]
+            .samples
+            .push(format!("{}{{{}}} {}", name, label_text, format_value(value)));
+    }
+
+    fn finish(self) -> String {
+        let mut output = String::new();
+        for family in self.families {
+            let _ = writeln!(output, "# HELP {} {}", family.name, family.help);
+            let _ = writeln!(output, "# TYPE {} {}", family.name, family.kind);
+            for sample in family.samples {
+                output.push_str(&sample);
+                output.push('\n');
+            }
+        }
+        output
+    }
+}
+
+/// 将各服务器的采样渲染为 Prometheus 文本格式
+fn render_metrics(samples: &[(ServerLabels, SystemMonitorData, i64)]) -> String {
+    let mut w = MetricsWriter::default();
+
+    for (server, data, at) in samples {
+        w.add("myssh_last_sample_timestamp_seconds", "Unix time of the latest sample.", "gauge", server, &[], *at as f64);
+        w.add("myssh_cpu_usage_percent", "CPU usage across all cores (0-100).", "gauge", server, &[], data.cpu.usage);
+        w.add("myssh_cpu_cores", "Number of CPU cores.", "gauge", server, &[], data.cpu.cores as f64);
+        w.add("myssh_cpu_frequency_mhz", "Average current CPU frequency in MHz (0 when unknown).", "gauge", server, &[], data.cpu.frequency);
+        for (period, value) in ["1m", "5m", "15m"].iter().zip(data.cpu.load_average.split(',')) {
+            if let Ok(value) = value.trim().parse::<f64>() {
+                w.add("myssh_load_average", "System load average.", "gauge", server, &[("period", period)], value);
+            }
+        }
+
+        w.add("myssh_memory_total_bytes", "Total memory in bytes.", "gauge", server, &[], data.memory.total as f64);
+        w.add("myssh_memory_used_bytes", "Used memory in bytes.", "gauge", server, &[], data.memory.used as f64);
+        w.add("myssh_memory_available_bytes", "Available memory in bytes.", "gauge", server, &[], data.memory.available as f64);
+
+        for disk in &data.disk {
+            let labels = [("mount", disk.mount.as_str()), ("fstype", disk.filesystem.as_str())];
+            w.add("myssh_filesystem_size_bytes", "Filesystem size in bytes.", "gauge", server, &labels, disk.total as f64);
+            w.add("myssh_filesystem_used_bytes", "Filesystem used space in bytes.", "gauge", server, &labels, disk.used as f64);
+            w.add("myssh_filesystem_avail_bytes", "Filesystem available space in bytes.", "gauge", server, &labels, disk.available as f64);
+            w.add("myssh_filesystem_usage_percent", "Filesystem usage (0-100).", "gauge", server, &labels, disk.usage);
+        }
+
+        for iface in &data.interfaces {
+            let labels = [("interface", iface.name.as_str())];
+            w.add("myssh_network_receive_bytes_per_second", "Network receive rate in bytes per second.", "gauge", server, &labels, iface.rx_rate as f64);
+            w.add("myssh_network_transmit_bytes_per_second", "Network transmit rate in bytes per second.", "gauge", server, &labels, iface.tx_rate as f64);
+            w.add("myssh_network_receive_bytes_total", "Total bytes received.", "counter", server, &labels, iface.rx_total as f64);
+            w.add("myssh_network_transmit_bytes_total", "Total bytes transmitted.", "counter", server, &labels, iface.tx_total as f64);
+            w.add("myssh_network_receive_errors_total", "Total receive errors.", "counter", server, &labels, iface.rx_errors as f64);
+            w.add("myssh_network_transmit_errors_total", "Total transmit errors.", "counter", server, &labels, iface.tx_errors as f64);
+        }
+
+        for disk in &data.disk_io {
+            let labels = [("device", disk.device.as_str())];
+            w.add("myssh_disk_read_bytes_per_second", "Disk read rate in bytes per second.", "gauge", server, &labels, disk.read_rate as f64);
+            w.add("myssh_disk_write_bytes_per_second", "Disk write rate in bytes per second.", "gauge", server, &labels, disk.write_rate as f64);
+            w.add("myssh_disk_reads_per_second", "Disk read operations per second.", "gauge", server, &labels, disk.read_iops);
+            w.add("myssh_disk_writes_per_second", "Disk write operations per second.", "gauge", server, &labels, disk.write_iops);
+            w.add("myssh_disk_utilization_percent", "Disk busy time (0-100).", "gauge", server, &labels, disk.utilization);
+        }
+
+        for temp in &data.sensors.temperatures {
+            let labels = [("chip", temp.chip.as_str()), ("sensor", temp.label.as_str())];
+            w.add("myssh_temperature_celsius", "Hardware temperature in degrees Celsius.", "gauge", server, &labels, temp.celsius);
+        }
+
+        for fan in &data.sensors.fans {
+            let labels = [("chip", fan.chip.as_str()), ("sensor", fan.label.as_str())];
+            w.add("myssh_fan_speed_rpm", "Fan speed in RPM.", "gauge", server, &labels, fan.rpm as f64);
+        }
+
+        for supply in &data.sensors.power_supplies {
+            let labels = [("supply", supply.name.as_str()), ("type", supply.kind.as_str())];
+            if let Some(capacity) = supply.capacity {
+                w.add("myssh_power_supply_capacity_percent", "Battery capacity (0-100).", "gauge", server, &labels, capacity);
+            }
+            if let Some(online) = supply.online {
+                w.add("myssh_power_supply_online", "Whether the power supply is online.", "gauge", server, &labels, if online { 1.0 } else { 0.0 });
+            }
+        }
+    }
+
+    w.finish()
+}
+
+/// 转义标签值中的反斜杠、双引号和换行
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// 格式化样本值（整数不带小数点，无穷值按 Prometheus 格式输出 `+Inf` / `-Inf`）
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{CpuInfo, MemoryInfo, NetworkInfo, SensorInfo};
+
+    fn sample_data() -> SystemMonitorData {
+        SystemMonitorData {
+            cpu: CpuInfo {
+                usage: 12.5,
+                cores: 2,
+                frequency: 0.0,
+                load_average: "0.50, 0.25, 0.10".to_string(),
+                cores_usage: vec![10.0, 15.0],
+            },
+            memory: MemoryInfo {
+                total: 2048,
+                used: 1024,
+                cached: None,
+                available: 1024,
+            },
+            disk: Vec::new(),
+            network: NetworkInfo {
+                download: 0,
+                upload: 0,
+                download_total: 0,
+                upload_total: 0,
+            },
+            interfaces: Vec::new(),
+            disk_io: Vec::new(),
+            sensors: SensorInfo::default(),
+        }
+    }
+
+    #[test]
+    fn renders_one_server_with_group_label() {
+        let server = ServerLabels {
+            server_id: "s1".to_string(),
+            name: "web \"prod\"".to_string(),
+            group: "生产".to_string(),
+        };
+        let output = render_metrics(&[(server, sample_data(), 1700000000)]);
+
+        let labels = "server_id=\"s1\",server=\"web \\\"prod\\\"\",group=\"生产\"";
+        let expected = [
+            "# HELP myssh_last_sample_timestamp_seconds Unix time of the latest sample.".to_string(),
+            "# TYPE myssh_last_sample_timestamp_seconds gauge".to_string(),
+            format!("myssh_last_sample_timestamp_seconds{{{}}} 1700000000", labels),
+            "# HELP myssh_cpu_usage_percent CPU usage across all cores (0-100).".to_string(),
+            "# TYPE myssh_cpu_usage_percent gauge".to_string(),
+            format!("myssh_cpu_usage_percent{{{}}} 12.5", labels),
+            "# HELP myssh_cpu_cores Number of CPU cores.".to_string(),
+            "# TYPE myssh_cpu_cores gauge".to_string(),
+            format!("myssh_cpu_cores{{{}}} 2", labels),
+            "# HELP myssh_cpu_frequency_mhz Average current CPU frequency in MHz (0 when unknown).".to_string(),
+            "# TYPE myssh_cpu_frequency_mhz gauge".to_string(),
+            format!("myssh_cpu_frequency_mhz{{{}}} 0", labels),
+            "# HELP myssh_load_average System load average.".to_string(),
+            "# TYPE myssh_load_average gauge".to_string(),
+            format!("myssh_load_average{{{},period=\"1m\"}} 0.5", labels),
+            format!("myssh_load_average{{{},period=\"5m\"}} 0.25", labels),
+            format!("myssh_load_average{{{},period=\"15m\"}} 0.1", labels),
+            "# HELP myssh_memory_total_bytes Total memory in bytes.".to_string(),
+            "# TYPE myssh_memory_total_bytes gauge".to_string(),
+            format!("myssh_memory_total_bytes{{{}}} 2048", labels),
+            "# HELP myssh_memory_used_bytes Used memory in bytes.".to_string(),
+            "# TYPE myssh_memory_used_bytes gauge".to_string(),
+            format!("myssh_memory_used_bytes{{{}}} 1024", labels),
+            "# HELP myssh_memory_available_bytes Available memory in bytes.".to_string(),
+            "# TYPE myssh_memory_available_bytes gauge".to_string(),
+            format!("myssh_memory_available_bytes{{{}}} 1024", labels),
+        ]
+        .join("\n")
+            + "\n";
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn groups_samples_of_same_family() {
+        let a = ServerLabels { server_id: "a".to_string(), name: "a".to_string(), group: String::new() };
+        let b = ServerLabels { server_id: "b".to_string(), name: "b".to_string(), group: String::new() };
+        let output = render_metrics(&[(a, sample_data(), 1), (b, sample_data(), 2)]);
+
+        assert_eq!(output.matches("# TYPE myssh_cpu_cores gauge").count(), 1);
+        assert!(output.contains(
+            "myssh_cpu_cores{server_id=\"a\",server=\"a\",group=\"\"} 2\nmyssh_cpu_cores{server_id=\"b\",server=\"b\",group=\"\"} 2\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn stop_releases_port_before_returning() {
+        let listener = bind_listener(0).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        spawn_exporter(port, listener);
+
+        stop_exporter().await;
+        assert!(EXPORTER.lock().unwrap().is_none());
+
+        // 停止后立即以相同端口重新监听
+        let listener = bind_listener(port).await.unwrap();
+        spawn_exporter(port, listener);
+        stop_exporter().await;
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape_label("plain"), "plain");
+        assert_eq!(escape_label("C:\\data"), "C:\\\\data");
+        assert_eq!(escape_label("say \"hi\""), "say \\\"hi\\\"");
+        assert_eq!(escape_label("a\nb"), "a\\nb");
+    }
+
+    #[test]
+    fn formats_special_values() {
+        assert_eq!(format_value(42.0), "42");
+        assert_eq!(format_value(-3.0), "-3");
+        assert_eq!(format_value(0.25), "0.25");
+        assert_eq!(format_value(1e16), "10000000000000000");
+        assert_eq!(format_value(f64::NAN), "NaN");
+        assert_eq!(format_value(f64::INFINITY), "+Inf");
+        assert_eq!(format_value(f64::NEG_INFINITY), "-Inf");
+    }
+}
@@ -3,9 +3,54 @@
  */
 
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Window;
+use tokio::sync::oneshot;
+use tokio::time::MissedTickBehavior;
+use crate::alerts::evaluate_monitor_sample;
+use crate::history::record_monitor_sample;
+use crate::metrics_exporter::record_latest_sample;
+use crate::monitor_bsd::{parse_bsd_sample, BSD_COLLECT_COMMAND};
+use crate::ssh::{exec_command, is_connected, shell_quote};
+use crate::util::split_sections;
+
+/// Linux 上一次性采集所有指标的远程命令，各部分输出以 `==名称==` 行分隔
+const LINUX_COLLECT_COMMAND: &str = "echo '==stat=='; cat /proc/stat; \
+echo '==meminfo=='; cat /proc/meminfo; \
+echo '==loadavg=='; cat /proc/loadavg; \
+echo '==cpufreq=='; cat /sys/devices/system/cpu/cpu[0-9]*/cpufreq/scaling_cur_freq 2>/dev/null || grep -i '^cpu MHz' /proc/cpuinfo 2>/dev/null; \
+echo '==netdev=='; cat /proc/net/dev; \
+echo '==diskstats=='; cat /proc/diskstats 2>/dev/null; \
+echo '==df=='; df -PTk 2>/dev/null; \
+echo '==sensors=='; for f in /sys/class/thermal/thermal_zone*/type /sys/class/thermal/thermal_zone*/temp \
+/sys/class/hwmon/hwmon*/name /sys/class/hwmon/hwmon*/temp*_input /sys/class/hwmon/hwmon*/temp*_label /sys/class/hwmon/hwmon*/temp*_crit \
+/sys/class/hwmon/hwmon*/fan*_input /sys/class/hwmon/hwmon*/fan*_label \
+/sys/class/power_supply/*/type /sys/class/power_supply/*/status /sys/class/power_supply/*/capacity \
+/sys/class/power_supply/*/online /sys/class/power_supply/*/power_now; \
+do [ -r \"$f\" ] && printf '%s=%s\\n' \"$f\" \"$(cat \"$f\" 2>/dev/null)\"; done";
+
+/// 默认采样间隔（毫秒）
+const DEFAULT_MONITOR_INTERVAL_MS: u64 = 2000;
+/// 最小采样间隔（毫秒）
+const MIN_MONITOR_INTERVAL_MS: u64 = 1000;
+
+/// 监控数据事件名
+const MONITOR_DATA_EVENT: &str = "system-monitor-data";
+/// 监控采样失败事件名
+const MONITOR_ERROR_EVENT: &str = "system-monitor-error";
+/// 监控结束事件名
+const MONITOR_STOPPED_EVENT: &str = "system-monitor-stopped";
+
+/// 不统计的伪文件系统类型（含 BSD 和 macOS）
+pub(crate) const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "tmpfs", "devtmpfs", "overlay", "squashfs", "proc", "sysfs", "devfs", "cgroup", "cgroup2", "efivarfs",
+    "autofs", "fdescfs", "procfs", "linprocfs", "linsysfs", "nullfs", "mfs",
+];
 
 /// CPU 信息
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CpuInfo {
     pub usage: f64,           // CPU 总使用率 (0-100)
     pub cores: usize,         // CPU 核心数
@@ -15,7 +60,7 @@ pub struct CpuInfo {
 }
 
 /// 内存信息
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MemoryInfo {
     pub total: u64,      // 总内存 (字节)
     pub used: u64,       // 已用内存 (字节)
@@ -24,7 +69,7 @@ pub struct MemoryInfo {
 }
 
 /// 磁盘信息
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DiskInfo {
     pub mount: String,      // 挂载点，如 "/"
     pub filesystem: String, // 文件系统类型，如 "ext4"
@@ -35,7 +80,7 @@ pub struct DiskInfo {
 }
 
 /// 网络信息
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct NetworkInfo {
     pub download: u64,        // 当前下载速度 (字节/秒)
     pub upload: u64,          // 当前上传速度 (字节/秒)
@@ -43,13 +88,78 @@ pub struct NetworkInfo {
     pub upload_total: u64,   // 总上传量 (字节)
 }
 
+/// 单个网卡信息
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceInfo {
+    pub name: String,
+    pub rx_rate: u64,     // 接收速度 (字节/秒)
+    pub tx_rate: u64,     // 发送速度 (字节/秒)
+    pub rx_total: u64,    // 总接收量 (字节)
+    pub tx_total: u64,    // 总发送量 (字节)
+    pub rx_errors: u64,   // 累计接收错误数
+    pub tx_errors: u64,   // 累计发送错误数
+    pub rx_dropped: u64,  // 累计接收丢包数
+    pub tx_dropped: u64,  // 累计发送丢包数
+}
+
+/// 单个块设备 I/O 信息
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskIoInfo {
+    pub device: String,     // 设备名，如 "sda"、"nvme0n1"
+    pub read_iops: f64,     // 每秒读次数
+    pub write_iops: f64,    // 每秒写次数
+    pub read_rate: u64,     // 读速度 (字节/秒)
+    pub write_rate: u64,    // 写速度 (字节/秒)
+    pub utilization: f64,   // 设备繁忙时间占比 (0-100)
+}
+
+/// 温度传感器
+#[derive(Debug, Clone, Serialize)]
+pub struct TemperatureInfo {
+    pub source: String,        // 来源："thermal"（thermal zone）| "hwmon"
+    pub chip: String,          // thermal zone 名称或 hwmon 芯片名，如 "x86_pkg_temp"、"coretemp"
+    pub label: String,         // 传感器名称，如 "Package id 0"、"temp1"
+    pub celsius: f64,          // 当前温度 (°C)
+    pub critical: Option<f64>, // 临界温度 (°C，可选)
+}
+
+/// 风扇转速
+#[derive(Debug, Clone, Serialize)]
+pub struct FanInfo {
+    pub chip: String,
+    pub label: String,
+    pub rpm: u64,
+}
+
+/// 电源（电池、适配器、UPS 等）
+#[derive(Debug, Clone, Serialize)]
+pub struct PowerSupplyInfo {
+    pub name: String,             // 设备名，如 "BAT0"、"AC"
+    pub kind: String,             // 类型，如 "Battery"、"Mains"、"UPS"
+    pub status: Option<String>,   // 电池状态，如 "Charging"、"Discharging"、"Full"
+    pub capacity: Option<f64>,    // 电量 (0-100)
+    pub online: Option<bool>,     // 外部电源是否接通
+    pub power: Option<f64>,       // 当前功率 (瓦)
+}
+
+/// 硬件传感器数据（无对应传感器时为空列表）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SensorInfo {
+    pub temperatures: Vec<TemperatureInfo>,
+    pub fans: Vec<FanInfo>,
+    pub power_supplies: Vec<PowerSupplyInfo>,
+}
+
 /// 系统监控数据
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SystemMonitorData {
     pub cpu: CpuInfo,
     pub memory: MemoryInfo,
     pub disk: Vec<DiskInfo>,
     pub network: NetworkInfo,
+    pub interfaces: Vec<InterfaceInfo>, // 各网卡流量（不含 lo）
+    pub disk_io: Vec<DiskIoInfo>,       // 各块设备 I/O（不含分区和虚拟设备）
+    pub sensors: SensorInfo,            // 温度、风扇及电源状态
 }
 
 /// 获取系统监控数据参数
@@ -58,8 +168,119 @@ pub struct GetSystemMonitorParams {
     pub server_id: String,
 }
 
+/// 单个 CPU 的累计时间（单位为 jiffies）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CpuTimes {
+    pub idle: u64,  // idle + iowait
+    pub total: u64,
+}
+
+/// 单个网卡的累计计数
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceCounters {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_bytes: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+}
+
+/// 单个块设备的累计计数
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskCounters {
+    pub device: String,
+    pub reads: u64,          // 完成的读次数
+    pub read_sectors: u64,   // 读扇区数（每扇区 512 字节）
+    pub writes: u64,         // 完成的写次数
+    pub write_sectors: u64,  // 写扇区数
+    pub io_ms: u64,          // 设备处理 I/O 的累计时间（毫秒）
+}
+
+/// 上一次采样的累计值，用于计算 CPU 使用率、网络速率和磁盘 I/O
+#[derive(Debug, Clone)]
+pub(crate) struct MonitorSample {
+    cpu: Vec<CpuTimes>,  // 第一个为总计，其后为各核心
+    interfaces: Vec<InterfaceCounters>,
+    disks: Vec<DiskCounters>,
+    at: Instant,
+}
+
+/// 一次采集解析出的原始数据（累计计数器尚未换算为速率）
+pub(crate) struct RawSample {
+    pub cpu: Vec<CpuTimes>,      // 第一个为总计，其后为各核心；无法获取累计时间时为空
+    pub cpu_usage: Option<f64>,  // 直接给出的 CPU 总使用率（没有累计时间时使用）
+    pub cores: usize,
+    pub frequency: f64,
+    pub load_average: String,
+    pub memory: MemoryInfo,
+    pub disk: Vec<DiskInfo>,
+    pub interfaces: Vec<InterfaceCounters>, // 不含回环网卡
+    pub disks: Vec<DiskCounters>,
+    pub sensors: SensorInfo,
+}
+
+/// 各服务器的上一次采样（server_id -> 采样）
+type MonitorSamples = Arc<Mutex<HashMap<String, MonitorSample>>>;
+
+/// 正在运行的监控任务（server_id -> 停止信号）
+type MonitorTasks = Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>;
+
+lazy_static::lazy_static! {
+    static ref MONITOR_SAMPLES: MonitorSamples = Arc::new(Mutex::new(HashMap::new()));
+    static ref MONITOR_TASKS: MonitorTasks = Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// 监控数据事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemMonitorDataEvent {
+    pub server_id: String,
+    pub data: SystemMonitorData,
+}
+
+/// 监控采样失败事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemMonitorErrorEvent {
+    pub server_id: String,
+    pub message: String,
+}
+
+/// 监控结束事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemMonitorStoppedEvent {
+    pub server_id: String,
+    pub reason: String, // "stopped" | "disconnected"
+}
+
+/// 开始监控参数
+#[derive(Debug, Deserialize)]
+pub struct StartMonitoringParams {
+    pub server_id: String,
+    pub interval_ms: Option<u64>, // 采样间隔（默认 2000，最小 1000）
+}
+
+/// 停止监控参数
+#[derive(Debug, Deserialize)]
+pub struct StopMonitoringParams {
+    pub server_id: String,
+}
+
+/// 开始/停止监控返回
+#[derive(Debug, Serialize)]
+pub struct MonitoringResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
 /// 获取系统监控数据
 /// 
+/// 通过一次远程命令读取 `/proc/stat`、`/proc/meminfo`、`/proc/loadavg`、`/proc/net/dev`
+/// 和 `df` 输出。CPU 使用率和网络速率根据与上一次采样的差值计算，首次采样时
+/// CPU 使用率为开机以来的平均值，网络速率为 0。
+/// FreeBSD 和 macOS 改用 `sysctl`、`vm_stat`、`netstat -ib` 和 `df` 采集，磁盘 I/O 和传感器数据为空。
+/// 单次查询不写入历史数据，历史数据由 `start_monitoring` 的后台采样记录。
+/// 
 /// # 命令名称
 /// `get_system_monitor`
 /// 
@@ -73,41 +294,901 @@ pub struct GetSystemMonitorParams {
 /// - `network`: 网络信息
 #[tauri::command]
 pub async fn get_system_monitor(params: GetSystemMonitorParams) -> Result<SystemMonitorData, String> {
-    // TODO: 实现实际的系统监控数据获取逻辑
-    // 这里需要通过 SSH 执行系统命令来获取监控数据
-    // 例如：top, df, free, ifconfig 等命令
-    
-    // 模拟返回
-    Ok(SystemMonitorData {
+    collect_system_monitor(&params.server_id).await
+}
+
+/// 开始推送服务器监控数据
+///
+/// 在后台按固定间隔采样，数据通过 `system-monitor-data` 事件推送，采样失败时推送
+/// `system-monitor-error` 事件。连接断开或调用 `stop_monitoring` 后结束并推送
+/// `system-monitor-stopped` 事件。对同一服务器重复调用会以新的间隔重新开始。
+///
+/// # 命令名称
+/// `start_monitoring`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `interval_ms`: 采样间隔，单位毫秒（可选）
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn start_monitoring(window: Window, params: StartMonitoringParams) -> Result<MonitoringResult, String> {
+    if !is_connected(&params.server_id) {
+        return Err("服务器未连接".to_string());
+    }
+
+    let interval_ms = params
+        .interval_ms
+        .unwrap_or(DEFAULT_MONITOR_INTERVAL_MS)
+        .max(MIN_MONITOR_INTERVAL_MS);
+
+    let (stop_tx, mut stop_rx) = oneshot::channel();
+    if let Some(previous) = MONITOR_TASKS.lock().unwrap().insert(params.server_id.clone(), stop_tx) {
+        let _ = previous.send(());
+    }
+
+    let server_id = params.server_id;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let reason = loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = &mut stop_rx => break "stopped",
+            }
+
+            if !is_connected(&server_id) {
+                break "disconnected";
+            }
+
+            match collect_system_monitor(&server_id).await {
+                Ok(data) => {
+                    // 历史数据只来自后台采样，单次查询和批量采样不写入
+                    record_monitor_sample(&server_id, &data);
+                    let _ = window.emit(MONITOR_DATA_EVENT, SystemMonitorDataEvent {
+                        server_id: server_id.clone(),
+                        data,
+                    });
+                }
+                Err(message) => {
+                    let _ = window.emit(MONITOR_ERROR_EVENT, SystemMonitorErrorEvent {
+                        server_id: server_id.clone(),
+                        message,
+                    });
+                }
+            }
+        };
+
+        // 被新的监控任务替换时不移除新任务，也不推送结束事件
+        if reason == "disconnected" {
+            MONITOR_TASKS.lock().unwrap().remove(&server_id);
+            MONITOR_SAMPLES.lock().unwrap().remove(&server_id);
+        }
+        let replaced = reason == "stopped" && MONITOR_TASKS.lock().unwrap().contains_key(&server_id);
+        if !replaced {
+            let _ = window.emit(MONITOR_STOPPED_EVENT, SystemMonitorStoppedEvent {
+                server_id,
+                reason: reason.to_string(),
+            });
+        }
+    });
+
+    Ok(MonitoringResult {
+        success: true,
+        message: Some("已开始监控".to_string()),
+    })
+}
+
+/// 停止推送服务器监控数据
+///
+/// # 命令名称
+/// `stop_monitoring`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn stop_monitoring(params: StopMonitoringParams) -> Result<MonitoringResult, String> {
+    let sender = MONITOR_TASKS.lock().unwrap().remove(&params.server_id);
+
+    match sender {
+        Some(sender) => {
+            let _ = sender.send(());
+            Ok(MonitoringResult {
+                success: true,
+                message: Some("已停止监控".to_string()),
+            })
+        }
+        None => Ok(MonitoringResult {
+            success: true,
+            message: Some("监控未在运行".to_string()),
+        }),
+    }
+}
+
+/// 采集一次监控数据，更新该服务器的采样记录和导出指标并评估告警规则
+///
+/// 不保存历史数据，历史数据只由 `start_monitoring` 的后台采样写入。
+pub(crate) async fn collect_system_monitor(server_id: &str) -> Result<SystemMonitorData, String> {
+    let previous = MONITOR_SAMPLES.lock().unwrap().get(server_id).cloned();
+    let (data, current) = sample_system_monitor(server_id, previous.as_ref()).await?;
+    MONITOR_SAMPLES.lock().unwrap().insert(server_id.to_string(), current);
+
+    record_latest_sample(server_id, &data);
+    evaluate_monitor_sample(server_id, &data);
+
+    Ok(data)
+}
+
+/// 采集一次监控数据，根据调用方提供的上一次采样计算速率
+///
+/// 不读写共享的采样记录，也不更新导出指标、历史数据或告警状态。
+/// 返回监控数据和本次采样，本次采样可作为下一次调用的 `previous`。
+pub(crate) async fn sample_system_monitor(
+    server_id: &str,
+    previous: Option<&MonitorSample>,
+) -> Result<(SystemMonitorData, MonitorSample), String> {
+    // 统一交给 sh 执行，FreeBSD 等系统的登录 shell 可能是 csh
+    let script = format!(
+        "echo '==os=='; uname -s; case \"$(uname -s)\" in Linux) {} ;; *) {} ;; esac",
+        LINUX_COLLECT_COMMAND, BSD_COLLECT_COMMAND
+    );
+    let output = exec_command(server_id, &format!("sh -c {}", shell_quote(&script))).await?;
+    let sections = split_sections(&output.stdout);
+    let os = sections.get("os").map(|v| v.trim()).unwrap_or("");
+
+    let raw = match os {
+        "Linux" => parse_linux_sample(&sections)?,
+        "Darwin" | "FreeBSD" => parse_bsd_sample(os, &sections)?,
+        "" => return Err("无法识别目标系统".to_string()),
+        other => return Err(format!("暂不支持监控该系统: {}", other)),
+    };
+
+    let current = MonitorSample {
+        cpu: raw.cpu,
+        interfaces: raw.interfaces,
+        disks: raw.disks,
+        at: Instant::now(),
+    };
+    let cpu_usage: Vec<f64> = current
+        .cpu
+        .iter()
+        .enumerate()
+        .map(|(i, times)| {
+            let prev = previous.and_then(|p| p.cpu.get(i));
+            cpu_usage_between(prev, times)
+        })
+        .collect();
+
+    let elapsed = previous
+        .map(|prev| current.at.duration_since(prev.at).as_secs_f64())
+        .unwrap_or(0.0);
+    let interfaces: Vec<InterfaceInfo> = current
+        .interfaces
+        .iter()
+        .map(|iface| {
+            let prev = previous
+                .and_then(|p| p.interfaces.iter().find(|i| i.name == iface.name));
+            interface_info_between(prev, iface, elapsed)
+        })
+        .collect();
+    let disk_io: Vec<DiskIoInfo> = current
+        .disks
+        .iter()
+        .map(|disk| {
+            let prev = previous
+                .and_then(|p| p.disks.iter().find(|d| d.device == disk.device));
+            disk_io_between(prev, disk, elapsed)
+        })
+        .collect();
+
+    let data = SystemMonitorData {
         cpu: CpuInfo {
-            usage: 25.5,
-            cores: 4,
-            frequency: 2400.0,
-            load_average: "1.5, 0.8, 0.3".to_string(),
-            cores_usage: vec![20.0, 25.0, 30.0, 27.0],
+            usage: raw.cpu_usage.or_else(|| cpu_usage.first().copied()).unwrap_or(0.0),
+            cores: raw.cores,
+            frequency: raw.frequency,
+            load_average: raw.load_average,
+            cores_usage: cpu_usage.get(1..).map(|v| v.to_vec()).unwrap_or_default(),
         },
-        memory: MemoryInfo {
-            total: 8 * 1024 * 1024 * 1024, // 8GB
-            used: 4 * 1024 * 1024 * 1024,  // 4GB
-            cached: Some(1 * 1024 * 1024 * 1024), // 1GB
-            available: 4 * 1024 * 1024 * 1024,  // 4GB
-        },
-        disk: vec![
-            DiskInfo {
-                mount: "/".to_string(),
-                filesystem: "ext4".to_string(),
-                total: 100 * 1024 * 1024 * 1024, // 100GB
-                used: 50 * 1024 * 1024 * 1024,   // 50GB
-                available: 50 * 1024 * 1024 * 1024, // 50GB
-                usage: 50.0,
-            },
-        ],
+        memory: raw.memory,
+        disk: raw.disk,
         network: NetworkInfo {
-            download: 1024 * 1024,      // 1MB/s
-            upload: 512 * 1024,         // 512KB/s
-            download_total: 10 * 1024 * 1024 * 1024, // 10GB
-            upload_total: 5 * 1024 * 1024 * 1024,   // 5GB
+            download: interfaces.iter().map(|i| i.rx_rate).sum(),
+            upload: interfaces.iter().map(|i| i.tx_rate).sum(),
+            download_total: interfaces.iter().map(|i| i.rx_total).sum(),
+            upload_total: interfaces.iter().map(|i| i.tx_total).sum(),
         },
+        interfaces,
+        disk_io,
+        sensors: raw.sensors,
+    };
+
+    Ok((data, current))
+}
+
+/// 解析 Linux 采集命令的输出
+fn parse_linux_sample(sections: &HashMap<&str, &str>) -> Result<RawSample, String> {
+    let section = |name: &str| sections.get(name).copied().unwrap_or("");
+
+    let cpu = parse_proc_stat(section("stat"));
+    if cpu.is_empty() {
+        return Err("无法读取 /proc/stat".to_string());
+    }
+
+    Ok(RawSample {
+        cores: cpu.len() - 1,
+        cpu,
+        cpu_usage: None,
+        frequency: parse_cpu_frequency(section("cpufreq")),
+        load_average: parse_loadavg(section("loadavg")),
+        memory: parse_meminfo(section("meminfo")),
+        disk: parse_df(section("df")),
+        interfaces: parse_net_dev(section("netdev"))
+            .into_iter()
+            .filter(|iface| iface.name != "lo")
+            .collect(),
+        disks: parse_diskstats(section("diskstats")),
+        sensors: parse_sensors(section("sensors")),
     })
 }
 
+/// 解析 `/proc/stat` 中的 CPU 行，第一个元素为总计，其后为各核心
+fn parse_proc_stat(text: &str) -> Vec<CpuTimes> {
+    text.lines()
+        .filter(|line| line.starts_with("cpu"))
+        .filter_map(|line| {
+            let values: Vec<u64> = line
+                .split_whitespace()
+                .skip(1)
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            if values.len() < 4 {
+                return None;
+            }
+            // user nice system idle iowait irq softirq steal guest guest_nice
+            // guest/guest_nice 已计入 user/nice，不重复累加
+            let idle = values[3] + values.get(4).copied().unwrap_or(0);
+            let total = values.iter().take(8).sum();
+            Some(CpuTimes { idle, total })
+        })
+        .collect()
+}
+
+/// 根据两次采样计算 CPU 使用率 (0-100)，没有上一次采样时使用开机以来的平均值
+fn cpu_usage_between(previous: Option<&CpuTimes>, current: &CpuTimes) -> f64 {
+    let (idle, total) = match previous {
+        Some(prev) if current.total > prev.total && current.idle >= prev.idle => {
+            (current.idle - prev.idle, current.total - prev.total)
+        }
+        _ => (current.idle, current.total),
+    };
+
+    if total == 0 {
+        return 0.0;
+    }
+    let usage = (1.0 - idle as f64 / total as f64) * 100.0;
+    (usage.clamp(0.0, 100.0) * 10.0).round() / 10.0
+}
+
+/// 根据两次累计值计算每秒速率，计数器回绕或重启时返回 0
+fn rate_between(previous: u64, current: u64, elapsed_secs: f64) -> u64 {
+    if elapsed_secs <= 0.0 || current < previous {
+        return 0;
+    }
+    ((current - previous) as f64 / elapsed_secs).round() as u64
+}
+
+/// 解析 `/proc/meminfo`（单位 kB）
+fn parse_meminfo(text: &str) -> MemoryInfo {
+    let values: HashMap<&str, u64> = text
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let value = rest.split_whitespace().next()?.parse::<u64>().ok()?;
+            Some((key.trim(), value * 1024))
+        })
+        .collect();
+    let get = |key: &str| values.get(key).copied().unwrap_or(0);
+
+    let total = get("MemTotal");
+    let cached = get("Cached") + get("SReclaimable");
+    // 3.14 之前的内核没有 MemAvailable
+    let available = values
+        .get("MemAvailable")
+        .copied()
+        .unwrap_or_else(|| get("MemFree") + get("Buffers") + cached);
+
+    MemoryInfo {
+        total,
+        used: total.saturating_sub(available),
+        cached: values.get("Cached").map(|_| cached),
+        available,
+    }
+}
+
+/// 解析 `/proc/loadavg`，格式化为 "1.5, 0.8, 0.3"
+fn parse_loadavg(text: &str) -> String {
+    text.split_whitespace().take(3).collect::<Vec<_>>().join(", ")
+}
+
+/// 解析 CPU 当前频率，返回各核心平均值 (MHz)，无法获取时为 0
+///
+/// 支持 cpufreq 的 `scaling_cur_freq`（kHz）和 `/proc/cpuinfo` 的 `cpu MHz : 2400.000` 两种格式。
+fn parse_cpu_frequency(text: &str) -> f64 {
+    let values: Vec<f64> = text
+        .lines()
+        .filter_map(|line| match line.split_once(':') {
+            Some((_, mhz)) => mhz.trim().parse::<f64>().ok(),
+            None => line.trim().parse::<f64>().ok().map(|khz| khz / 1000.0),
+        })
+        .filter(|mhz| *mhz > 0.0)
+        .collect();
+
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// 解析 `/proc/net/dev`
+fn parse_net_dev(text: &str) -> Vec<InterfaceCounters> {
+    text.lines()
+        .filter_map(|line| {
+            // 前两行为表头，数据行格式为 "  eth0: rx_bytes rx_packets ... tx_bytes ..."
+            let (name, rest) = line.split_once(':')?;
+            let values: Vec<u64> = rest.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+            if values.len() < 16 {
+                return None;
+            }
+            // 接收：bytes packets errs drop fifo frame compressed multicast；发送：bytes packets errs drop ...
+            Some(InterfaceCounters {
+                name: name.trim().to_string(),
+                rx_bytes: values[0],
+                rx_errors: values[2],
+                rx_dropped: values[3],
+                tx_bytes: values[8],
+                tx_errors: values[10],
+                tx_dropped: values[11],
+            })
+        })
+        .collect()
+}
+
+/// 解析 `/proc/diskstats`，只保留有过 I/O 的整盘设备
+fn parse_diskstats(text: &str) -> Vec<DiskCounters> {
+    text.lines()
+        .filter_map(|line| {
+            // major minor name reads reads_merged sectors_read ms_reading writes writes_merged sectors_written ms_writing in_progress io_ms ...
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 13 {
+                return None;
+            }
+            let device = fields[2];
+            if is_virtual_or_partition(device) {
+                return None;
+            }
+
+            let value = |i: usize| fields[i].parse::<u64>().ok();
+            let counters = DiskCounters {
+                device: device.to_string(),
+                reads: value(3)?,
+                read_sectors: value(5)?,
+                writes: value(7)?,
+                write_sectors: value(9)?,
+                io_ms: value(12)?,
+            };
+            if counters.reads == 0 && counters.writes == 0 {
+                return None;
+            }
+            Some(counters)
+        })
+        .collect()
+}
+
+/// 判断设备是否为虚拟设备或分区
+fn is_virtual_or_partition(device: &str) -> bool {
+    if device.starts_with("loop") || device.starts_with("ram") || device.starts_with("zram") || device.starts_with("sr") {
+        return true;
+    }
+
+    // nvme0n1p1、mmcblk0p1 形式的分区
+    if device.starts_with("nvme") || device.starts_with("mmcblk") {
+        return device
+            .rfind('p')
+            .map_or(false, |pos| pos > 0 && device[pos + 1..].parse::<u32>().is_ok() && device[..pos].ends_with(|c: char| c.is_ascii_digit()));
+    }
+
+    // sda1、vdb2、xvda1 形式的分区
+    let is_classic = ["sd", "vd", "hd", "xvd"].iter().any(|prefix| device.starts_with(prefix));
+    is_classic && device.ends_with(|c: char| c.is_ascii_digit())
+}
+
+/// 根据两次采样计算单个网卡的速率
+fn interface_info_between(previous: Option<&InterfaceCounters>, current: &InterfaceCounters, elapsed_secs: f64) -> InterfaceInfo {
+    let (rx_rate, tx_rate) = match previous {
+        Some(prev) => (
+            rate_between(prev.rx_bytes, current.rx_bytes, elapsed_secs),
+            rate_between(prev.tx_bytes, current.tx_bytes, elapsed_secs),
+        ),
+        None => (0, 0),
+    };
+
+    InterfaceInfo {
+        name: current.name.clone(),
+        rx_rate,
+        tx_rate,
+        rx_total: current.rx_bytes,
+        tx_total: current.tx_bytes,
+        rx_errors: current.rx_errors,
+        tx_errors: current.tx_errors,
+        rx_dropped: current.rx_dropped,
+        tx_dropped: current.tx_dropped,
+    }
+}
+
+/// 根据两次采样计算单个块设备的 I/O 速率
+fn disk_io_between(previous: Option<&DiskCounters>, current: &DiskCounters, elapsed_secs: f64) -> DiskIoInfo {
+    let mut info = DiskIoInfo {
+        device: current.device.clone(),
+        read_iops: 0.0,
+        write_iops: 0.0,
+        read_rate: 0,
+        write_rate: 0,
+        utilization: 0.0,
+    };
+
+    if let Some(prev) = previous {
+        if elapsed_secs > 0.0 {
+            let round = |v: f64| (v * 10.0).round() / 10.0;
+            info.read_iops = round(rate_between(prev.reads, current.reads, 1.0) as f64 / elapsed_secs);
+            info.write_iops = round(rate_between(prev.writes, current.writes, 1.0) as f64 / elapsed_secs);
+            info.read_rate = rate_between(prev.read_sectors * 512, current.read_sectors * 512, elapsed_secs);
+            info.write_rate = rate_between(prev.write_sectors * 512, current.write_sectors * 512, elapsed_secs);
+            let busy_ms = rate_between(prev.io_ms, current.io_ms, 1.0) as f64;
+            info.utilization = round((busy_ms / (elapsed_secs * 1000.0) * 100.0).min(100.0));
+        }
+    }
+
+    info
+}
+
+/// 解析 `df -PTk` 输出，忽略伪文件系统
+fn parse_df(text: &str) -> Vec<DiskInfo> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            // Filesystem Type 1024-blocks Used Available Capacity Mounted-on（挂载点可能包含空格）
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let filesystem = fields[1];
+            if PSEUDO_FILESYSTEMS.contains(&filesystem) {
+                return None;
+            }
+
+            let total = fields[2].parse::<u64>().ok()? * 1024;
+            let used = fields[3].parse::<u64>().ok()? * 1024;
+            let available = fields[4].parse::<u64>().ok()? * 1024;
+            if total == 0 {
+                return None;
+            }
+            let usage = fields[5]
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .unwrap_or_else(|_| used as f64 * 100.0 / (used + available).max(1) as f64);
+
+            Some(DiskInfo {
+                mount: fields[6..].join(" "),
+                filesystem: filesystem.to_string(),
+                total,
+                used,
+                available,
+                usage,
+            })
+        })
+        .collect()
+}
+
+/// 解析 sysfs 传感器输出（每行 `<文件路径>=<内容>`），读取失败或不存在的传感器直接跳过
+///
+/// - `/sys/class/thermal/thermal_zoneN/{type,temp}`：温度单位为毫摄氏度
+/// - `/sys/class/hwmon/hwmonN/{name,tempK_input,tempK_label,tempK_crit,fanK_input,fanK_label}`
+/// - `/sys/class/power_supply/<名称>/{type,status,capacity,online,power_now}`：功率单位为微瓦
+fn parse_sensors(text: &str) -> SensorInfo {
+    // 按设备目录分组：目录 -> (文件名 -> 内容)
+    let mut devices: BTreeMap<&str, HashMap<&str, &str>> = BTreeMap::new();
+    for line in text.lines() {
+        let (path, value) = match line.split_once('=') {
+            Some((path, value)) if !value.trim().is_empty() => (path, value.trim()),
+            _ => continue,
+        };
+        if let Some((dir, file)) = path.rsplit_once('/') {
+            devices.entry(dir).or_default().insert(file, value);
+        }
+    }
+
+    let mut sensors = SensorInfo::default();
+    for (dir, files) in &devices {
+        let device = dir.rsplit('/').next().unwrap_or(dir);
+
+        if dir.starts_with("/sys/class/thermal/") {
+            if let Some(celsius) = files.get("temp").and_then(|v| parse_millidegrees(v)) {
+                sensors.temperatures.push(TemperatureInfo {
+                    source: "thermal".to_string(),
+                    chip: files.get("type").unwrap_or(&device).to_string(),
+                    label: device.to_string(),
+                    celsius,
+                    critical: None,
+                });
+            }
+        } else if dir.starts_with("/sys/class/hwmon/") {
+            let chip = files.get("name").unwrap_or(&device).to_string();
+            let mut inputs: Vec<&&str> = files.keys().filter(|f| f.ends_with("_input")).collect();
+            inputs.sort_by_key(|f| sensor_index(f));
+
+            for input in inputs {
+                let prefix = input.trim_end_matches("_input");
+                let label = files
+                    .get(format!("{}_label", prefix).as_str())
+                    .unwrap_or(&prefix)
+                    .to_string();
+                let value = files[*input];
+
+                if prefix.starts_with("temp") {
+                    if let Some(celsius) = parse_millidegrees(value) {
+                        sensors.temperatures.push(TemperatureInfo {
+                            source: "hwmon".to_string(),
+                            chip: chip.clone(),
+                            label,
+                            celsius,
+                            critical: files
+                                .get(format!("{}_crit", prefix).as_str())
+                                .and_then(|v| parse_millidegrees(v)),
+                        });
+                    }
+                } else if prefix.starts_with("fan") {
+                    if let Ok(rpm) = value.parse::<u64>() {
+                        sensors.fans.push(FanInfo {
+                            chip: chip.clone(),
+                            label,
+                            rpm,
+                        });
+                    }
+                }
+            }
+        } else if dir.starts_with("/sys/class/power_supply/") {
+            sensors.power_supplies.push(PowerSupplyInfo {
+                name: device.to_string(),
+                kind: files.get("type").unwrap_or(&"Unknown").to_string(),
+                status: files.get("status").map(|v| v.to_string()),
+                capacity: files.get("capacity").and_then(|v| v.parse().ok()),
+                online: files.get("online").map(|v| *v == "1"),
+                power: files
+                    .get("power_now")
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .map(|microwatts| microwatts / 1_000_000.0),
+            });
+        }
+    }
+
+    sensors
+}
+
+/// 将毫摄氏度转换为摄氏度，过滤驱动返回的无效值
+fn parse_millidegrees(value: &str) -> Option<f64> {
+    let celsius = value.trim().parse::<f64>().ok()? / 1000.0;
+    if (-60.0..=250.0).contains(&celsius) {
+        Some(celsius)
+    } else {
+        None
+    }
+}
+
+/// 取传感器文件名中的序号用于排序，如 "temp10_input" -> 10
+fn sensor_index(file: &str) -> u32 {
+    file.chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_STAT: &str = "\
+cpu  10132153 290696 3084719 46828483 16683 0 25195 0 175628 0
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 23933 0
+cpu1 1335446 29408 487416 11124562 4126 0 3346 0 23578 0
+intr 199292250 40 9 0 0 0 0 3 0 1 0 0 36 3
+ctxt 480326112
+btime 1697007651
+processes 1234567
+";
+
+    const MEMINFO: &str = "\
+MemTotal:       16318504 kB
+MemFree:         1503276 kB
+MemAvailable:    9562644 kB
+Buffers:          612340 kB
+Cached:          7012132 kB
+SwapCached:            0 kB
+SReclaimable:     512004 kB
+";
+
+    const NET_DEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 81520633  543221    0    0    0     0          0         0 81520633  543221    0    0    0     0       0          0
+  eth0: 9825439231 8291542    3   17    0     0          0     12345 1203948223 5039211    1    2    0     0       0          0
+";
+
+    const DISKSTATS: &str = "\
+   7       0 loop0 43 0 796 10 0 0 0 0 0 24 10 0 0 0 0
+   8       0 sda 142891 53211 7012318 56120 532011 402218 18923344 612003 0 381220 668123 0 0 0 0
+   8       1 sda1 141203 53211 6998222 55980 532011 402218 18923344 612003 0 381100 668000 0 0 0 0
+ 259       0 nvme0n1 9021 12 602118 2310 88120 7012 2031288 41022 0 51230 43332 0 0 0 0
+ 259       1 nvme0n1p1 8923 12 598110 2300 88120 7012 2031288 41022 0 51200 43322 0 0 0 0
+ 253       0 dm-0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+";
+
+    const DF: &str = "\
+Filesystem     Type     1024-blocks      Used Available Capacity Mounted on
+/dev/sda1      ext4        41152812  19524704  19514404      51% /
+tmpfs          tmpfs        1631852      1244   1630608       1% /run
+/dev/sdb1      xfs        104806400  52403200  52403200      50% /mnt/backup disk
+overlay        overlay     41152812  19524704  19514404      51% /var/lib/docker/overlay2/abc/merged
+";
+
+    #[test]
+    fn parses_proc_stat_total_and_cores() {
+        let cpu = parse_proc_stat(PROC_STAT);
+        assert_eq!(cpu.len(), 3);
+        assert_eq!(cpu[0].idle, 46828483 + 16683);
+        // guest/guest_nice 不计入总数
+        assert_eq!(cpu[0].total, 10132153 + 290696 + 3084719 + 46828483 + 16683 + 25195);
+        assert_eq!(cpu[2].idle, 11124562 + 4126);
+    }
+
+    #[test]
+    fn cpu_usage_uses_delta_and_falls_back_on_first_sample_or_wrap() {
+        let previous = CpuTimes { idle: 900, total: 1000 };
+        let current = CpuTimes { idle: 1150, total: 1500 };
+        assert_eq!(cpu_usage_between(Some(&previous), &current), 50.0);
+
+        // 没有上一次采样或计数器回绕（如重启）时使用开机以来的平均值
+        let since_boot = (1.0 - 1150.0 / 1500.0) * 100.0;
+        let since_boot = (since_boot * 10.0_f64).round() / 10.0;
+        assert_eq!(cpu_usage_between(None, &current), since_boot);
+        let wrapped = CpuTimes { idle: 5000, total: 9000 };
+        assert_eq!(cpu_usage_between(Some(&wrapped), &current), since_boot);
+
+        assert_eq!(cpu_usage_between(None, &CpuTimes { idle: 0, total: 0 }), 0.0);
+    }
+
+    #[test]
+    fn parses_meminfo_with_and_without_mem_available() {
+        let memory = parse_meminfo(MEMINFO);
+        assert_eq!(memory.total, 16318504 * 1024);
+        assert_eq!(memory.available, 9562644 * 1024);
+        assert_eq!(memory.used, (16318504 - 9562644) * 1024);
+        assert_eq!(memory.cached, Some((7012132 + 512004) * 1024));
+
+        let old_kernel: String = MEMINFO.lines().filter(|l| !l.starts_with("MemAvailable")).map(|l| format!("{}\n", l)).collect();
+        let memory = parse_meminfo(&old_kernel);
+        assert_eq!(memory.available, (1503276 + 612340 + 7012132 + 512004) * 1024);
+    }
+
+    #[test]
+    fn parses_loadavg() {
+        assert_eq!(parse_loadavg("0.52 0.41 0.38 2/1234 56789\n"), "0.52, 0.41, 0.38");
+        assert_eq!(parse_loadavg(""), "");
+    }
+
+    #[test]
+    fn parses_cpu_frequency_from_cpufreq_or_cpuinfo() {
+        assert_eq!(parse_cpu_frequency("2400000\n3600000\n"), 3000.0);
+        assert_eq!(parse_cpu_frequency("cpu MHz\t\t: 2000.000\ncpu MHz\t\t: 3000.000\n"), 2500.0);
+        assert_eq!(parse_cpu_frequency(""), 0.0);
+    }
+
+    #[test]
+    fn parses_net_dev() {
+        let interfaces = parse_net_dev(NET_DEV);
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(interfaces[1], InterfaceCounters {
+            name: "eth0".to_string(),
+            rx_bytes: 9825439231,
+            rx_errors: 3,
+            rx_dropped: 17,
+            tx_bytes: 1203948223,
+            tx_errors: 1,
+            tx_dropped: 2,
+        });
+    }
+
+    #[test]
+    fn parses_diskstats_whole_devices_only() {
+        let disks = parse_diskstats(DISKSTATS);
+        let names: Vec<&str> = disks.iter().map(|d| d.device.as_str()).collect();
+        assert_eq!(names, vec!["sda", "nvme0n1"]);
+        assert_eq!(disks[0], DiskCounters {
+            device: "sda".to_string(),
+            reads: 142891,
+            read_sectors: 7012318,
+            writes: 532011,
+            write_sectors: 18923344,
+            io_ms: 381220,
+        });
+    }
+
+    #[test]
+    fn parses_df_skipping_pseudo_filesystems() {
+        let disks = parse_df(DF);
+        assert_eq!(disks.len(), 2);
+        assert_eq!(disks[0].mount, "/");
+        assert_eq!(disks[0].filesystem, "ext4");
+        assert_eq!(disks[0].total, 41152812 * 1024);
+        assert_eq!(disks[0].usage, 51.0);
+        assert_eq!(disks[1].mount, "/mnt/backup disk");
+    }
+
+    #[test]
+    fn interface_rates_handle_first_sample_and_counter_wrap() {
+        let previous = InterfaceCounters {
+            name: "eth0".to_string(),
+            rx_bytes: 1000,
+            rx_errors: 0,
+            rx_dropped: 0,
+            tx_bytes: 5000,
+            tx_errors: 0,
+            tx_dropped: 0,
+        };
+        let current = InterfaceCounters { rx_bytes: 5000, tx_bytes: 100, ..previous.clone() };
+
+        let info = interface_info_between(Some(&previous), &current, 2.0);
+        assert_eq!(info.rx_rate, 2000);
+        assert_eq!(info.tx_rate, 0); // 计数器回绕
+        assert_eq!(info.rx_total, 5000);
+
+        let first = interface_info_between(None, &current, 0.0);
+        assert_eq!((first.rx_rate, first.tx_rate), (0, 0));
+    }
+
+    #[test]
+    fn disk_io_rates_handle_first_sample_and_counter_wrap() {
+        let previous = DiskCounters {
+            device: "sda".to_string(),
+            reads: 100,
+            read_sectors: 1000,
+            writes: 200,
+            write_sectors: 4000,
+            io_ms: 1000,
+        };
+        let current = DiskCounters {
+            reads: 150,
+            read_sectors: 3000,
+            writes: 100,
+            write_sectors: 2000,
+            io_ms: 1500,
+            ..previous.clone()
+        };
+
+        let info = disk_io_between(Some(&previous), &current, 2.0);
+        assert_eq!(info.read_iops, 25.0);
+        assert_eq!(info.read_rate, 2000 * 512 / 2);
+        assert_eq!(info.utilization, 25.0);
+        // 计数器回绕（设备重新挂载等）
+        assert_eq!(info.write_iops, 0.0);
+        assert_eq!(info.write_rate, 0);
+
+        let first = disk_io_between(None, &current, 2.0);
+        assert_eq!((first.read_iops, first.read_rate, first.utilization), (0.0, 0, 0.0));
+        let no_elapsed = disk_io_between(Some(&previous), &current, 0.0);
+        assert_eq!(no_elapsed.read_rate, 0);
+    }
+
+    const SENSORS: &str = "\
+/sys/class/thermal/thermal_zone0/type=x86_pkg_temp
+/sys/class/thermal/thermal_zone0/temp=52000
+/sys/class/thermal/thermal_zone1/type=acpitz
+/sys/class/thermal/thermal_zone1/temp=
+/sys/class/hwmon/hwmon1/name=coretemp
+/sys/class/hwmon/hwmon1/temp1_label=Package id 0
+/sys/class/hwmon/hwmon1/temp1_input=54000
+/sys/class/hwmon/hwmon1/temp1_crit=100000
+/sys/class/hwmon/hwmon1/temp10_input=49500
+/sys/class/hwmon/hwmon1/temp2_input=48000
+/sys/class/hwmon/hwmon1/temp3_input=-273150
+/sys/class/hwmon/hwmon2/name=thinkpad
+/sys/class/hwmon/hwmon2/fan1_input=2950
+/sys/class/hwmon/hwmon2/fan2_label=GPU
+/sys/class/hwmon/hwmon2/fan2_input=0
+/sys/class/power_supply/BAT0/type=Battery
+/sys/class/power_supply/BAT0/status=Discharging
+/sys/class/power_supply/BAT0/capacity=87
+/sys/class/power_supply/BAT0/power_now=12500000
+/sys/class/power_supply/AC/type=Mains
+/sys/class/power_supply/AC/online=0
+";
+
+    #[test]
+    fn parses_thermal_zones_and_skips_empty_values() {
+        let sensors = parse_sensors(SENSORS);
+        let thermal: Vec<_> = sensors.temperatures.iter().filter(|t| t.source == "thermal").collect();
+        assert_eq!(thermal.len(), 1);
+        assert_eq!(thermal[0].chip, "x86_pkg_temp");
+        assert_eq!(thermal[0].label, "thermal_zone0");
+        assert_eq!(thermal[0].celsius, 52.0);
+        assert_eq!(thermal[0].critical, None);
+    }
+
+    #[test]
+    fn parses_hwmon_temperatures_and_fans_with_and_without_labels() {
+        let sensors = parse_sensors(SENSORS);
+        let hwmon: Vec<_> = sensors
+            .temperatures
+            .iter()
+            .filter(|t| t.source == "hwmon")
+            .map(|t| (t.chip.as_str(), t.label.as_str(), t.celsius, t.critical))
+            .collect();
+        // 按序号排序，无效读数跳过
+        assert_eq!(
+            hwmon,
+            vec![
+                ("coretemp", "Package id 0", 54.0, Some(100.0)),
+                ("coretemp", "temp2", 48.0, None),
+                ("coretemp", "temp10", 49.5, None),
+            ]
+        );
+
+        let fans: Vec<_> = sensors
+            .fans
+            .iter()
+            .map(|f| (f.chip.as_str(), f.label.as_str(), f.rpm))
+            .collect();
+        assert_eq!(fans, vec![("thinkpad", "fan1", 2950), ("thinkpad", "GPU", 0)]);
+    }
+
+    #[test]
+    fn parses_power_supplies() {
+        let sensors = parse_sensors(SENSORS);
+        assert_eq!(sensors.power_supplies.len(), 2);
+
+        let ac = &sensors.power_supplies[0];
+        assert_eq!((ac.name.as_str(), ac.kind.as_str()), ("AC", "Mains"));
+        assert_eq!(ac.online, Some(false));
+        assert_eq!((ac.status.as_deref(), ac.capacity, ac.power), (None, None, None));
+
+        let battery = &sensors.power_supplies[1];
+        assert_eq!((battery.name.as_str(), battery.kind.as_str()), ("BAT0", "Battery"));
+        assert_eq!(battery.status.as_deref(), Some("Discharging"));
+        assert_eq!(battery.capacity, Some(87.0));
+        assert_eq!(battery.power, Some(12.5));
+        assert_eq!(battery.online, None);
+    }
+
+    #[test]
+    fn parses_empty_or_missing_sensor_sections() {
+        for text in ["", "\n", "no separator\n/sys/class/hwmon/hwmon0/name=acpitz\n"] {
+            let sensors = parse_sensors(text);
+            assert!(sensors.temperatures.is_empty());
+            assert!(sensors.fans.is_empty());
+            assert!(sensors.power_supplies.is_empty());
+        }
+
+        let sections = split_sections("==os==\nLinux\n==stat==\ncpu 1 2 3 4\n");
+        let raw = parse_linux_sample(&sections).unwrap();
+        assert!(raw.sensors.temperatures.is_empty());
+    }
+}
@@ -0,0 +1,474 @@
+/**
+ * FreeBSD 和 macOS 系统监控数据采集
+ */
+
+use std::collections::HashMap;
+use crate::monitor::{CpuTimes, DiskInfo, InterfaceCounters, MemoryInfo, RawSample, SensorInfo, PSEUDO_FILESYSTEMS};
+
+/// FreeBSD/macOS 上一次性采集所有指标的远程命令，各部分输出以 `==名称==` 行分隔
+pub(crate) const BSD_COLLECT_COMMAND: &str = "echo '==sysctl=='; \
+for k in hw.ncpu hw.physmem hw.memsize hw.pagesize vm.loadavg kern.cp_time kern.cp_times \
+hw.cpufrequency dev.cpu.0.freq vm.stats.vm.v_free_count vm.stats.vm.v_inactive_count vm.stats.vm.v_cache_count; \
+do sysctl \"$k\" 2>/dev/null; done; \
+echo '==vmstat=='; vm_stat 2>/dev/null; \
+echo '==pscpu=='; [ \"$(uname -s)\" = Darwin ] && ps -A -o %cpu=; \
+echo '==netstat=='; netstat -ibn 2>/dev/null; \
+echo '==mount=='; mount 2>/dev/null; \
+echo '==df=='; df -kP 2>/dev/null";
+
+/// 解析 FreeBSD/macOS 采集命令的输出
+///
+/// - CPU：FreeBSD 使用 `kern.cp_time`/`kern.cp_times` 累计时间；macOS 没有对应 sysctl，
+///   改为汇总 `ps` 的各进程 CPU 占用，此时没有各核心使用率
+/// - 内存：FreeBSD 使用 `vm.stats.vm.*` 页数；macOS 使用 `vm_stat`
+/// - 网卡：`netstat -ibn` 的链路层统计
+/// - 磁盘：`df -kP`，文件系统类型取自 `mount`
+///
+/// 磁盘 I/O 和传感器数据暂不采集。
+pub(crate) fn parse_bsd_sample(os: &str, sections: &HashMap<&str, &str>) -> Result<RawSample, String> {
+    let section = |name: &str| sections.get(name).copied().unwrap_or("");
+    let sysctl = parse_sysctl(section("sysctl"));
+    let number = |key: &str| sysctl.get(key).and_then(|v| v.trim().parse::<u64>().ok());
+
+    let cores = number("hw.ncpu").unwrap_or(0) as usize;
+    if cores == 0 {
+        return Err("无法读取 sysctl 输出".to_string());
+    }
+
+    let (cpu, cpu_usage) = if os == "Darwin" {
+        (Vec::new(), Some(parse_ps_cpu(section("pscpu"), cores)))
+    } else {
+        (parse_cp_times(&sysctl), None)
+    };
+
+    let memory = if os == "Darwin" {
+        parse_vm_stat(section("vmstat"), number("hw.memsize").unwrap_or(0), number("hw.pagesize").unwrap_or(4096))
+    } else {
+        parse_freebsd_memory(&sysctl)
+    };
+
+    // macOS 的 hw.cpufrequency 单位为 Hz（Apple Silicon 上不存在），FreeBSD 的 dev.cpu.0.freq 单位为 MHz
+    let frequency = number("hw.cpufrequency")
+        .map(|hz| hz as f64 / 1_000_000.0)
+        .or_else(|| number("dev.cpu.0.freq").map(|mhz| mhz as f64))
+        .unwrap_or(0.0);
+
+    Ok(RawSample {
+        cpu,
+        cpu_usage,
+        cores,
+        frequency,
+        load_average: sysctl.get("vm.loadavg").map(|v| parse_bsd_loadavg(v)).unwrap_or_default(),
+        memory,
+        disk: parse_bsd_df(section("df"), &parse_mount(section("mount"))),
+        interfaces: parse_netstat_ib(section("netstat")),
+        disks: Vec::new(),
+        sensors: SensorInfo::default(),
+    })
+}
+
+/// 解析 `sysctl` 输出，兼容 `key: value` 和 `key=value` 两种格式
+fn parse_sysctl(text: &str) -> HashMap<&str, &str> {
+    text.lines()
+        .filter_map(|line| {
+            let index = line.find([':', '='])?;
+            Some((line[..index].trim(), line[index + 1..].trim()))
+        })
+        .collect()
+}
+
+/// 解析 `vm.loadavg`，如 `{ 0.52 0.41 0.38 }`
+fn parse_bsd_loadavg(value: &str) -> String {
+    value
+        .split_whitespace()
+        .filter(|v| *v != "{" && *v != "}")
+        .take(3)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 解析 FreeBSD 的 `kern.cp_time`（总计）和 `kern.cp_times`（各核心依次排列），
+/// 每个 CPU 依次为 user nice sys intr idle 五项
+fn parse_cp_times(sysctl: &HashMap<&str, &str>) -> Vec<CpuTimes> {
+    let values = |key: &str| -> Vec<u64> {
+        sysctl
+            .get(key)
+            .map(|v| v.split_whitespace().filter_map(|n| n.parse().ok()).collect())
+            .unwrap_or_default()
+    };
+    let to_times = |states: &[u64]| CpuTimes {
+        idle: states.last().copied().unwrap_or(0),
+        total: states.iter().sum(),
+    };
+
+    let total = values("kern.cp_time");
+    if total.is_empty() {
+        return Vec::new();
+    }
+    let mut cpu = vec![to_times(&total)];
+
+    let per_core = values("kern.cp_times");
+    if !per_core.is_empty() && per_core.len() % total.len() == 0 {
+        cpu.extend(per_core.chunks(total.len()).map(to_times));
+    }
+    cpu
+}
+
+/// 汇总 `ps -A -o %cpu=` 的各进程 CPU 占用，换算为整机使用率 (0-100)
+fn parse_ps_cpu(text: &str, cores: usize) -> f64 {
+    let sum: f64 = text.lines().filter_map(|line| line.trim().parse::<f64>().ok()).sum();
+    (sum / cores.max(1) as f64).clamp(0.0, 100.0)
+}
+
+/// 根据 FreeBSD 的 `hw.physmem` 和 `vm.stats.vm.*` 页数计算内存使用情况
+///
+/// 空闲、非活动和缓存页都可以直接回收，计为可用内存。
+fn parse_freebsd_memory(sysctl: &HashMap<&str, &str>) -> MemoryInfo {
+    let number = |key: &str| sysctl.get(key).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+    let page_size = number("hw.pagesize").max(1);
+
+    let total = number("hw.physmem");
+    let inactive = number("vm.stats.vm.v_inactive_count") * page_size;
+    let available = (number("vm.stats.vm.v_free_count") * page_size + inactive
+        + number("vm.stats.vm.v_cache_count") * page_size)
+        .min(total);
+
+    MemoryInfo {
+        total,
+        used: total - available,
+        cached: Some(inactive),
+        available,
+    }
+}
+
+/// 解析 macOS 的 `vm_stat` 输出
+///
+/// 页大小优先取标题行中的 `page size of N bytes`。空闲、非活动和预读页计为可用内存，
+/// 文件缓存页计为缓存。
+fn parse_vm_stat(text: &str, total: u64, default_page_size: u64) -> MemoryInfo {
+    let mut page_size = default_page_size;
+    let mut pages: HashMap<&str, u64> = HashMap::new();
+    for line in text.lines() {
+        if let Some(rest) = line.split("page size of ").nth(1) {
+            if let Some(size) = rest.split_whitespace().next().and_then(|v| v.parse().ok()) {
+                page_size = size;
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            if let Ok(count) = value.trim().trim_end_matches('.').parse::<u64>() {
+                pages.insert(key.trim().trim_matches('"'), count);
+            }
+        }
+    }
+    let bytes = |key: &str| pages.get(key).copied().unwrap_or(0) * page_size;
+
+    let available = (bytes("Pages free") + bytes("Pages inactive") + bytes("Pages speculative")).min(total);
+    MemoryInfo {
+        total,
+        used: total - available,
+        cached: pages.get("File-backed pages").map(|_| bytes("File-backed pages")),
+        available,
+    }
+}
+
+/// 解析 `netstat -ibn`，只取链路层（`<Link#N>`）行，不含回环网卡
+///
+/// 地址列可能为空，因此统计列按表头从右往左对齐；不同系统的列不完全相同
+/// （FreeBSD 有 `Idrop`，macOS 没有），缺少的列记为 0。
+fn parse_netstat_ib(text: &str) -> Vec<InterfaceCounters> {
+    let mut lines = text.lines();
+    let header: Vec<&str> = match lines.next() {
+        Some(line) => line.split_whitespace().collect(),
+        None => return Vec::new(),
+    };
+    let first_counter = match header.iter().position(|h| *h == "Ipkts") {
+        Some(index) => index,
+        None => return Vec::new(),
+    };
+    let counters = &header[first_counter..];
+
+    let mut interfaces: Vec<InterfaceCounters> = Vec::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < counters.len() + 3 || !fields[2].starts_with("<Link") {
+            continue;
+        }
+        let name = fields[0].trim_end_matches('*');
+        if name.starts_with("lo") || interfaces.iter().any(|i| i.name == name) {
+            continue;
+        }
+
+        let values = &fields[fields.len() - counters.len()..];
+        let get = |column: &str| {
+            counters
+                .iter()
+                .position(|c| *c == column)
+                .and_then(|i| values[i].parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+        interfaces.push(InterfaceCounters {
+            name: name.to_string(),
+            rx_bytes: get("Ibytes"),
+            rx_errors: get("Ierrs"),
+            rx_dropped: get("Idrop"),
+            tx_bytes: get("Obytes"),
+            tx_errors: get("Oerrs"),
+            tx_dropped: get("Odrop"),
+        });
+    }
+    interfaces
+}
+
+/// 解析 `mount` 输出，返回挂载点到文件系统类型的映射
+///
+/// 格式为 `<设备> on <挂载点> (<类型>, <选项>...)`。
+fn parse_mount(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (mount, options) = rest.rsplit_once(" (")?;
+            let filesystem = options.trim_end_matches(')').split(',').next()?.trim();
+            Some((mount.to_string(), filesystem.to_string()))
+        })
+        .collect()
+}
+
+/// 解析 `df -kP` 输出（没有类型列，类型从 `mount` 输出中查找）
+///
+/// 设备名和挂载点都可能包含空格（如 macOS 的 `map auto_home`），因此以容量百分比列定位各字段。
+/// macOS 的 `/System/Volumes/*` 与根目录共享同一 APFS 容器，只保留数据卷。
+fn parse_bsd_df(text: &str, filesystems: &HashMap<String, String>) -> Vec<DiskInfo> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            // Filesystem 1024-blocks Used Available Capacity Mounted-on
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let capacity = fields.iter().skip(4).position(|f| f.ends_with('%'))? + 4;
+            let mount = fields[capacity + 1..].join(" ");
+            if mount.is_empty() || (mount.starts_with("/System/Volumes/") && mount != "/System/Volumes/Data") {
+                return None;
+            }
+            let filesystem = filesystems.get(&mount).cloned().unwrap_or_default();
+            if PSEUDO_FILESYSTEMS.contains(&filesystem.as_str()) {
+                return None;
+            }
+
+            let total = fields[capacity - 3].parse::<u64>().ok()? * 1024;
+            let used = fields[capacity - 2].parse::<u64>().ok()? * 1024;
+            let available = fields[capacity - 1].parse::<u64>().ok()? * 1024;
+            if total == 0 {
+                return None;
+            }
+            let usage = fields[capacity]
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .unwrap_or_else(|_| used as f64 * 100.0 / (used + available).max(1) as f64);
+
+            Some(DiskInfo {
+                mount,
+                filesystem,
+                total,
+                used,
+                available,
+                usage,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::split_sections;
+
+    const FREEBSD_SYSCTL: &str = "\
+hw.ncpu: 2
+hw.physmem: 8589934592
+hw.pagesize: 4096
+vm.loadavg: { 0.52 0.41 0.38 }
+kern.cp_time: 1000 10 500 40 8450
+kern.cp_times: 600 5 300 20 4075 400 5 200 20 4375
+dev.cpu.0.freq: 2400
+vm.stats.vm.v_free_count: 1000000
+vm.stats.vm.v_inactive_count: 200000
+vm.stats.vm.v_cache_count: 0
+";
+
+    const FREEBSD_NETSTAT: &str = "\
+Name    Mtu Network        Address              Ipkts Ierrs Idrop     Ibytes    Opkts Oerrs     Obytes  Coll
+em0    1500 <Link#1>       08:00:27:aa:bb:cc   123456     1     2  987654321    65432     3   12345678     0
+em0       - 192.168.1.0/24 192.168.1.10        120000     -     -  980000000    65000     -   12300000     -
+lo0   16384 <Link#2>       lo0                    100     0     0       5000      100     0       5000     0
+";
+
+    const MACOS_NETSTAT: &str = "\
+Name       Mtu   Network       Address            Ipkts Ierrs     Ibytes    Opkts Oerrs     Obytes  Coll
+lo0        16384 <Link#1>                          1234     0     567890     1234     0     567890     0
+en0        1500  <Link#4>    a4:83:e7:12:34:56  2345678     4 3123456789  1234567     5  234567890     0
+en0        1500  fe80::1%en0 fe80:4::1          2345678     - 3123456789  1234567     -  234567890     -
+utun0*     1380  <Link#12>                            0     0          0        5     0        640     0
+";
+
+    const MACOS_VM_STAT: &str = "\
+Mach Virtual Memory Statistics: (page size of 16384 bytes)
+Pages free:                               12345.
+Pages active:                            200000.
+Pages inactive:                          190000.
+Pages speculative:                         5000.
+File-backed pages:                       150000.
+\"Translation faults\":                123456789.
+";
+
+    const MACOS_MOUNT: &str = "\
+/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)
+devfs on /dev (devfs, local, nobrowse)
+/dev/disk3s6 on /System/Volumes/VM (apfs, local, noexec, journaled, noatime, nobrowse)
+/dev/disk3s5 on /System/Volumes/Data (apfs, local, journaled, nobrowse, protect)
+map auto_home on /System/Volumes/Data/home (autofs, automounted, nobrowse)
+//guest@nas/Media Files on /Volumes/Media Files (smbfs, nodev, nosuid, mounted by me)
+";
+
+    const MACOS_DF: &str = "\
+Filesystem              1024-blocks      Used Available Capacity  Mounted on
+/dev/disk3s1s1            482797652  10123456 300000000     4%    /
+devfs                           205       205         0   100%    /dev
+/dev/disk3s6              482797652   2097152 300000000     1%    /System/Volumes/VM
+/dev/disk3s5              482797652 170000000 300000000    37%    /System/Volumes/Data
+map auto_home                     0         0         0   100%    /System/Volumes/Data/home
+//guest@nas/Media Files   976762584 500000000 476762584    52%    /Volumes/Media Files
+";
+
+    #[test]
+    fn parses_sysctl_in_both_formats() {
+        let sysctl = parse_sysctl("hw.ncpu: 8\nhw.pagesize=16384\nvm.loadavg: { 1.50 1.20 0.90 }\ngarbage\n");
+        assert_eq!(sysctl.get("hw.ncpu"), Some(&"8"));
+        assert_eq!(sysctl.get("hw.pagesize"), Some(&"16384"));
+        assert_eq!(parse_bsd_loadavg(sysctl["vm.loadavg"]), "1.50, 1.20, 0.90");
+        assert_eq!(sysctl.len(), 3);
+    }
+
+    #[test]
+    fn parses_freebsd_cp_times() {
+        let cpu = parse_cp_times(&parse_sysctl(FREEBSD_SYSCTL));
+        assert_eq!(cpu.len(), 3);
+        assert_eq!(cpu[0], CpuTimes { idle: 8450, total: 10000 });
+        assert_eq!(cpu[1], CpuTimes { idle: 4075, total: 5000 });
+        assert_eq!(cpu[2], CpuTimes { idle: 4375, total: 5000 });
+
+        // 各核心数据不完整时只保留总计
+        let partial = parse_sysctl("kern.cp_time: 1 2 3 4 5\nkern.cp_times: 1 2 3\n");
+        assert_eq!(parse_cp_times(&partial).len(), 1);
+        assert!(parse_cp_times(&parse_sysctl("hw.ncpu: 2\n")).is_empty());
+    }
+
+    #[test]
+    fn parses_freebsd_memory() {
+        let memory = parse_freebsd_memory(&parse_sysctl(FREEBSD_SYSCTL));
+        assert_eq!(memory.total, 8589934592);
+        assert_eq!(memory.available, 1_200_000 * 4096);
+        assert_eq!(memory.used, 8589934592 - 1_200_000 * 4096);
+        assert_eq!(memory.cached, Some(200_000 * 4096));
+    }
+
+    #[test]
+    fn parses_macos_vm_stat() {
+        let total = 17179869184;
+        let memory = parse_vm_stat(MACOS_VM_STAT, total, 4096);
+        // 页大小取自标题行而不是 hw.pagesize
+        assert_eq!(memory.available, (12345 + 190000 + 5000) * 16384);
+        assert_eq!(memory.used, total - memory.available);
+        assert_eq!(memory.cached, Some(150000 * 16384));
+
+        let without_header = parse_vm_stat("Pages free: 10.\nPages inactive: 5.\n", 1_000_000, 4096);
+        assert_eq!(without_header.available, 15 * 4096);
+        assert_eq!(without_header.cached, None);
+        // 可用内存不超过总内存
+        assert_eq!(parse_vm_stat(MACOS_VM_STAT, 4096, 4096).used, 0);
+    }
+
+    #[test]
+    fn parses_netstat_ib_with_idrop_column() {
+        let interfaces = parse_netstat_ib(FREEBSD_NETSTAT);
+        assert_eq!(
+            interfaces,
+            vec![InterfaceCounters {
+                name: "em0".to_string(),
+                rx_bytes: 987654321,
+                rx_errors: 1,
+                rx_dropped: 2,
+                tx_bytes: 12345678,
+                tx_errors: 3,
+                tx_dropped: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_netstat_ib_without_idrop_column() {
+        let interfaces = parse_netstat_ib(MACOS_NETSTAT);
+        let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        // 地址列为空的行同样按表头右对齐，网卡名去掉 `*`
+        assert_eq!(names, vec!["en0", "utun0"]);
+        assert_eq!(interfaces[0].rx_bytes, 3123456789);
+        assert_eq!(interfaces[0].rx_errors, 4);
+        assert_eq!(interfaces[0].rx_dropped, 0);
+        assert_eq!(interfaces[0].tx_bytes, 234567890);
+        assert_eq!(interfaces[0].tx_errors, 5);
+        assert_eq!(interfaces[1].tx_bytes, 640);
+
+        assert!(parse_netstat_ib("").is_empty());
+        assert!(parse_netstat_ib("Name Mtu Network\nen0 1500 <Link#4>\n").is_empty());
+    }
+
+    #[test]
+    fn parses_mount_with_spaces() {
+        let mounts = parse_mount(MACOS_MOUNT);
+        assert_eq!(mounts["/"], "apfs");
+        assert_eq!(mounts["/dev"], "devfs");
+        assert_eq!(mounts["/System/Volumes/Data/home"], "autofs");
+        assert_eq!(mounts["/Volumes/Media Files"], "smbfs");
+        assert_eq!(mounts.len(), 6);
+    }
+
+    #[test]
+    fn parses_bsd_df_with_spaces_and_system_volumes() {
+        let disks = parse_bsd_df(MACOS_DF, &parse_mount(MACOS_MOUNT));
+        let mounts: Vec<&str> = disks.iter().map(|d| d.mount.as_str()).collect();
+        // devfs 和 map auto_home 被过滤，/System/Volumes/* 只保留数据卷
+        assert_eq!(mounts, vec!["/", "/System/Volumes/Data", "/Volumes/Media Files"]);
+
+        let share = &disks[2];
+        assert_eq!(share.filesystem, "smbfs");
+        assert_eq!(share.total, 976762584 * 1024);
+        assert_eq!(share.used, 500000000 * 1024);
+        assert_eq!(share.available, 476762584 * 1024);
+        assert_eq!(share.usage, 52.0);
+
+        // 找不到挂载信息时类型为空
+        let unknown = parse_bsd_df(MACOS_DF, &HashMap::new());
+        assert!(unknown.iter().any(|d| d.mount == "/dev" && d.filesystem.is_empty()));
+        assert!(!unknown.iter().any(|d| d.mount == "/System/Volumes/Data/home"));
+    }
+
+    #[test]
+    fn parses_freebsd_and_macos_samples() {
+        let freebsd = format!("==sysctl==\n{}==netstat==\n{}", FREEBSD_SYSCTL, FREEBSD_NETSTAT);
+        let raw = parse_bsd_sample("FreeBSD", &split_sections(&freebsd)).unwrap();
+        assert_eq!((raw.cores, raw.cpu.len(), raw.cpu_usage), (2, 3, None));
+        assert_eq!(raw.frequency, 2400.0);
+        assert_eq!(raw.load_average, "0.52, 0.41, 0.38");
+        assert_eq!(raw.interfaces.len(), 1);
+
+        let macos = format!(
+            "==sysctl==\nhw.ncpu: 4\nhw.memsize: 17179869184\n==vmstat==\n{}==pscpu==\n 50.0\n 30.0\n  0.0\n",
+            MACOS_VM_STAT
+        );
+        let raw = parse_bsd_sample("Darwin", &split_sections(&macos)).unwrap();
+        assert!(raw.cpu.is_empty());
+        assert_eq!(raw.cpu_usage, Some(20.0));
+        assert_eq!(raw.memory.total, 17179869184);
+
+        assert!(parse_bsd_sample("FreeBSD", &split_sections("==sysctl==\n")).is_err());
+    }
+}
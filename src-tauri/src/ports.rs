@@ -0,0 +1,570 @@
+/**
+ * 监听端口查看与本地端口转发相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::Window;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::task::JoinSet;
+use crate::ssh::{exec_command, is_connected, open_direct_tcpip_channel};
+
+/// 端口转发结束事件名
+const PORT_FORWARD_STOPPED_EVENT: &str = "port-forward-stopped";
+/// 端口转发连接失败事件名
+const PORT_FORWARD_ERROR_EVENT: &str = "port-forward-error";
+
+/// 正在运行的端口转发（forward_id -> 转发信息）
+type Forwards = Arc<Mutex<HashMap<String, ForwardEntry>>>;
+
+lazy_static::lazy_static! {
+    static ref FORWARDS: Forwards = Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// 端口转发记录
+struct ForwardEntry {
+    info: PortForwardInfo,
+    connections: Arc<AtomicUsize>,
+    stop: oneshot::Sender<()>,
+}
+
+/// 套接字信息
+#[derive(Debug, Clone, Serialize)]
+pub struct SocketInfo {
+    pub protocol: String,        // 协议，如 "tcp"、"udp"、"tcp6"
+    pub state: String,           // 状态，如 "LISTEN"、"ESTAB"、"UNCONN"
+    pub local_address: String,
+    pub local_port: u16,
+    pub peer_address: String,
+    pub peer_port: Option<u16>,  // 对端端口（监听套接字为 None）
+    pub process: Option<String>, // 所属进程名（非 root 用户只能看到自己的进程）
+    pub pid: Option<u32>,
+    pub forward_host: Option<String>, // 本地转发时应连接的远程地址（仅 TCP 监听端口）
+}
+
+/// 端口转发信息
+#[derive(Debug, Clone, Serialize)]
+pub struct PortForwardInfo {
+    pub forward_id: String,
+    pub server_id: String,
+    pub local_port: u16,      // 本地监听端口（仅绑定 127.0.0.1）
+    pub remote_host: String,  // 远程服务器视角下的目标地址
+    pub remote_port: u16,
+    pub connections: usize,   // 当前活动连接数
+}
+
+/// 列出端口参数
+#[derive(Debug, Deserialize)]
+pub struct ListPortsParams {
+    pub server_id: String,
+    pub include_connections: Option<bool>, // 是否包含已建立的连接（默认只列出监听端口）
+}
+
+/// 列出端口返回
+#[derive(Debug, Serialize)]
+pub struct ListPortsResult {
+    pub sockets: Vec<SocketInfo>,
+    pub source: String,         // 数据来源："ss" | "netstat"
+    pub process_complete: bool, // 是否能看到所有进程信息（否则部分套接字缺少所属进程）
+}
+
+/// 开始端口转发参数
+#[derive(Debug, Deserialize)]
+pub struct StartPortForwardParams {
+    pub server_id: String,
+    pub remote_host: Option<String>, // 远程目标地址（默认 127.0.0.1）
+    pub remote_port: u16,
+    pub local_port: Option<u16>,     // 本地端口（默认与远程端口相同，被占用时自动分配）
+}
+
+/// 停止端口转发参数
+#[derive(Debug, Deserialize)]
+pub struct StopPortForwardParams {
+    pub forward_id: String,
+}
+
+/// 停止端口转发返回
+#[derive(Debug, Serialize)]
+pub struct StopPortForwardResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 列出端口转发返回
+#[derive(Debug, Serialize)]
+pub struct ListPortForwardsResult {
+    pub forwards: Vec<PortForwardInfo>,
+}
+
+/// 端口转发结束事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct PortForwardStoppedEvent {
+    pub forward_id: String,
+    pub reason: String, // "stopped" | "disconnected"
+}
+
+/// 端口转发连接失败事件载荷（单个连接被断开，转发本身继续运行）
+#[derive(Debug, Clone, Serialize)]
+pub struct PortForwardErrorEvent {
+    pub forward_id: String,
+    pub message: String,
+}
+
+/// 列出远程服务器的监听端口（及已建立的连接）
+///
+/// 优先使用 `ss -tulpn`，没有 ss 时回退到 `netstat -tulpn`。
+///
+/// # 命令名称
+/// `list_listening_ports`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `include_connections`: 是否包含已建立的连接（可选）
+///
+/// # 返回
+/// - `sockets`: 套接字列表，按协议和端口排序
+/// - `source`: 数据来源
+/// - `process_complete`: 进程信息是否完整
+#[tauri::command]
+pub async fn list_listening_ports(params: ListPortsParams) -> Result<ListPortsResult, String> {
+    let flags = if params.include_connections.unwrap_or(false) { "-tuapn" } else { "-tulpn" };
+    let command = format!(
+        "if command -v ss >/dev/null 2>&1; then echo '==ss=='; ss {f}; \
+         else echo '==netstat=='; netstat {f}; fi; echo '==uid=='; id -u",
+        f = flags
+    );
+    let output = exec_command(&params.server_id, &command).await?;
+
+    let (source, body) = if let Some(rest) = output.stdout.split_once("==ss==\n").map(|(_, rest)| rest) {
+        ("ss", rest)
+    } else if let Some(rest) = output.stdout.split_once("==netstat==\n").map(|(_, rest)| rest) {
+        ("netstat", rest)
+    } else {
+        return Err("无法获取端口信息".to_string());
+    };
+    let (body, uid) = body.split_once("==uid==").unwrap_or((body, ""));
+
+    let mut sockets: Vec<SocketInfo> = if source == "ss" {
+        body.lines().filter_map(parse_ss_line).collect()
+    } else {
+        body.lines().filter_map(parse_netstat_line).collect()
+    };
+    if sockets.is_empty() && output.exit_code != 0 {
+        let message = output.stderr.lines().next().unwrap_or("").trim().to_string();
+        return Err(if message.is_empty() { "获取端口信息失败".to_string() } else { message });
+    }
+    sockets.sort_by(|a, b| {
+        a.protocol
+            .cmp(&b.protocol)
+            .then(a.local_port.cmp(&b.local_port))
+            .then(a.local_address.cmp(&b.local_address))
+    });
+
+    Ok(ListPortsResult {
+        sockets,
+        source: source.to_string(),
+        process_complete: uid.trim() == "0",
+    })
+}
+
+/// 开始本地端口转发
+///
+/// 在本机 127.0.0.1 上监听端口，每个连接通过 SSH 连接转发到远程服务器视角下的目标地址。
+/// 连接断开后转发自动停止并推送 `port-forward-stopped` 事件；
+/// 单个连接无法打开远程通道时推送 `port-forward-error` 事件并断开该连接。
+///
+/// # 命令名称
+/// `start_port_forward`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `remote_host`: 远程目标地址（可选）
+/// - `remote_port`: 远程目标端口
+/// - `local_port`: 本地端口（可选）
+///
+/// # 返回
+/// 端口转发信息（含实际使用的本地端口）
+#[tauri::command]
+pub async fn start_port_forward(window: Window, params: StartPortForwardParams) -> Result<PortForwardInfo, String> {
+    if !is_connected(&params.server_id) {
+        return Err("服务器未连接".to_string());
+    }
+    if params.remote_port == 0 {
+        return Err("远程端口无效".to_string());
+    }
+    let remote_host = params
+        .remote_host
+        .filter(|h| !h.trim().is_empty())
+        .unwrap_or_else(|| "127.0.0.1".to_string());
+
+    // 默认尝试与远程端口相同的本地端口，被占用（或无权限）时由系统分配
+    let listener = match params.local_port {
+        Some(port) => TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|e| format!("无法监听本地端口 {}: {}", port, e))?,
+        None => match TcpListener::bind(("127.0.0.1", params.remote_port)).await {
+            Ok(listener) => listener,
+            Err(_) => TcpListener::bind(("127.0.0.1", 0))
+                .await
+                .map_err(|e| format!("无法监听本地端口: {}", e))?,
+        },
+    };
+    let local_port = listener
+        .local_addr()
+        .map_err(|e| format!("无法获取本地端口: {}", e))?
+        .port();
+
+    let info = PortForwardInfo {
+        forward_id: format!("{}:{}", params.server_id, local_port),
+        server_id: params.server_id.clone(),
+        local_port,
+        remote_host: remote_host.clone(),
+        remote_port: params.remote_port,
+        connections: 0,
+    };
+    let connections = Arc::new(AtomicUsize::new(0));
+    let (stop_tx, mut stop_rx) = oneshot::channel();
+    FORWARDS.lock().unwrap().insert(info.forward_id.clone(), ForwardEntry {
+        info: info.clone(),
+        connections: connections.clone(),
+        stop: stop_tx,
+    });
+
+    let forward_id = info.forward_id.clone();
+    let server_id = params.server_id;
+    let remote_port = params.remote_port;
+    tokio::spawn(async move {
+        // 转发停止时 JoinSet 被丢弃，所有连接随之中止
+        let mut tasks = JoinSet::new();
+        let mut health = tokio::time::interval(std::time::Duration::from_secs(5));
+
+        let reason = loop {
+            tokio::select! {
+                _ = &mut stop_rx => break "stopped",
+                _ = health.tick() => {
+                    if !is_connected(&server_id) {
+                        break "disconnected";
+                    }
+                }
+                Some(_) = tasks.join_next() => {}
+                accepted = listener.accept() => {
+                    let (mut socket, peer) = match accepted {
+                        Ok(accepted) => accepted,
+                        Err(_) => continue,
+                    };
+                    // 通道在连接任务中打开，目标无响应时不会阻塞停止、健康检查和其他连接
+                    let window = window.clone();
+                    let forward_id = forward_id.clone();
+                    let server_id = server_id.clone();
+                    let remote_host = remote_host.clone();
+                    let connections = connections.clone();
+                    tasks.spawn(async move {
+                        let channel = match open_direct_tcpip_channel(&server_id, &remote_host, remote_port, peer).await {
+                            Ok(channel) => channel,
+                            Err(e) => {
+                                let _ = window.emit(PORT_FORWARD_ERROR_EVENT, PortForwardErrorEvent {
+                                    forward_id,
+                                    message: format!("无法连接 {}:{}: {}", remote_host, remote_port, e),
+                                });
+                                return;
+                            }
+                        };
+
+                        connections.fetch_add(1, Ordering::SeqCst);
+                        let mut stream = channel.into_stream();
+                        let _ = tokio::io::copy_bidirectional(&mut socket, &mut stream).await;
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+            }
+        };
+
+        FORWARDS.lock().unwrap().remove(&forward_id);
+        let _ = window.emit(PORT_FORWARD_STOPPED_EVENT, PortForwardStoppedEvent {
+            forward_id,
+            reason: reason.to_string(),
+        });
+    });
+
+    Ok(info)
+}
+
+/// 停止本地端口转发
+///
+/// # 命令名称
+/// `stop_port_forward`
+///
+/// # 参数
+/// - `forward_id`: 转发ID
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn stop_port_forward(params: StopPortForwardParams) -> Result<StopPortForwardResult, String> {
+    let entry = FORWARDS.lock().unwrap().remove(&params.forward_id);
+
+    match entry {
+        Some(entry) => {
+            let _ = entry.stop.send(());
+            Ok(StopPortForwardResult {
+                success: true,
+                message: Some("已停止端口转发".to_string()),
+            })
+        }
+        None => Ok(StopPortForwardResult {
+            success: true,
+            message: Some("端口转发已结束".to_string()),
+        }),
+    }
+}
+
+/// 列出正在运行的端口转发
+///
+/// # 命令名称
+/// `list_port_forwards`
+///
+/// # 返回
+/// - `forwards`: 端口转发列表
+#[tauri::command]
+pub async fn list_port_forwards() -> Result<ListPortForwardsResult, String> {
+    let forwards = FORWARDS.lock().unwrap();
+    let mut list: Vec<PortForwardInfo> = forwards
+        .values()
+        .map(|entry| PortForwardInfo {
+            connections: entry.connections.load(Ordering::SeqCst),
+            ..entry.info.clone()
+        })
+        .collect();
+    list.sort_by_key(|forward| forward.local_port);
+
+    Ok(ListPortForwardsResult { forwards: list })
+}
+
+/// 解析 `ss -tulpn` 数据行：
+/// `tcp LISTEN 0 4096 0.0.0.0:22 0.0.0.0:* users:(("sshd",pid=812,fd=3))`
+fn parse_ss_line(line: &str) -> Option<SocketInfo> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 6 || fields[0] == "Netid" {
+        return None;
+    }
+
+    let (local_address, local_port) = split_host_port(fields[4])?;
+    let local_port = local_port?;
+    let (peer_address, peer_port) = split_host_port(fields[5]).unwrap_or_default();
+    let (process, pid) = fields
+        .get(6..)
+        .map(|rest| parse_ss_process(&rest.join(" ")))
+        .unwrap_or_default();
+
+    Some(build_socket(fields[0], fields[1], local_address, local_port, peer_address, peer_port, process, pid))
+}
+
+/// 解析 ss 的进程字段，取第一个进程：`users:(("nginx",pid=100,fd=6),("nginx",pid=101,fd=6))`
+fn parse_ss_process(text: &str) -> (Option<String>, Option<u32>) {
+    let name = text
+        .split_once("((\"")
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(name, _)| name.to_string());
+    let pid = text
+        .split_once("pid=")
+        .and_then(|(_, rest)| rest.split(|c: char| !c.is_ascii_digit()).next())
+        .and_then(|pid| pid.parse().ok());
+    (name, pid)
+}
+
+/// 解析 `netstat -tulpn` 数据行（UDP 行可能没有状态列）：
+/// `tcp 0 0 0.0.0.0:22 0.0.0.0:* LISTEN 812/sshd`
+fn parse_netstat_line(line: &str) -> Option<SocketInfo> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 5 || !(fields[0].starts_with("tcp") || fields[0].starts_with("udp")) {
+        return None;
+    }
+
+    let (local_address, local_port) = split_host_port(fields[3])?;
+    let local_port = local_port?;
+    let (peer_address, peer_port) = split_host_port(fields[4]).unwrap_or_default();
+
+    let mut rest = &fields[5..];
+    let state = match rest.first() {
+        Some(state) if state.chars().all(|c| c.is_ascii_uppercase() || c == '_' || c == '2') => {
+            rest = &rest[1..];
+            *state
+        }
+        _ => "UNCONN",
+    };
+    // 新版 netstat 在程序名后附带截断的命令行：`812/sshd: /usr/sbin`
+    let program = rest.join(" ");
+    let (pid, process) = match program.split_once('/') {
+        Some((pid, name)) => (pid.parse().ok(), name.split(':').next().map(|n| n.trim().to_string())),
+        None => (None, None),
+    };
+
+    Some(build_socket(fields[0], state, local_address, local_port, peer_address, peer_port, process, pid))
+}
+
+/// 构建套接字信息，并为 TCP 监听端口计算本地转发的目标地址
+#[allow(clippy::too_many_arguments)]
+fn build_socket(
+    protocol: &str,
+    state: &str,
+    local_address: String,
+    local_port: u16,
+    peer_address: String,
+    peer_port: Option<u16>,
+    process: Option<String>,
+    pid: Option<u32>,
+) -> SocketInfo {
+    let listening = state == "LISTEN" && protocol.starts_with("tcp");
+    let forward_host = listening.then(|| forward_host_for(&local_address));
+
+    SocketInfo {
+        protocol: protocol.to_string(),
+        state: state.to_string(),
+        local_address,
+        local_port,
+        peer_address,
+        peer_port,
+        process,
+        pid,
+        forward_host,
+    }
+}
+
+/// 监听在所有地址上的端口转发到回环地址，否则转发到监听的具体地址
+fn forward_host_for(address: &str) -> String {
+    match address {
+        "0.0.0.0" | "*" | "" | "127.0.0.1" => "127.0.0.1".to_string(),
+        "::" | "::1" => "::1".to_string(),
+        address => address.to_string(),
+    }
+}
+
+/// 拆分 `地址:端口`，支持 `[::]:22`、`*:80`、`127.0.0.53%lo:53`、`:::22`（netstat）等格式，端口为 `*` 时返回 None
+fn split_host_port(text: &str) -> Option<(String, Option<u16>)> {
+    let (host, port) = text.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let host = host.split('%').next().unwrap_or(host);
+    Some((host.to_string(), port.parse().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `ss -tulpn` 输出（root 用户）
+    const SS_OUTPUT: &str = "\
+Netid State  Recv-Q Send-Q      Local Address:Port  Peer Address:PortProcess
+udp   UNCONN 0      0           127.0.0.53%lo:53         0.0.0.0:*    users:((\"systemd-resolve\",pid=640,fd=13))
+udp   UNCONN 0      0                 0.0.0.0:68         0.0.0.0:*    users:((\"dhclient\",pid=702,fd=7))
+tcp   LISTEN 0      4096              0.0.0.0:22         0.0.0.0:*    users:((\"sshd\",pid=812,fd=3))
+tcp   LISTEN 0      511               0.0.0.0:80         0.0.0.0:*    users:((\"nginx\",pid=1001,fd=6),(\"nginx\",pid=1000,fd=6))
+tcp   LISTEN 0      4096            127.0.0.1:5432       0.0.0.0:*
+tcp   LISTEN 0      4096                 [::]:22            [::]:*    users:((\"sshd\",pid=812,fd=4))
+tcp   LISTEN 0      128                     *:9100             *:*    users:((\"node_exporter\",pid=900,fd=3))
+tcp   ESTAB  0      36            10.0.0.5:22      203.0.113.7:51234 users:((\"sshd\",pid=2001,fd=4))
+";
+
+    /// `netstat -tulpn` 输出（net-tools）
+    const NETSTAT_OUTPUT: &str = "\
+Active Internet connections (only servers)
+Proto Recv-Q Send-Q Local Address           Foreign Address         State       PID/Program name
+tcp        0      0 0.0.0.0:22              0.0.0.0:*               LISTEN      812/sshd: /usr/sbin
+tcp        0      0 127.0.0.1:5432          0.0.0.0:*               LISTEN      -
+tcp6       0      0 :::22                   :::*                    LISTEN      812/sshd: /usr/sbin
+udp        0      0 127.0.0.53:53           0.0.0.0:*                           640/systemd-resolve
+udp6       0      0 fe80::1%eth0:546        :::*                                702/dhclient
+";
+
+    fn parse_ss(text: &str) -> Vec<SocketInfo> {
+        text.lines().filter_map(parse_ss_line).collect()
+    }
+
+    fn parse_netstat(text: &str) -> Vec<SocketInfo> {
+        text.lines().filter_map(parse_netstat_line).collect()
+    }
+
+    #[test]
+    fn parses_ss_listening_sockets() {
+        let sockets = parse_ss(SS_OUTPUT);
+        assert_eq!(sockets.len(), 8);
+
+        let resolver = &sockets[0];
+        assert_eq!((resolver.protocol.as_str(), resolver.state.as_str()), ("udp", "UNCONN"));
+        assert_eq!((resolver.local_address.as_str(), resolver.local_port), ("127.0.0.53", 53));
+        assert_eq!((resolver.peer_address.as_str(), resolver.peer_port), ("0.0.0.0", None));
+        assert_eq!(resolver.process.as_deref(), Some("systemd-resolve"));
+        assert_eq!(resolver.pid, Some(640));
+        assert_eq!(resolver.forward_host, None);
+
+        let nginx = &sockets[3];
+        assert_eq!((nginx.process.as_deref(), nginx.pid), (Some("nginx"), Some(1001)));
+        assert_eq!(nginx.forward_host.as_deref(), Some("127.0.0.1"));
+
+        let postgres = &sockets[4];
+        assert_eq!((postgres.process.as_deref(), postgres.pid), (None, None));
+
+        let sshd6 = &sockets[5];
+        assert_eq!((sshd6.local_address.as_str(), sshd6.local_port), ("::", 22));
+        assert_eq!(sshd6.forward_host.as_deref(), Some("::1"));
+
+        let exporter = &sockets[6];
+        assert_eq!((exporter.local_address.as_str(), exporter.peer_address.as_str()), ("*", "*"));
+        assert_eq!(exporter.forward_host.as_deref(), Some("127.0.0.1"));
+
+        let established = &sockets[7];
+        assert_eq!(established.state, "ESTAB");
+        assert_eq!((established.peer_address.as_str(), established.peer_port), ("203.0.113.7", Some(51234)));
+        assert_eq!(established.forward_host, None);
+    }
+
+    #[test]
+    fn parses_netstat_rows_with_and_without_state() {
+        let sockets = parse_netstat(NETSTAT_OUTPUT);
+        assert_eq!(sockets.len(), 5);
+
+        assert_eq!((sockets[0].state.as_str(), sockets[0].pid), ("LISTEN", Some(812)));
+        assert_eq!(sockets[0].process.as_deref(), Some("sshd"));
+        assert_eq!((sockets[1].process.as_deref(), sockets[1].pid), (None, None));
+        assert_eq!(sockets[1].forward_host.as_deref(), Some("127.0.0.1"));
+        assert_eq!((sockets[2].protocol.as_str(), sockets[2].local_address.as_str()), ("tcp6", "::"));
+        assert_eq!(sockets[2].peer_port, None);
+
+        let resolver = &sockets[3];
+        assert_eq!(resolver.state, "UNCONN");
+        assert_eq!((resolver.pid, resolver.process.as_deref()), (Some(640), Some("systemd-resolve")));
+        assert_eq!(resolver.forward_host, None);
+
+        assert_eq!((sockets[4].local_address.as_str(), sockets[4].local_port), ("fe80::1", 546));
+    }
+
+    #[test]
+    fn parses_ss_process_field() {
+        assert_eq!(
+            parse_ss_process("users:((\"nginx\",pid=100,fd=6),(\"nginx\",pid=101,fd=6))"),
+            (Some("nginx".to_string()), Some(100))
+        );
+        assert_eq!(parse_ss_process(""), (None, None));
+    }
+
+    #[test]
+    fn splits_host_and_port() {
+        assert_eq!(split_host_port("[::]:22"), Some(("::".to_string(), Some(22))));
+        assert_eq!(split_host_port("127.0.0.53%lo:53"), Some(("127.0.0.53".to_string(), Some(53))));
+        assert_eq!(split_host_port("*:*"), Some(("*".to_string(), None)));
+        assert_eq!(split_host_port(":::22"), Some(("::".to_string(), Some(22))));
+        assert_eq!(split_host_port("[fe80::1%eth0]:546"), Some(("fe80::1".to_string(), Some(546))));
+        assert_eq!(split_host_port("nocolon"), None);
+    }
+
+    #[test]
+    fn forwards_wildcard_listeners_to_loopback() {
+        assert_eq!(forward_host_for("0.0.0.0"), "127.0.0.1");
+        assert_eq!(forward_host_for("*"), "127.0.0.1");
+        assert_eq!(forward_host_for("::"), "::1");
+        assert_eq!(forward_host_for("10.0.0.5"), "10.0.0.5");
+    }
+}
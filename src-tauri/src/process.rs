@@ -0,0 +1,534 @@
+/**
+ * 远程进程管理相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use crate::ssh::exec_command;
+
+/// 列出进程的远程命令：第一行为远程当前时间，其后为 ps 输出
+///
+/// GNU ps 的 `user` 列默认截断为 8 个字符，优先使用 `user:32`，不支持时退回通用格式。
+const PS_COMMAND: &str = "date +%s; \
+LC_ALL=C ps -A -o pid=,ppid=,user:32=,pcpu=,pmem=,rss=,etime=,stat=,args= 2>/dev/null \
+|| LC_ALL=C ps -A -o pid=,ppid=,user=,pcpu=,pmem=,rss=,etime=,stat=,args=";
+
+/// 允许发送的信号
+const SIGNALS: &[&str] = &["TERM", "KILL", "HUP", "INT", "QUIT", "STOP", "CONT", "USR1", "USR2"];
+
+/// 进程信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    pub user: String,
+    pub cpu: f64,         // CPU 使用率 (%)
+    pub mem: f64,         // 内存使用率 (%)
+    pub rss: u64,         // 常驻内存 (字节)
+    pub start_time: i64,  // 启动时间（Unix 时间戳，秒）
+    pub elapsed: u64,     // 已运行时间（秒）
+    pub state: String,    // 进程状态，如 "S"、"R"、"Z"
+    pub command: String,  // 完整命令行
+}
+
+/// 进程树节点
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessNode {
+    #[serde(flatten)]
+    pub process: ProcessInfo,
+    pub children: Vec<ProcessNode>,
+}
+
+/// 获取进程列表参数
+#[derive(Debug, Deserialize)]
+pub struct ListProcessesParams {
+    pub server_id: String,
+    pub sort_by: Option<String>,    // "cpu"（默认）| "mem" | "rss" | "pid" | "start" | "user" | "command"
+    pub descending: Option<bool>,   // 是否降序（默认降序）
+    pub filter: Option<String>,     // 按命令行、用户或 PID 过滤（不区分大小写）
+    pub user: Option<String>,       // 只显示指定用户的进程
+    pub limit: Option<usize>,       // 最大返回条数（树形模式下不生效）
+    pub tree: Option<bool>,         // 是否以进程树返回
+}
+
+/// 获取进程列表返回
+#[derive(Debug, Serialize)]
+pub struct ListProcessesResult {
+    pub total: usize,                    // 远程进程总数
+    pub processes: Vec<ProcessInfo>,     // 过滤排序后的进程（树形模式下为空）
+    pub tree: Option<Vec<ProcessNode>>,  // 进程树（树形模式）
+}
+
+/// 发送信号参数
+#[derive(Debug, Deserialize)]
+pub struct SignalProcessesParams {
+    pub server_id: String,
+    pub pids: Vec<u32>,
+    pub signal: Option<String>, // 信号名（默认 "TERM"）
+}
+
+/// 单个进程的信号发送结果
+#[derive(Debug, Serialize)]
+pub struct SignalResult {
+    pub pid: u32,
+    pub command: Option<String>, // 发送前的命令行（进程不存在时为空）
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 发送信号返回
+#[derive(Debug, Serialize)]
+pub struct SignalProcessesResult {
+    pub success: bool,  // 是否全部成功
+    pub signal: String,
+    pub results: Vec<SignalResult>,
+}
+
+/// 调整优先级参数
+#[derive(Debug, Deserialize)]
+pub struct ReniceProcessParams {
+    pub server_id: String,
+    pub pid: u32,
+    pub nice: i32, // 新的 nice 值（-20 ~ 19）
+}
+
+/// 调整优先级返回
+#[derive(Debug, Serialize)]
+pub struct ReniceProcessResult {
+    pub success: bool,
+    pub pid: u32,
+    pub command: Option<String>,
+    pub old_nice: Option<i32>,
+    pub new_nice: i32,
+    pub message: Option<String>,
+}
+
+/// 获取远程进程列表
+///
+/// # 命令名称
+/// `list_processes`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `sort_by`: 排序字段（可选）
+/// - `descending`: 是否降序（可选）
+/// - `filter`: 过滤关键字（可选）
+/// - `user`: 用户（可选）
+/// - `limit`: 最大返回条数（可选）
+/// - `tree`: 是否以进程树返回（可选）
+///
+/// # 返回
+/// - `total`: 进程总数
+/// - `processes`: 进程列表
+/// - `tree`: 进程树（可选）
+#[tauri::command]
+pub async fn list_processes(params: ListProcessesParams) -> Result<ListProcessesResult, String> {
+    let output = exec_command(&params.server_id, PS_COMMAND).await?;
+    let all = parse_ps_output(&output.stdout);
+    if all.is_empty() {
+        return Err(format!("获取进程列表失败: {}", output.stderr.trim()));
+    }
+
+    let total = all.len();
+    let sort_by = params.sort_by.as_deref().unwrap_or("cpu");
+    let descending = params.descending.unwrap_or(true);
+    let filter = params.filter.as_deref().map(str::trim).filter(|f| !f.is_empty());
+    let matches = |p: &ProcessInfo| {
+        params.user.as_deref().map_or(true, |user| p.user == user) && filter.map_or(true, |f| process_matches(p, f))
+    };
+
+    if params.tree.unwrap_or(false) {
+        let mut tree = build_process_tree(all, &matches);
+        sort_tree(&mut tree, sort_by, descending);
+        return Ok(ListProcessesResult {
+            total,
+            processes: Vec::new(),
+            tree: Some(tree),
+        });
+    }
+
+    let mut processes: Vec<ProcessInfo> = all.into_iter().filter(|p| matches(p)).collect();
+    processes.sort_by(|a, b| compare_processes(a, b, sort_by, descending));
+    if let Some(limit) = params.limit {
+        processes.truncate(limit);
+    }
+
+    Ok(ListProcessesResult {
+        total,
+        processes,
+        tree: None,
+    })
+}
+
+/// 向远程进程发送信号
+///
+/// 每个进程单独发送并返回结果，结果中包含发送前的命令行，便于确认操作对象。
+///
+/// # 命令名称
+/// `signal_processes`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `pids`: 进程ID数组
+/// - `signal`: 信号名（可选）
+///
+/// # 返回
+/// - `success`: 是否全部成功
+/// - `signal`: 发送的信号
+/// - `results`: 各进程的结果
+#[tauri::command]
+pub async fn signal_processes(params: SignalProcessesParams) -> Result<SignalProcessesResult, String> {
+    let signal = params
+        .signal
+        .as_deref()
+        .unwrap_or("TERM")
+        .trim_start_matches("SIG")
+        .to_uppercase();
+    if !SIGNALS.contains(&signal.as_str()) {
+        return Err(format!("不支持的信号: {}", signal));
+    }
+    if params.pids.is_empty() {
+        return Err("未选择进程".to_string());
+    }
+    if params.pids.contains(&0) || params.pids.contains(&1) {
+        return Err("不能向 PID 0 或 1 发送信号".to_string());
+    }
+
+    // 每个进程输出三个以 NUL 结尾的字段：PID、命令行、结果（ok 或错误信息）
+    let script: Vec<String> = params
+        .pids
+        .iter()
+        .map(|pid| {
+            format!(
+                "c=$(ps -o args= -p {pid} 2>/dev/null); r=$(kill -s {sig} {pid} 2>&1) && r=ok; printf '%s\\0%s\\0%s\\0' {pid} \"$c\" \"$r\"",
+                pid = pid,
+                sig = signal
+            )
+        })
+        .collect();
+    let output = exec_command(&params.server_id, &script.join("; ")).await?;
+    let parsed = parse_command_results(&output.stdout);
+
+    let results: Vec<SignalResult> = params
+        .pids
+        .iter()
+        .map(|pid| match parsed.get(pid) {
+            Some((command, result)) => SignalResult {
+                pid: *pid,
+                command: command.clone(),
+                success: result == "ok",
+                message: if result == "ok" { None } else { Some(result.clone()) },
+            },
+            None => SignalResult {
+                pid: *pid,
+                command: None,
+                success: false,
+                message: Some("未返回结果".to_string()),
+            },
+        })
+        .collect();
+
+    Ok(SignalProcessesResult {
+        success: results.iter().all(|r| r.success),
+        signal,
+        results,
+    })
+}
+
+/// 调整远程进程优先级
+///
+/// 降低 nice 值（提高优先级）通常需要 root 权限。
+///
+/// # 命令名称
+/// `renice_process`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `pid`: 进程ID
+/// - `nice`: 新的 nice 值
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `pid`: 进程ID
+/// - `command`: 命令行（可选）
+/// - `old_nice`: 调整前的 nice 值（可选）
+/// - `new_nice`: 新的 nice 值
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn renice_process(params: ReniceProcessParams) -> Result<ReniceProcessResult, String> {
+    if !(-20..=19).contains(&params.nice) {
+        return Err("nice 值必须在 -20 到 19 之间".to_string());
+    }
+
+    let command = format!(
+        "c=$(ps -o ni=,args= -p {pid} 2>/dev/null); r=$(renice -n {nice} -p {pid} 2>&1 >/dev/null) && r=ok; printf '%s\\0%s\\0%s\\0' {pid} \"$c\" \"$r\"",
+        pid = params.pid,
+        nice = params.nice
+    );
+    let output = exec_command(&params.server_id, &command).await?;
+    let (info, result) = parse_command_results(&output.stdout)
+        .remove(&params.pid)
+        .ok_or_else(|| "调整优先级失败: 未返回结果".to_string())?;
+
+    // info 格式为 "<nice> <命令行>"
+    let (old_nice, command) = match info.as_deref().map(str::trim_start) {
+        Some(info) => match info.split_once(' ') {
+            Some((nice, command)) => (nice.parse().ok(), Some(command.trim().to_string())),
+            None => (info.parse().ok(), None),
+        },
+        None => (None, None),
+    };
+
+    let success = result == "ok";
+    Ok(ReniceProcessResult {
+        success,
+        pid: params.pid,
+        command,
+        old_nice,
+        new_nice: params.nice,
+        message: Some(if success { "调整成功".to_string() } else { result }),
+    })
+}
+
+/// 解析 ps 输出（第一行为远程当前时间）
+fn parse_ps_output(output: &str) -> Vec<ProcessInfo> {
+    let mut lines = output.lines();
+    let now: i64 = match lines.next().and_then(|l| l.trim().parse().ok()) {
+        Some(now) => now,
+        None => return Vec::new(),
+    };
+
+    lines.filter_map(|line| parse_ps_line(line, now)).collect()
+}
+
+/// 解析单行 ps 输出：pid ppid user pcpu pmem rss etime stat args
+fn parse_ps_line(line: &str, now: i64) -> Option<ProcessInfo> {
+    let mut rest = line.trim_start();
+    let mut fields = Vec::with_capacity(8);
+    for _ in 0..8 {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    let elapsed = parse_etime(fields[6])?;
+    Some(ProcessInfo {
+        pid: fields[0].parse().ok()?,
+        ppid: fields[1].parse().ok()?,
+        user: fields[2].to_string(),
+        cpu: fields[3].parse().unwrap_or(0.0),
+        mem: fields[4].parse().unwrap_or(0.0),
+        rss: fields[5].parse::<u64>().unwrap_or(0) * 1024,
+        start_time: now - elapsed as i64,
+        elapsed,
+        state: fields[7].to_string(),
+        command: rest.to_string(),
+    })
+}
+
+/// 解析 etime 格式 `[[dd-]hh:]mm:ss`，返回秒数
+fn parse_etime(etime: &str) -> Option<u64> {
+    let (days, clock) = match etime.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, etime),
+    };
+
+    let parts: Vec<u64> = clock
+        .split(':')
+        .map(|p| p.parse().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let seconds = match parts.as_slice() {
+        [m, s] => m * 60 + s,
+        [h, m, s] => h * 3600 + m * 60 + s,
+        _ => return None,
+    };
+
+    Some(days * 86400 + seconds)
+}
+
+/// 进程是否匹配过滤关键字
+fn process_matches(process: &ProcessInfo, filter: &str) -> bool {
+    let filter = filter.to_lowercase();
+    process.command.to_lowercase().contains(&filter)
+        || process.user.to_lowercase().contains(&filter)
+        || process.pid.to_string() == filter
+}
+
+/// 按指定字段比较两个进程
+fn compare_processes(a: &ProcessInfo, b: &ProcessInfo, sort_by: &str, descending: bool) -> Ordering {
+    let ordering = match sort_by {
+        "mem" => a.mem.partial_cmp(&b.mem).unwrap_or(Ordering::Equal),
+        "rss" => a.rss.cmp(&b.rss),
+        "pid" => a.pid.cmp(&b.pid),
+        "start" => a.start_time.cmp(&b.start_time),
+        "user" => a.user.cmp(&b.user),
+        "command" => a.command.cmp(&b.command),
+        _ => a.cpu.partial_cmp(&b.cpu).unwrap_or(Ordering::Equal),
+    };
+    let ordering = if descending { ordering.reverse() } else { ordering };
+    ordering.then_with(|| a.pid.cmp(&b.pid))
+}
+
+/// 构建进程树，只保留匹配的进程及其祖先
+fn build_process_tree<F: Fn(&ProcessInfo) -> bool>(processes: Vec<ProcessInfo>, matches: &F) -> Vec<ProcessNode> {
+    let pids: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+    let mut children: HashMap<u32, Vec<ProcessInfo>> = HashMap::new();
+    let mut roots = Vec::new();
+    for process in processes {
+        // 父进程不在列表中（或为自身）的作为根节点
+        if process.ppid != process.pid && pids.contains(&process.ppid) {
+            children.entry(process.ppid).or_default().push(process);
+        } else {
+            roots.push(process);
+        }
+    }
+
+    roots
+        .into_iter()
+        .filter_map(|process| build_node(process, &mut children, matches))
+        .collect()
+}
+
+/// 递归构建进程树节点，子树中没有匹配进程时返回 None
+fn build_node<F: Fn(&ProcessInfo) -> bool>(process: ProcessInfo, children: &mut HashMap<u32, Vec<ProcessInfo>>, matches: &F) -> Option<ProcessNode> {
+    let nodes: Vec<ProcessNode> = children
+        .remove(&process.pid)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|child| build_node(child, children, matches))
+        .collect();
+
+    if nodes.is_empty() && !matches(&process) {
+        return None;
+    }
+    Some(ProcessNode { process, children: nodes })
+}
+
+/// 递归排序进程树的兄弟节点
+fn sort_tree(nodes: &mut [ProcessNode], sort_by: &str, descending: bool) {
+    nodes.sort_by(|a, b| compare_processes(&a.process, &b.process, sort_by, descending));
+    for node in nodes.iter_mut() {
+        sort_tree(&mut node.children, sort_by, descending);
+    }
+}
+
+/// 解析 `PID\0信息\0结果\0` 格式的输出，返回 PID -> (信息, 结果)
+///
+/// 命令行和错误信息中可能包含制表符或换行，因此按 NUL 分隔、每三个字段为一条记录。
+fn parse_command_results(output: &str) -> HashMap<u32, (Option<String>, String)> {
+    let fields: Vec<&str> = output.split('\0').collect();
+    fields
+        .chunks_exact(3)
+        .filter_map(|record| {
+            let pid = record[0].trim().parse().ok()?;
+            let info = Some(record[1].trim()).filter(|c| !c.is_empty()).map(str::to_string);
+            let result = record[2].trim().to_string();
+            Some((pid, (info, result)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, ppid: u32, command: &str) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            ppid,
+            user: "root".to_string(),
+            cpu: 0.0,
+            mem: 0.0,
+            rss: 0,
+            start_time: 0,
+            elapsed: 0,
+            state: "S".to_string(),
+            command: command.to_string(),
+        }
+    }
+
+    fn pids(nodes: &[ProcessNode]) -> Vec<(u32, Vec<u32>)> {
+        nodes
+            .iter()
+            .map(|n| (n.process.pid, n.children.iter().map(|c| c.process.pid).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_ps_output_with_spaces_in_commands() {
+        let output = "1700000000\n\
+    1     0 root          0.0  0.1 11884 12-03:04:05 Ss   /sbin/init splash\n\
+  812     1 root          0.0  0.0  7200    01:02:03 Ss   sshd: /usr/sbin/sshd -D [listener] 0 of 10-100 startups\n\
+ 2001   812 www-data     12.5  3.2 204800      05:06 R+   python3 -m http.server  8000\n\
+    2     0 root          0.0  0.0     0 12-03:04:06 S    [kthreadd]\n";
+        let processes = parse_ps_output(output);
+        assert_eq!(processes.len(), 4);
+
+        let init = &processes[0];
+        assert_eq!((init.pid, init.ppid, init.user.as_str()), (1, 0, "root"));
+        assert_eq!(init.rss, 11884 * 1024);
+        assert_eq!(init.elapsed, 12 * 86400 + 3 * 3600 + 4 * 60 + 5);
+        assert_eq!(init.start_time, 1700000000 - init.elapsed as i64);
+        assert_eq!((init.state.as_str(), init.command.as_str()), ("Ss", "/sbin/init splash"));
+
+        assert_eq!(processes[1].command, "sshd: /usr/sbin/sshd -D [listener] 0 of 10-100 startups");
+        assert_eq!(processes[1].elapsed, 3723);
+
+        let python = &processes[2];
+        assert_eq!((python.cpu, python.mem, python.elapsed), (12.5, 3.2, 306));
+        // 命令行内部的连续空格保留
+        assert_eq!(python.command, "python3 -m http.server  8000");
+        assert_eq!(processes[3].command, "[kthreadd]");
+    }
+
+    #[test]
+    fn skips_malformed_ps_lines() {
+        assert!(parse_ps_output("not a timestamp\n    1     0 root 0.0 0.0 1 00:01 S init\n").is_empty());
+        assert!(parse_ps_line("    1     0 root 0.0 0.0 1 00:01 S", 0).is_none());
+        assert!(parse_ps_line("  abc     0 root 0.0 0.0 1 00:01 S init", 0).is_none());
+        assert!(parse_ps_line("    1     0 root 0.0 0.0 1 bad S init", 0).is_none());
+    }
+
+    #[test]
+    fn parses_etime_formats() {
+        assert_eq!(parse_etime("05:06"), Some(306));
+        assert_eq!(parse_etime("01:02:03"), Some(3723));
+        assert_eq!(parse_etime("1-02:03:04"), Some(86400 + 7384));
+        assert_eq!(parse_etime("123-00:00:01"), Some(123 * 86400 + 1));
+        assert_eq!(parse_etime("42"), None);
+        assert_eq!(parse_etime("1-2-03:04"), None);
+        assert_eq!(parse_etime("1:2:3:4"), None);
+        assert_eq!(parse_etime(""), None);
+    }
+
+    #[test]
+    fn parses_nul_separated_results_with_tabs_and_newlines() {
+        let output = "42\0python -c 'print(1)\tx\ny'\0ok\0\
+43\0\0kill: (43) - No such process\nsecond line\0";
+        let results = parse_command_results(output);
+        assert_eq!(results[&42], (Some("python -c 'print(1)\tx\ny'".to_string()), "ok".to_string()));
+        assert_eq!(results[&43], (None, "kill: (43) - No such process\nsecond line".to_string()));
+    }
+
+    #[test]
+    fn tree_keeps_matches_and_their_ancestors() {
+        let processes = vec![
+            process(1, 0, "init"),
+            process(100, 1, "sshd"),
+            process(200, 100, "bash"),
+            process(300, 200, "vim notes.txt"),
+            process(400, 1, "cron"),
+            process(500, 999, "orphan vim"), // 父进程不在列表中
+        ];
+        let tree = build_process_tree(processes, &|p: &ProcessInfo| p.command.contains("vim"));
+        assert_eq!(pids(&tree), vec![(1, vec![100]), (500, vec![])]);
+        assert_eq!(pids(&tree[0].children[0].children), vec![(200, vec![300])]);
+    }
+
+    #[test]
+    fn tree_treats_self_parented_process_as_root() {
+        let tree = build_process_tree(vec![process(0, 0, "sched"), process(1, 0, "init")], &|_: &ProcessInfo| true);
+        assert_eq!(pids(&tree), vec![(0, vec![1])]);
+    }
+}
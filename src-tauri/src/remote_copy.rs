@@ -0,0 +1,266 @@
+/**
+ * 服务器之间直接复制文件相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use russh_sftp::client::SftpSession;
+use tauri::Window;
+use tokio::io::AsyncWriteExt;
+use crate::file::{copy_with_progress, join_remote_path, open_sftp};
+use crate::ssh::{exec_command, get_connection_address, shell_quote};
+use crate::throttle::TransferThrottle;
+
+/// 复制进度事件名
+const SERVER_COPY_PROGRESS_EVENT: &str = "server-copy-progress";
+/// 进度事件最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 复制进度事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerCopyProgressEvent {
+    pub task_id: String,
+    pub path: String,      // 当前正在复制的源文件
+    pub transferred: u64,  // 已复制总字节数
+    pub total: u64,        // 需要复制的总字节数
+}
+
+/// 服务器间复制参数
+#[derive(Debug, Deserialize)]
+pub struct CopyBetweenServersParams {
+    pub task_id: String,           // 任务ID（用于匹配进度事件）
+    pub source_server_id: String,
+    pub source_path: String,       // 源文件或目录
+    pub target_server_id: String,
+    pub target_path: String,       // 目标完整路径
+    pub mode: Option<String>,      // "stream"（默认，经本机中转，不落盘）| "scp"（由源服务器直接 scp 到目标服务器）
+    pub target_host: Option<String>, // scp 模式下源服务器访问目标服务器的地址（默认与本机连接时相同）
+    pub target_port: Option<u16>,    // scp 模式下源服务器访问目标服务器的端口（默认与本机连接时相同）
+}
+
+/// 服务器间复制返回
+#[derive(Debug, Serialize)]
+pub struct CopyBetweenServersResult {
+    pub success: bool,
+    pub transferred: u64,
+    pub message: Option<String>,
+}
+
+/// 在两台已连接的服务器之间复制文件或目录
+///
+/// `stream` 模式从源服务器的 SFTP 读取并直接写入目标服务器的 SFTP，数据只经过内存；
+/// `scp` 模式要求源服务器能够通过密钥免密访问目标服务器。默认使用本机连接目标服务器时的
+/// 地址和端口，源服务器需经内网地址等其他方式访问时可通过 `target_host`/`target_port` 指定。
+/// 源服务器必须已信任目标服务器的主机密钥（不会自动接受未知密钥）；`scp` 模式不推送进度事件。
+///
+/// # 命令名称
+/// `copy_between_servers`
+///
+/// # 参数
+/// - `task_id`: 任务ID
+/// - `source_server_id`: 源服务器ID
+/// - `source_path`: 源文件或目录
+/// - `target_server_id`: 目标服务器ID
+/// - `target_path`: 目标完整路径
+/// - `mode`: 复制模式（可选）
+/// - `target_host`: scp 模式下源服务器访问目标服务器的地址（可选）
+/// - `target_port`: scp 模式下源服务器访问目标服务器的端口（可选）
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `transferred`: 已复制字节数（`scp` 模式下为 0）
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn copy_between_servers(window: Window, params: CopyBetweenServersParams) -> Result<CopyBetweenServersResult, String> {
+    if params.source_server_id == params.target_server_id {
+        return Err("源服务器和目标服务器相同，请使用重命名或复制命令".to_string());
+    }
+
+    match params.mode.as_deref() {
+        None | Some("stream") => copy_by_stream(&window, &params).await,
+        Some("scp") => copy_by_scp(&params).await,
+        Some(other) => Err(format!("不支持的复制模式: {}", other)),
+    }
+}
+
+/// 经本机内存中转复制
+async fn copy_by_stream(window: &Window, params: &CopyBetweenServersParams) -> Result<CopyBetweenServersResult, String> {
+    let source = open_sftp(&params.source_server_id).await?;
+    let target = match open_sftp(&params.target_server_id).await {
+        Ok(target) => target,
+        Err(e) => {
+            let _ = source.close().await;
+            return Err(e);
+        }
+    };
+
+    let result = copy_tree(window, params, &source, &target).await;
+
+    let _ = source.close().await;
+    let _ = target.close().await;
+
+    let transferred = result?;
+    Ok(CopyBetweenServersResult {
+        success: true,
+        transferred,
+        message: Some("复制成功".to_string()),
+    })
+}
+
+/// 复制文件或整个目录树，返回复制的总字节数
+async fn copy_tree(window: &Window, params: &CopyBetweenServersParams, source: &SftpSession, target: &SftpSession) -> Result<u64, String> {
+    let metadata = source
+        .metadata(params.source_path.as_str())
+        .await
+        .map_err(|e| format!("读取源文件失败: {}", e))?;
+
+    // 先列出所有需要复制的文件（源路径，目标路径，大小）和需要创建的目录
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    if metadata.is_dir() {
+        let mut pending = vec![(params.source_path.clone(), params.target_path.clone())];
+        while let Some((source_dir, target_dir)) = pending.pop() {
+            let entries = source
+                .read_dir(source_dir.as_str())
+                .await
+                .map_err(|e| format!("读取源目录失败: {}: {}", source_dir, e))?;
+            dirs.push(target_dir.clone());
+
+            for entry in entries {
+                let name = entry.file_name();
+                if name == "." || name == ".." {
+                    continue;
+                }
+                let file_type = entry.file_type();
+                let source_child = join_remote_path(&source_dir, &name);
+                let target_child = join_remote_path(&target_dir, &name);
+                if file_type.is_dir() {
+                    pending.push((source_child, target_child));
+                } else if file_type.is_file() {
+                    files.push((source_child, target_child, entry.metadata().len()));
+                }
+            }
+        }
+    } else {
+        files.push((params.source_path.clone(), params.target_path.clone(), metadata.len()));
+    }
+
+    // 目录按路径排序，保证父目录先创建
+    dirs.sort();
+    for dir in &dirs {
+        if !target.try_exists(dir.as_str()).await.unwrap_or(false) {
+            target
+                .create_dir(dir.as_str())
+                .await
+                .map_err(|e| format!("创建目标目录失败: {}: {}", dir, e))?;
+        }
+    }
+
+    let total: u64 = files.iter().map(|(_, _, size)| size).sum();
+    let throttle = TransferThrottle::for_transfer(&params.task_id, None);
+    let mut done = 0u64;
+    let mut last_emit = Instant::now();
+
+    for (source_path, target_path, _) in &files {
+        let mut reader = source
+            .open(source_path.as_str())
+            .await
+            .map_err(|e| format!("打开源文件失败: {}: {}", source_path, e))?;
+        let mut writer = target
+            .create(target_path.as_str())
+            .await
+            .map_err(|e| format!("创建目标文件失败: {}: {}", target_path, e))?;
+
+        let copied = copy_with_progress(&mut reader, &mut writer, &throttle, |transferred| {
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                let _ = window.emit(SERVER_COPY_PROGRESS_EVENT, ServerCopyProgressEvent {
+                    task_id: params.task_id.clone(),
+                    path: source_path.clone(),
+                    transferred: done + transferred,
+                    total,
+                });
+            }
+        })
+        .await?;
+        // 目标服务器在关闭文件时才可能报告配额不足等写入错误
+        writer
+            .shutdown()
+            .await
+            .map_err(|e| format!("写入目标文件失败: {}: {}", target_path, e))?;
+        done += copied;
+    }
+
+    let _ = window.emit(SERVER_COPY_PROGRESS_EVENT, ServerCopyProgressEvent {
+        task_id: params.task_id.clone(),
+        path: params.source_path.clone(),
+        transferred: done,
+        total,
+    });
+
+    Ok(done)
+}
+
+/// 由源服务器直接 scp 到目标服务器
+///
+/// 目标路径会先在目标服务器的 shell 中展开，因此需要再引用一次；OpenSSH 9 起 scp 默认使用
+/// SFTP 协议，不经过远程 shell，支持时加 `-O` 使用原有协议以保证引用被正确解析。
+async fn copy_by_scp(params: &CopyBetweenServersParams) -> Result<CopyBetweenServersResult, String> {
+    let (host, port, username) = get_connection_address(&params.target_server_id)?;
+    let host = params.target_host.as_deref().map(str::trim).filter(|h| !h.is_empty()).unwrap_or(&host);
+    let port = params.target_port.unwrap_or(port);
+
+    // BatchMode 避免在无法免密登录或主机密钥未知时卡在交互提示
+    let destination = scp_destination(&username, host, &params.target_path);
+    let command = format!(
+        "if scp -O 2>&1 | grep -q 'option -- O'; then legacy=; else legacy=-O; fi; \
+         scp $legacy -r -o BatchMode=yes -o ConnectTimeout=10 -P {} {} {}",
+        port,
+        shell_quote(&params.source_path),
+        shell_quote(&destination)
+    );
+
+    let output = exec_command(&params.source_server_id, &command).await?;
+    if output.exit_code != 0 {
+        let message = output.stderr.trim();
+        if message.contains("Host key verification failed") {
+            return Err(format!(
+                "源服务器不信任目标服务器 {} 的主机密钥，请先在源服务器上手动 ssh 连接一次并确认主机密钥",
+                host
+            ));
+        }
+        return Err(if message.is_empty() {
+            format!("源服务器无法通过 scp 访问目标服务器（退出码 {}）", output.exit_code)
+        } else {
+            format!("源服务器无法通过 scp 访问目标服务器: {}", message)
+        });
+    }
+
+    Ok(CopyBetweenServersResult {
+        success: true,
+        transferred: 0,
+        message: Some("复制成功".to_string()),
+    })
+}
+
+/// 构建 scp 目标地址 `用户@主机:路径`，IPv6 地址需用方括号包裹
+fn scp_destination(username: &str, host: &str, path: &str) -> String {
+    let host = if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]", host)
+    } else {
+        host.to_string()
+    };
+    format!("{}@{}:{}", username, host, shell_quote(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brackets_ipv6_hosts_in_scp_destination() {
+        assert_eq!(scp_destination("root", "10.0.0.2", "/tmp/a b"), "root@10.0.0.2:'/tmp/a b'");
+        assert_eq!(scp_destination("root", "fe80::1", "/tmp/a"), "root@[fe80::1]:'/tmp/a'");
+        assert_eq!(scp_destination("root", "[::1]", "/tmp/a"), "root@[::1]:'/tmp/a'");
+    }
+}
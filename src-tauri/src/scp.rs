@@ -0,0 +1,145 @@
+/**
+ * SCP 协议传输（用于不支持 SFTP 子系统的服务器）
+ */
+
+use std::path::Path;
+use russh::ChannelStream;
+use russh::client::Msg;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use crate::file::copy_with_progress;
+use crate::ssh::{open_exec_channel, shell_quote};
+use crate::throttle::TransferThrottle;
+
+/// 通过 SCP 协议上传本地文件到远程完整路径
+pub(crate) async fn scp_upload<F: FnMut(u64)>(server_id: &str, local_path: &Path, remote_path: &str, throttle: &TransferThrottle, on_progress: F) -> Result<u64, String> {
+    let mut local = tokio::fs::File::open(local_path)
+        .await
+        .map_err(|e| format!("打开本地文件失败: {}", e))?;
+    let size = local
+        .metadata()
+        .await
+        .map_err(|e| format!("读取本地文件信息失败: {}", e))?
+        .len();
+    let file_name = local_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| "无效的本地文件路径".to_string())?;
+
+    let channel = open_exec_channel(server_id, &format!("scp -t {}", shell_quote(remote_path))).await?;
+    let mut stream = BufReader::new(channel.into_stream());
+
+    // 接收端就绪后发送文件头：C<权限> <大小> <文件名>
+    read_ack(&mut stream).await?;
+    write_all(&mut stream, format!("C0644 {} {}\n", size, file_name).as_bytes()).await?;
+    read_ack(&mut stream).await?;
+
+    let transferred = copy_with_progress(&mut local, stream.get_mut(), throttle, on_progress).await?;
+    write_all(&mut stream, b"\0").await?;
+    read_ack(&mut stream).await?;
+
+    let _ = stream.get_mut().shutdown().await;
+
+    Ok(transferred)
+}
+
+/// 通过 SCP 协议下载远程文件到本地完整路径
+pub(crate) async fn scp_download<F: FnMut(u64)>(server_id: &str, remote_path: &str, local_path: &Path, throttle: &TransferThrottle, on_progress: F) -> Result<u64, String> {
+    let channel = open_exec_channel(server_id, &format!("scp -f {}", shell_quote(remote_path))).await?;
+    let mut stream = BufReader::new(channel.into_stream());
+
+    write_all(&mut stream, b"\0").await?;
+
+    // 读取文件头，跳过时间戳（T 开头）等其他控制消息
+    let size = loop {
+        let header = read_control_line(&mut stream).await?;
+        match header.chars().next() {
+            Some('C') => break parse_file_header(&header)?,
+            Some('D') => return Err("远程路径是目录，SCP 模式仅支持单个文件".to_string()),
+            _ => write_all(&mut stream, b"\0").await?,
+        }
+    };
+    write_all(&mut stream, b"\0").await?;
+
+    if let Some(parent) = local_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("创建本地目录失败: {}", e))?;
+    }
+    let mut local = tokio::fs::File::create(local_path)
+        .await
+        .map_err(|e| format!("创建本地文件失败: {}", e))?;
+
+    let transferred = copy_with_progress(&mut (&mut stream).take(size), &mut local, throttle, on_progress).await?;
+    if transferred != size {
+        return Err("SCP 传输意外中断".to_string());
+    }
+
+    read_ack(&mut stream).await?;
+    write_all(&mut stream, b"\0").await?;
+    let _ = stream.get_mut().shutdown().await;
+
+    Ok(transferred)
+}
+
+/// 写入数据
+async fn write_all(stream: &mut BufReader<ChannelStream<Msg>>, data: &[u8]) -> Result<(), String> {
+    let stream = stream.get_mut();
+    stream.write_all(data).await.map_err(|e| format!("SCP 写入失败: {}", e))?;
+    stream.flush().await.map_err(|e| format!("SCP 写入失败: {}", e))
+}
+
+/// 读取确认字节：0 表示成功，1/2 后跟错误信息
+async fn read_ack(stream: &mut BufReader<ChannelStream<Msg>>) -> Result<(), String> {
+    let mut code = [0u8; 1];
+    stream
+        .read_exact(&mut code)
+        .await
+        .map_err(|_| "SCP 连接意外关闭，服务器可能未安装 scp".to_string())?;
+
+    if code[0] == 0 {
+        return Ok(());
+    }
+
+    let mut message = String::new();
+    let _ = stream.read_line(&mut message).await;
+    Err(format!("SCP 错误: {}", message.trim()))
+}
+
+/// 读取一行控制消息，错误消息（1/2 开头）转换为错误
+async fn read_control_line(stream: &mut BufReader<ChannelStream<Msg>>) -> Result<String, String> {
+    let mut line = Vec::new();
+    let n = stream
+        .read_until(b'\n', &mut line)
+        .await
+        .map_err(|e| format!("SCP 读取失败: {}", e))?;
+    if n == 0 {
+        return Err("SCP 连接意外关闭，服务器可能未安装 scp".to_string());
+    }
+
+    match line.first() {
+        Some(1) | Some(2) => Err(format!("SCP 错误: {}", String::from_utf8_lossy(&line[1..]).trim())),
+        _ => Ok(String::from_utf8_lossy(&line).trim_end().to_string()),
+    }
+}
+
+/// 解析文件头 `C0644 <大小> <文件名>`，返回文件大小
+fn parse_file_header(header: &str) -> Result<u64, String> {
+    header
+        .splitn(3, ' ')
+        .nth(1)
+        .and_then(|size| size.parse().ok())
+        .ok_or_else(|| format!("无法解析 SCP 文件头: {}", header))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_file_header_size() {
+        assert_eq!(parse_file_header("C0644 1048576 backup.tar.gz"), Ok(1048576));
+        assert_eq!(parse_file_header("C0600 0 name with spaces.txt"), Ok(0));
+        assert!(parse_file_header("C0644 abc file").is_err());
+        assert!(parse_file_header("C0644").is_err());
+    }
+}
@@ -0,0 +1,399 @@
+/**
+ * 远程文件搜索相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use russh::ChannelMsg;
+use tauri::Window;
+use crate::ssh::{shell_quote, RemoteProcess};
+use crate::util::TaskRegistry;
+
+/// 默认最大结果数
+const DEFAULT_MAX_RESULTS: usize = 1000;
+
+/// 输出格式：`类型\t大小\t修改时间\t路径\0`
+const PRINTF_FORMAT: &str = "%y\\t%s\\t%T@\\t%p\\0";
+
+/// 搜索结果事件名
+const SEARCH_RESULT_EVENT: &str = "file-search-result";
+/// 搜索结束事件名
+const SEARCH_FINISHED_EVENT: &str = "file-search-finished";
+
+lazy_static::lazy_static! {
+    /// 正在运行的搜索任务（search_id -> 取消信号）
+    static ref SEARCH_TASKS: TaskRegistry = TaskRegistry::new();
+}
+
+/// 搜索结果项
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub file_type: String, // "file" | "directory" | "link" | "other"
+    pub size: u64,
+    pub modified: u64, // 修改时间（Unix 时间戳，秒）
+}
+
+/// 搜索结果事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct FileSearchResultEvent {
+    pub search_id: String,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// 搜索结束事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct FileSearchFinishedEvent {
+    pub search_id: String,
+    pub total: usize,
+    pub truncated: bool,  // 是否因达到最大结果数而提前结束
+    pub cancelled: bool,  // 是否被用户取消
+}
+
+/// 搜索远程文件参数
+#[derive(Debug, Deserialize)]
+pub struct SearchRemoteFilesParams {
+    pub server_id: String,
+    pub search_id: String,               // 搜索ID（由前端生成，用于匹配事件）
+    pub path: String,                    // 搜索起始目录
+    pub name_pattern: Option<String>,    // 文件名通配符，如 "*.conf"
+    pub case_sensitive: Option<bool>,    // 文件名是否区分大小写（默认不区分）
+    pub content_pattern: Option<String>, // 文件内容正则（扩展正则）
+    pub file_type: Option<String>,       // "file" | "directory"
+    pub min_size: Option<u64>,           // 最小文件大小（字节，包含）
+    pub max_size: Option<u64>,           // 最大文件大小（字节，包含）
+    pub modified_within: Option<u64>,    // 最近 N 分钟内修改过
+    pub modified_before: Option<u64>,    // N 分钟之前修改过
+    pub max_depth: Option<u32>,          // 最大搜索深度
+    pub max_results: Option<usize>,      // 最大结果数（默认 1000）
+}
+
+/// 搜索远程文件返回
+#[derive(Debug, Serialize)]
+pub struct SearchRemoteFilesResult {
+    pub success: bool,
+    pub search_id: String,
+}
+
+/// 取消搜索参数
+#[derive(Debug, Deserialize)]
+pub struct CancelFileSearchParams {
+    pub search_id: String,
+}
+
+/// 取消搜索返回
+#[derive(Debug, Serialize)]
+pub struct CancelFileSearchResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 搜索远程文件
+///
+/// 在远程服务器上执行 `find`（内容搜索时配合 `grep`），结果通过
+/// `file-search-result` 事件分批推送，结束时推送 `file-search-finished` 事件。
+///
+/// # 命令名称
+/// `search_remote_files`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `search_id`: 搜索ID
+/// - `path`: 搜索起始目录
+/// - `name_pattern`: 文件名通配符（可选）
+/// - `case_sensitive`: 文件名是否区分大小写（可选）
+/// - `content_pattern`: 文件内容正则（可选）
+/// - `file_type`: 文件类型过滤（可选）
+/// - `min_size` / `max_size`: 文件大小范围（可选）
+/// - `modified_within` / `modified_before`: 修改时间范围，单位分钟（可选）
+/// - `max_depth`: 最大搜索深度（可选）
+/// - `max_results`: 最大结果数（可选）
+///
+/// # 返回
+/// - `success`: 是否成功启动
+/// - `search_id`: 搜索ID
+#[tauri::command]
+pub async fn search_remote_files(window: Window, params: SearchRemoteFilesParams) -> Result<SearchRemoteFilesResult, String> {
+    if params.path.trim().is_empty() {
+        return Err("搜索路径不能为空".to_string());
+    }
+
+    let command = build_find_command(&params)?;
+
+    let (generation, mut cancel_rx) = SEARCH_TASKS
+        .register(&params.search_id)
+        .ok_or_else(|| "搜索任务已存在".to_string())?;
+
+    let max_results = params.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let mut process = match RemoteProcess::spawn(&params.server_id, &command).await {
+        Ok(process) => process,
+        Err(e) => {
+            SEARCH_TASKS.finish(&params.search_id, generation);
+            return Err(e);
+        }
+    };
+
+    let search_id = params.search_id.clone();
+    tokio::spawn(async move {
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut total = 0;
+        let mut truncated = false;
+        let mut cancelled = false;
+
+        loop {
+            let msg = tokio::select! {
+                msg = process.wait() => msg,
+                _ = &mut cancel_rx => {
+                    cancelled = true;
+                    break;
+                }
+            };
+
+            match msg {
+                Some(ChannelMsg::Data { data }) => {
+                    buffer.extend_from_slice(&data);
+                    let mut matches = take_complete_records(&mut buffer);
+                    if total + matches.len() > max_results {
+                        matches.truncate(max_results - total);
+                        truncated = true;
+                    }
+                    total += matches.len();
+
+                    if !matches.is_empty() {
+                        let _ = window.emit(SEARCH_RESULT_EVENT, FileSearchResultEvent {
+                            search_id: search_id.clone(),
+                            matches,
+                        });
+                    }
+
+                    if truncated {
+                        break;
+                    }
+                }
+                Some(ChannelMsg::Close) | None => {
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        // 取消或达到最大结果数时结束远程 find/grep，否则它们会继续扫描文件系统
+        process.close(cancelled || truncated).await;
+        SEARCH_TASKS.finish(&search_id, generation);
+
+        let _ = window.emit(SEARCH_FINISHED_EVENT, FileSearchFinishedEvent {
+            search_id,
+            total,
+            truncated,
+            cancelled,
+        });
+    });
+
+    Ok(SearchRemoteFilesResult {
+        success: true,
+        search_id: params.search_id,
+    })
+}
+
+/// 取消远程文件搜索
+///
+/// # 命令名称
+/// `cancel_file_search`
+///
+/// # 参数
+/// - `search_id`: 搜索ID
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn cancel_file_search(params: CancelFileSearchParams) -> Result<CancelFileSearchResult, String> {
+    let message = if SEARCH_TASKS.stop(&params.search_id) { "已取消搜索" } else { "搜索已结束" };
+    Ok(CancelFileSearchResult {
+        success: true,
+        message: Some(message.to_string()),
+    })
+}
+
+/// 构建 find 命令
+///
+/// 输出格式为 `类型\t大小\t修改时间\t路径\0`，使用 NUL 分隔以支持包含换行的文件名。
+/// 内容搜索时由 `grep -l` 成批检查文件（`-exec ... {} +`，不必每个文件启动一次 grep），
+/// 再把匹配的文件交给 `find -maxdepth 0` 按同样格式输出。
+fn build_find_command(params: &SearchRemoteFilesParams) -> Result<String, String> {
+    let content_pattern = params.content_pattern.as_deref().filter(|p| !p.is_empty());
+
+    // 内容搜索只对普通文件有意义
+    let file_type = match (params.file_type.as_deref(), content_pattern) {
+        (Some(other), _) if other != "file" && other != "directory" => {
+            return Err(format!("不支持的文件类型: {}", other));
+        }
+        (Some("directory"), Some(_)) => return Err("内容搜索不支持目录类型".to_string()),
+        (None, Some(_)) => Some("file"),
+        (file_type, _) => file_type,
+    };
+
+    let mut parts = vec!["find".to_string(), shell_quote(&params.path)];
+
+    if let Some(depth) = params.max_depth {
+        parts.push(format!("-maxdepth {}", depth));
+    }
+
+    match file_type {
+        Some("file") => parts.push("-type f".to_string()),
+        Some("directory") => parts.push("-type d".to_string()),
+        _ => {}
+    }
+
+    if let Some(pattern) = params.name_pattern.as_deref().filter(|p| !p.is_empty()) {
+        let flag = if params.case_sensitive.unwrap_or(false) { "-name" } else { "-iname" };
+        parts.push(format!("{} {}", flag, shell_quote(pattern)));
+    }
+
+    if let Some(min_size) = params.min_size.filter(|s| *s > 0) {
+        parts.push(format!("-size +{}c", min_size - 1));
+    }
+
+    if let Some(max_size) = params.max_size {
+        parts.push(format!("-size -{}c", max_size + 1));
+    }
+
+    if let Some(minutes) = params.modified_within {
+        parts.push(format!("-mmin -{}", minutes));
+    }
+
+    if let Some(minutes) = params.modified_before {
+        parts.push(format!("-mmin +{}", minutes));
+    }
+
+    if let Some(pattern) = content_pattern {
+        parts.push(format!("-exec grep -lZIE -e {} {{}} + 2>/dev/null", shell_quote(pattern)));
+        let print = format!("find \"$@\" -maxdepth 0 -printf '{}'", PRINTF_FORMAT);
+        parts.push(format!("| xargs -0 -r sh -c {} sh 2>/dev/null", shell_quote(&print)));
+        return Ok(parts.join(" "));
+    }
+
+    parts.push(format!("-printf '{}'", PRINTF_FORMAT));
+    parts.push("2>/dev/null".to_string());
+
+    Ok(parts.join(" "))
+}
+
+/// 从缓冲区取出所有完整记录，不完整的尾部数据保留在缓冲区中
+fn take_complete_records(buffer: &mut Vec<u8>) -> Vec<SearchMatch> {
+    let end = match buffer.iter().rposition(|b| *b == 0) {
+        Some(pos) => pos + 1,
+        None => return Vec::new(),
+    };
+
+    let complete: Vec<u8> = buffer.drain(..end).collect();
+    complete
+        .split(|b| *b == 0)
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| parse_find_record(&String::from_utf8_lossy(record)))
+        .collect()
+}
+
+/// 解析单条 find 输出记录
+fn parse_find_record(record: &str) -> Option<SearchMatch> {
+    let mut fields = record.splitn(4, '\t');
+    let kind = fields.next()?;
+    let size = fields.next()?.parse().ok()?;
+    let modified = fields.next()?.split('.').next()?.parse().ok()?;
+    let path = fields.next()?.to_string();
+
+    let file_type = match kind {
+        "f" => "file",
+        "d" => "directory",
+        "l" => "link",
+        _ => "other",
+    };
+
+    Some(SearchMatch {
+        path,
+        file_type: file_type.to_string(),
+        size,
+        modified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(path: &str) -> SearchRemoteFilesParams {
+        SearchRemoteFilesParams {
+            server_id: "server".to_string(),
+            search_id: "search".to_string(),
+            path: path.to_string(),
+            name_pattern: None,
+            case_sensitive: None,
+            content_pattern: None,
+            file_type: None,
+            min_size: None,
+            max_size: None,
+            modified_within: None,
+            modified_before: None,
+            max_depth: None,
+            max_results: None,
+        }
+    }
+
+    #[test]
+    fn quotes_path_and_name_pattern() {
+        let mut p = params("/var/it's here");
+        p.name_pattern = Some("*.conf".to_string());
+        assert_eq!(
+            build_find_command(&p).unwrap(),
+            "find '/var/it'\\''s here' -iname '*.conf' -printf '%y\\t%s\\t%T@\\t%p\\0' 2>/dev/null"
+        );
+
+        p.case_sensitive = Some(true);
+        assert!(build_find_command(&p).unwrap().contains(" -name '*.conf' "));
+    }
+
+    #[test]
+    fn builds_depth_size_and_mtime_flags() {
+        let mut p = params("/srv");
+        p.max_depth = Some(2);
+        p.file_type = Some("directory".to_string());
+        p.min_size = Some(1024);
+        p.max_size = Some(2048);
+        p.modified_within = Some(60);
+        p.modified_before = Some(5);
+        let command = build_find_command(&p).unwrap();
+        assert!(command.starts_with(
+            "find '/srv' -maxdepth 2 -type d -size +1023c -size -2049c -mmin -60 -mmin +5 -printf "
+        ));
+
+        // min_size 为 0 时不添加下限
+        p.min_size = Some(0);
+        assert!(!build_find_command(&p).unwrap().contains("-size +"));
+    }
+
+    #[test]
+    fn content_search_forces_regular_files() {
+        let mut p = params("/etc");
+        p.content_pattern = Some("listen 80".to_string());
+        let command = build_find_command(&p).unwrap();
+        assert!(command.starts_with("find '/etc' -type f -exec grep -lZIE -e 'listen 80' {} + 2>/dev/null | xargs -0 -r sh -c "));
+
+        p.file_type = Some("file".to_string());
+        assert_eq!(build_find_command(&p).unwrap(), command);
+    }
+
+    #[test]
+    fn rejects_content_search_for_directories() {
+        let mut p = params("/etc");
+        p.content_pattern = Some("listen".to_string());
+        p.file_type = Some("directory".to_string());
+        assert!(build_find_command(&p).is_err());
+
+        // 空内容模式不触发内容搜索
+        p.content_pattern = Some(String::new());
+        assert!(build_find_command(&p).unwrap().contains(" -type d "));
+
+        p.file_type = Some("socket".to_string());
+        assert!(build_find_command(&p).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+/**
+ * 本地敏感配置加密存储
+ */
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::path::Path;
+use crate::db::data_dir;
+
+/// 密钥文件名（应用数据目录可能是系统公共目录，文件名需带应用前缀）
+const KEY_FILE: &str = "myssh-secret.key";
+/// 密文前缀，用于区分格式版本
+const CIPHERTEXT_PREFIX: &str = "v1:";
+/// AES-GCM 随机数长度
+const NONCE_LEN: usize = 12;
+
+lazy_static::lazy_static! {
+    /// 本机密钥，首次使用时从密钥文件读取，不存在则生成
+    static ref SECRET_KEY: Result<[u8; 32], String> = load_or_create_key(&data_dir().join(KEY_FILE));
+}
+
+/// 加密字符串，返回 `v1:<base64(随机数 + 密文)>`
+pub(crate) fn encrypt_secret(plaintext: &str) -> Result<String, String> {
+    encrypt_with(secret_key()?, plaintext)
+}
+
+/// 解密 `encrypt_secret` 生成的字符串
+///
+/// 密钥文件被删除或更换后无法解密，需要重新填写。
+pub(crate) fn decrypt_secret(value: &str) -> Result<String, String> {
+    decrypt_with(secret_key()?, value)
+}
+
+fn secret_key() -> Result<&'static [u8; 32], String> {
+    SECRET_KEY.as_ref().map_err(|e| e.clone())
+}
+
+fn encrypt_with(key: &[u8; 32], plaintext: &str) -> Result<String, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(key)
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| "加密失败".to_string())?;
+
+    let mut data = nonce.to_vec();
+    data.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", CIPHERTEXT_PREFIX, BASE64.encode(data)))
+}
+
+fn decrypt_with(key: &[u8; 32], value: &str) -> Result<String, String> {
+    let encoded = value
+        .strip_prefix(CIPHERTEXT_PREFIX)
+        .ok_or_else(|| "密文格式无效".to_string())?;
+    let data = BASE64.decode(encoded).map_err(|_| "密文格式无效".to_string())?;
+    if data.len() <= NONCE_LEN {
+        return Err("密文格式无效".to_string());
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let plaintext = cipher(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "解密失败，密钥可能已更换".to_string())?;
+    String::from_utf8(plaintext).map_err(|_| "解密失败".to_string())
+}
+
+fn cipher(key: &[u8; 32]) -> Aes256Gcm {
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+}
+
+/// 读取密钥文件，不存在时生成新密钥（Unix 上权限为 0600）
+fn load_or_create_key(path: &Path) -> Result<[u8; 32], String> {
+    if let Ok(bytes) = std::fs::read(path) {
+        return bytes
+            .try_into()
+            .map_err(|_| format!("密钥文件已损坏: {}", path.display()));
+    }
+
+    let key = Aes256Gcm::generate_key(&mut OsRng);
+    write_private_file(path, &key).map_err(|e| format!("保存密钥文件失败: {}", e))?;
+    Ok(key.into())
+}
+
+fn write_private_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在系统临时目录下生成一个不存在的密钥文件路径
+    fn temp_key_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("myssh-secret-test-{}-{}.key", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn round_trips_with_same_key() {
+        let key = [7u8; 32];
+        let encrypted = encrypt_with(&key, "sk-测试-key").unwrap();
+        assert!(encrypted.starts_with(CIPHERTEXT_PREFIX));
+        assert!(!encrypted.contains("sk-"));
+        assert_eq!(decrypt_with(&key, &encrypted).unwrap(), "sk-测试-key");
+        // 每次加密使用新的随机数
+        assert_ne!(encrypt_with(&key, "sk-测试-key").unwrap(), encrypted);
+    }
+
+    #[test]
+    fn rejects_ciphertext_after_key_swap() {
+        let path = temp_key_path("swap");
+        let old_key = load_or_create_key(&path).unwrap();
+        assert_eq!(load_or_create_key(&path).unwrap(), old_key);
+        let encrypted = encrypt_with(&old_key, "secret").unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        let new_key = load_or_create_key(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_ne!(new_key, old_key);
+        assert_eq!(decrypt_with(&new_key, &encrypted).unwrap_err(), "解密失败，密钥可能已更换");
+    }
+
+    #[test]
+    fn rejects_malformed_ciphertext() {
+        let key = [1u8; 32];
+        assert_eq!(decrypt_with(&key, "plain").unwrap_err(), "密文格式无效");
+        assert_eq!(decrypt_with(&key, "v1:not-base64!").unwrap_err(), "密文格式无效");
+        assert_eq!(decrypt_with(&key, &format!("v1:{}", BASE64.encode([0u8; NONCE_LEN]))).unwrap_err(), "密文格式无效");
+    }
+
+    #[test]
+    fn rejects_corrupted_key_file() {
+        let path = temp_key_path("corrupt");
+        std::fs::write(&path, b"short").unwrap();
+        let error = load_or_create_key(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.starts_with("密钥文件已损坏"));
+    }
+}
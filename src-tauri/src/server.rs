@@ -3,7 +3,7 @@
  */
 
 use serde::{Deserialize, Serialize};
-use rusqlite::{params, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension};
 use crate::db::get_db;
 
 /// 服务器配置信息
@@ -16,6 +16,7 @@ pub struct ServerConfig {
     pub username: String,
     pub password: Option<String>,
     pub key_path: Option<String>,
+    pub group_name: Option<String>,  // 服务器分组（可选）
 }
 
 /// 获取所有服务器配置参数
@@ -38,6 +39,7 @@ pub struct SaveServerParams {
     pub username: String,
     pub password: Option<String>,
     pub key_path: Option<String>,
+    pub group_name: Option<String>,  // 服务器分组（可选）
 }
 
 /// 保存服务器配置返回
@@ -58,6 +60,7 @@ pub struct UpdateServerParams {
     pub username: Option<String>,
     pub password: Option<String>,
     pub key_path: Option<String>,
+    pub group_name: Option<String>,  // 服务器分组（可选）
 }
 
 /// 更新服务器配置返回
@@ -105,7 +108,7 @@ pub async fn get_servers() -> Result<GetServersResult, String> {
     let conn = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
     
     let mut stmt = conn
-        .prepare("SELECT id, name, host, port, username, password, key_path FROM servers ORDER BY created_at DESC")
+        .prepare("SELECT id, name, host, port, username, password, key_path, group_name FROM servers ORDER BY created_at DESC")
         .map_err(|e| format!("查询准备失败: {}", e))?;
     
     let server_iter = stmt
@@ -118,6 +121,7 @@ pub async fn get_servers() -> Result<GetServersResult, String> {
                 username: row.get(4)?,
                 password: row.get(5)?,
                 key_path: row.get(6)?,
+                group_name: row.get(7)?,
             })
         })
         .map_err(|e| format!("查询执行失败: {}", e))?;
@@ -145,6 +149,7 @@ pub async fn get_servers() -> Result<GetServersResult, String> {
 /// - `username`: 用户名
 /// - `password`: 密码（可选）
 /// - `key_path`: 密钥路径（可选）
+/// - `group_name`: 服务器分组（可选）
 /// 
 /// # 返回
 /// - `success`: 是否成功
@@ -167,7 +172,7 @@ pub async fn save_server(params: SaveServerParams) -> Result<SaveServerResult, S
     if exists {
         // 更新现有记录
         conn.execute(
-            "UPDATE servers SET name = ?2, host = ?3, port = ?4, username = ?5, password = ?6, key_path = ?7, updated_at = datetime('now') WHERE id = ?1",
+            "UPDATE servers SET name = ?2, host = ?3, port = ?4, username = ?5, password = ?6, key_path = ?7, group_name = ?8, updated_at = datetime('now') WHERE id = ?1",
             params![
                 params.id,
                 params.name,
@@ -175,14 +180,15 @@ pub async fn save_server(params: SaveServerParams) -> Result<SaveServerResult, S
                 params.port,
                 params.username,
                 params.password,
-                params.key_path
+                params.key_path,
+                params.group_name
             ],
         )
         .map_err(|e| format!("更新失败: {}", e))?;
     } else {
         // 插入新记录
         conn.execute(
-            "INSERT INTO servers (id, name, host, port, username, password, key_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO servers (id, name, host, port, username, password, key_path, group_name) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 params.id,
                 params.name,
@@ -190,7 +196,8 @@ pub async fn save_server(params: SaveServerParams) -> Result<SaveServerResult, S
                 params.port,
                 params.username,
                 params.password,
-                params.key_path
+                params.key_path,
+                params.group_name
             ],
         )
         .map_err(|e| format!("插入失败: {}", e))?;
@@ -216,6 +223,7 @@ pub async fn save_server(params: SaveServerParams) -> Result<SaveServerResult, S
 /// - `username`: 用户名（可选）
 /// - `password`: 密码（可选）
 /// - `key_path`: 密钥路径（可选）
+/// - `group_name`: 服务器分组（可选）
 /// 
 /// # 返回
 /// - `success`: 是否成功
@@ -295,6 +303,15 @@ pub async fn update_server(params: UpdateServerParams) -> Result<UpdateServerRes
         updated = true;
     }
     
+    if params.group_name.is_some() {
+        conn.execute(
+            "UPDATE servers SET group_name = ?1, updated_at = datetime('now') WHERE id = ?2",
+            params![params.group_name, params.id],
+        )
+        .map_err(|e| format!("更新分组失败: {}", e))?;
+        updated = true;
+    }
+    
     if !updated {
         return Ok(UpdateServerResult {
             success: true,
@@ -355,7 +372,7 @@ pub async fn get_server(params: GetServerParams) -> Result<GetServerResult, Stri
     
     let server = conn
         .query_row(
-            "SELECT id, name, host, port, username, password, key_path FROM servers WHERE id = ?1",
+            "SELECT id, name, host, port, username, password, key_path, group_name FROM servers WHERE id = ?1",
             params![params.server_id],
             |row| {
                 Ok(ServerConfig {
@@ -366,6 +383,7 @@ pub async fn get_server(params: GetServerParams) -> Result<GetServerResult, Stri
                     username: row.get(4)?,
                     password: row.get(5)?,
                     key_path: row.get(6)?,
+                    group_name: row.get(7)?,
                 })
             },
         )
@@ -377,3 +395,24 @@ pub async fn get_server(params: GetServerParams) -> Result<GetServerResult, Stri
     })
 }
 
+
+/// 获取服务器名称和分组（服务器配置不存在时返回 None）
+pub(crate) fn get_server_name_and_group(conn: &Connection, server_id: &str) -> Option<(String, Option<String>)> {
+    conn.query_row(
+        "SELECT name, group_name FROM servers WHERE id = ?1",
+        params![server_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .ok()
+    .flatten()
+}
+
+/// 获取服务器列表（ID，名称，分组），指定分组时只返回该分组的服务器
+pub(crate) fn list_server_names(conn: &Connection, group_name: Option<&str>) -> rusqlite::Result<Vec<(String, String, Option<String>)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, group_name FROM servers WHERE ?1 IS NULL OR group_name = ?1 ORDER BY name",
+    )?;
+    let rows = stmt.query_map(params![group_name], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+    rows.collect()
+}
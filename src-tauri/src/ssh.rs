@@ -3,17 +3,20 @@
  */
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use russh::{client, ChannelMsg, Disconnect, Error};
+use russh::{client, Channel, ChannelMsg, CryptoVec, Disconnect, Error};
 use russh_keys::load_secret_key;
 use async_trait::async_trait;
 use tokio::sync::Mutex as TokioMutex;
 use tokio::time::sleep;
+use crate::disk_usage::DiskUsageCache;
+use crate::sync::discard_server_sync_plans;
+use crate::system_info::SystemInfo;
 
 /// SSH 客户端 Handler
-struct SshHandler;
+pub struct SshHandler;
 
 #[async_trait]
 impl client::Handler for SshHandler {
@@ -37,6 +40,8 @@ pub struct SshConnection {
     pub session: Arc<TokioMutex<client::Handle<SshHandler>>>,
     pub last_heartbeat: Arc<Mutex<Instant>>,  // 最后心跳时间
     pub heartbeat_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,  // 心跳任务句柄
+    pub disk_usage_cache: Arc<Mutex<DiskUsageCache>>,  // 磁盘占用分析缓存（随连接释放）
+    pub system_info: Arc<Mutex<Option<SystemInfo>>>,   // 主机信息缓存（随连接释放）
 }
 
 /// 全局连接池
@@ -225,6 +230,8 @@ pub async fn connect_ssh_server(params: ConnectSshParams) -> Result<ConnectSshRe
                 session,
                 last_heartbeat,
                 heartbeat_task,
+                disk_usage_cache: Arc::new(Mutex::new(DiskUsageCache::default())),
+                system_info: Arc::new(Mutex::new(None)),
             };
 
             let mut connections = CONNECTIONS.lock().unwrap();
@@ -346,6 +353,9 @@ async fn disconnect_ssh_server_internal(server_id: &str) {
             (None, None)
         }
     };
+
+    // 丢弃该服务器未执行的同步计划
+    discard_server_sync_plans(server_id);
     
     // 停止心跳任务（在锁外）
     if let Some(task) = heartbeat_task_opt {
@@ -359,6 +369,260 @@ async fn disconnect_ssh_server_internal(server_id: &str) {
     }
 }
 
+/// 远程命令执行结果
+#[derive(Debug)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: u32,
+}
+
+/// 获取已连接服务器的会话句柄（同时刷新最后心跳时间）
+pub(crate) fn get_session(server_id: &str) -> Result<Arc<TokioMutex<client::Handle<SshHandler>>>, String> {
+    let connections = CONNECTIONS.lock().unwrap();
+    match connections.get(server_id) {
+        Some(conn) => {
+            *conn.last_heartbeat.lock().unwrap() = Instant::now();
+            Ok(conn.session.clone())
+        }
+        None => Err("服务器未连接".to_string()),
+    }
+}
+
+/// 服务器是否处于连接状态
+pub(crate) fn is_connected(server_id: &str) -> bool {
+    CONNECTIONS.lock().unwrap().contains_key(server_id)
+}
+
+/// 获取已连接服务器的地址信息（主机，端口，用户名）
+pub(crate) fn get_connection_address(server_id: &str) -> Result<(String, u16, String), String> {
+    let connections = CONNECTIONS.lock().unwrap();
+    match connections.get(server_id) {
+        Some(conn) => Ok((conn.host.clone(), conn.port, conn.username.clone())),
+        None => Err("服务器未连接".to_string()),
+    }
+}
+
+/// 获取已连接服务器的磁盘占用分析缓存
+pub(crate) fn get_disk_usage_cache(server_id: &str) -> Result<Arc<Mutex<DiskUsageCache>>, String> {
+    let connections = CONNECTIONS.lock().unwrap();
+    match connections.get(server_id) {
+        Some(conn) => Ok(conn.disk_usage_cache.clone()),
+        None => Err("服务器未连接".to_string()),
+    }
+}
+
+/// 获取已连接服务器的主机信息缓存
+pub(crate) fn get_system_info_cache(server_id: &str) -> Result<Arc<Mutex<Option<SystemInfo>>>, String> {
+    let connections = CONNECTIONS.lock().unwrap();
+    match connections.get(server_id) {
+        Some(conn) => Ok(conn.system_info.clone()),
+        None => Err("服务器未连接".to_string()),
+    }
+}
+
+/// 打开会话通道并执行命令，返回通道由调用方读取输出
+///
+/// 会话锁只在打开通道时持有，长时间运行的命令不会阻塞同一连接上的其他操作。
+pub(crate) async fn open_exec_channel(server_id: &str, command: &str) -> Result<Channel<client::Msg>, String> {
+    let session = get_session(server_id)?;
+    let channel = {
+        let handle = session.lock().await;
+        handle
+            .channel_open_session()
+            .await
+            .map_err(|e| format!("打开通道失败: {}，连接可能已断开", e))?
+    };
+
+    if let Err(e) = channel.exec(true, command.as_bytes().to_vec()).await {
+        return Err(format!("执行命令失败: {}", e));
+    }
+
+    Ok(channel)
+}
+
+/// 可主动结束的长时间运行远程命令（`tail -F`、`docker logs -f`、`find` 等）
+///
+/// 没有 PTY 时关闭通道不会让远程进程退出，进程要等到下次写输出收到 SIGPIPE 才结束，
+/// 安静的日志或长时间扫描会一直残留。sshd 会让登录 shell 成为新会话和进程组的首进程，
+/// 因此先输出 `$$` 取得进程组 ID，停止时结束整个进程组（包括管道中的所有命令）。
+pub(crate) struct RemoteProcess {
+    server_id: String,
+    channel: Channel<client::Msg>,
+    pgid: Option<u32>,
+    buffered: VecDeque<ChannelMsg>, // 读取进程组 ID 时收到的其他消息
+}
+
+impl RemoteProcess {
+    /// 启动远程命令并读取其进程组 ID
+    pub(crate) async fn spawn(server_id: &str, command: &str) -> Result<RemoteProcess, String> {
+        let mut channel = open_exec_channel(server_id, &format!("echo $$; {}", command)).await?;
+        let mut buffered = VecDeque::new();
+        let mut first_line: Vec<u8> = Vec::new();
+        let mut pgid = None;
+
+        loop {
+            match channel.wait().await {
+                Some(ChannelMsg::Data { data }) => {
+                    first_line.extend_from_slice(&data);
+                    if let Some(end) = first_line.iter().position(|b| *b == b'\n') {
+                        pgid = parse_pgid(&first_line[..end]);
+                        if end + 1 < first_line.len() {
+                            buffered.push_back(ChannelMsg::Data {
+                                data: CryptoVec::from_slice(&first_line[end + 1..]),
+                            });
+                        }
+                        break;
+                    }
+                }
+                Some(ChannelMsg::Close) | None => {
+                    buffered.push_back(ChannelMsg::Close);
+                    break;
+                }
+                Some(msg) => buffered.push_back(msg),
+            }
+        }
+
+        Ok(RemoteProcess {
+            server_id: server_id.to_string(),
+            channel,
+            pgid,
+            buffered,
+        })
+    }
+
+    /// 等待下一条通道消息
+    pub(crate) async fn wait(&mut self) -> Option<ChannelMsg> {
+        match self.buffered.pop_front() {
+            Some(msg) => Some(msg),
+            None => self.channel.wait().await,
+        }
+    }
+
+    /// 关闭通道；`kill` 为 true 时先结束远程进程组
+    pub(crate) async fn close(self, kill: bool) {
+        if kill {
+            if let Some(pgid) = self.pgid {
+                let _ = exec_command(&self.server_id, &format!("kill -TERM -- -{} 2>/dev/null", pgid)).await;
+            }
+        }
+        let _ = self.channel.close().await;
+    }
+}
+
+/// 解析 `echo $$` 输出的进程组 ID，拒绝 0 和 1 以免 `kill -- -1` 结束所有进程
+fn parse_pgid(line: &[u8]) -> Option<u32> {
+    String::from_utf8_lossy(line)
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|pgid| *pgid > 1)
+}
+
+/// 通过 SSH 连接打开到远程主机端口的 TCP 转发通道（direct-tcpip）
+pub(crate) async fn open_direct_tcpip_channel(
+    server_id: &str,
+    host: &str,
+    port: u16,
+    originator: std::net::SocketAddr,
+) -> Result<Channel<client::Msg>, String> {
+    let session = get_session(server_id)?;
+    let handle = session.lock().await;
+    handle
+        .channel_open_direct_tcpip(host, port as u32, originator.ip().to_string(), originator.port() as u32)
+        .await
+        .map_err(|e| format!("打开转发通道失败: {}", e))
+}
+
+/// 在远程服务器上执行命令并收集全部输出
+pub(crate) async fn exec_command(server_id: &str, command: &str) -> Result<CommandOutput, String> {
+    let channel = open_exec_channel(server_id, command).await?;
+    Ok(collect_output(channel).await)
+}
+
+/// 在远程服务器上执行命令，将 `input` 写入命令的标准输入后收集全部输出
+pub(crate) async fn exec_command_with_input(server_id: &str, command: &str, input: &[u8]) -> Result<CommandOutput, String> {
+    let channel = open_exec_channel(server_id, command).await?;
+    channel
+        .data(input)
+        .await
+        .map_err(|e| format!("写入标准输入失败: {}", e))?;
+    let _ = channel.eof().await;
+    Ok(collect_output(channel).await)
+}
+
+/// 以 root 权限在远程服务器上执行命令
+///
+/// 当前用户已是 root 时直接执行；提供 sudo 密码时通过 `sudo -S` 从标准输入传入（密码不会出现在命令行中），
+/// 否则使用 `sudo -n`，需要密码时立即失败而不是等待输入。可用 [`is_sudo_password_error`] 判断失败原因。
+pub(crate) async fn exec_privileged_command(
+    server_id: &str,
+    command: &str,
+    sudo_password: Option<&str>,
+) -> Result<CommandOutput, String> {
+    let sudo = if sudo_password.is_some() { "sudo -S -p ''" } else { "sudo -n" };
+    let wrapped = format!(
+        "if [ \"$(id -u)\" -eq 0 ]; then sh -c {cmd}; else {sudo} sh -c {cmd}; fi",
+        cmd = shell_quote(command),
+        sudo = sudo
+    );
+
+    match sudo_password {
+        Some(password) => exec_command_with_input(server_id, &wrapped, format!("{}\n", password).as_bytes()).await,
+        None => exec_command(server_id, &wrapped).await,
+    }
+}
+
+/// 判断 sudo 是否因需要密码或密码错误而失败
+pub(crate) fn is_sudo_password_error(stderr: &str) -> bool {
+    stderr.lines().any(|line| {
+        line.starts_with("sudo:")
+            && (line.contains("password is required")
+                || line.contains("terminal is required")
+                || line.contains("incorrect password")
+                || line.contains("no password was provided"))
+    }) || stderr.contains("Sorry, try again")
+}
+
+/// 读取通道输出直到通道关闭
+async fn collect_output(mut channel: Channel<client::Msg>) -> CommandOutput {
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let mut exit_code = 0;
+
+    // 退出码可能在 EOF 之后才到达，因此读到通道关闭为止
+    loop {
+        match channel.wait().await {
+            Some(ChannelMsg::Data { data }) => {
+                stdout.extend_from_slice(&data);
+            }
+            Some(ChannelMsg::ExtendedData { data, ext: 1 }) => {
+                stderr.extend_from_slice(&data);
+            }
+            Some(ChannelMsg::ExitStatus { exit_status }) => {
+                exit_code = exit_status;
+            }
+            Some(ChannelMsg::Close) | None => {
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let _ = channel.close().await;
+
+    CommandOutput {
+        stdout: String::from_utf8_lossy(&stdout).to_string(),
+        stderr: String::from_utf8_lossy(&stderr).to_string(),
+        exit_code,
+    }
+}
+
+/// 将字符串转义为 shell 单引号字面量，可安全处理空格、引号等特殊字符
+pub(crate) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
 /// 断开 SSH 服务器连接
 /// 
 /// # 命令名称
@@ -382,6 +646,9 @@ pub async fn disconnect_ssh_server(params: DisconnectSshParams) -> Result<Discon
             (None, None)
         }
     };
+
+    // 丢弃该服务器未执行的同步计划
+    discard_server_sync_plans(&params.server_id);
     
     // 停止心跳任务（在锁外）
     if let Some(task) = heartbeat_task_opt {
@@ -0,0 +1,718 @@
+/**
+ * 本地与远程目录同步相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use russh_sftp::client::SftpSession;
+use russh_sftp::protocol::FileAttributes;
+use tauri::Window;
+use crate::checksum::{local_sha256, remote_checksums, ChecksumAlgorithm};
+use crate::file::{join_remote_path, open_sftp, remove_remote_path, sftp_download, sftp_upload};
+use crate::throttle::TransferThrottle;
+
+/// 同步进度事件名
+const SYNC_PROGRESS_EVENT: &str = "sync-progress";
+/// 进度事件最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+/// 未执行的同步计划保留时长，超时后需重新生成
+const PLAN_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// 待执行的同步计划（plan_id -> 计划）
+type SyncPlans = Arc<Mutex<HashMap<String, StoredSyncPlan>>>;
+
+lazy_static::lazy_static! {
+    static ref SYNC_PLANS: SyncPlans = Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// 目录树中的条目
+#[derive(Debug, Clone)]
+struct TreeEntry {
+    is_dir: bool,
+    size: u64,
+    mtime: u64,
+}
+
+/// 目录树（相对路径 -> 条目），按路径排序保证父目录先于子项
+type Tree = BTreeMap<String, TreeEntry>;
+
+/// 同步方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SyncDirection {
+    Upload,   // 本地 -> 远程
+    Download, // 远程 -> 本地
+}
+
+/// 同步操作
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncAction {
+    pub action: String, // "mkdir" | "upload" | "download" | "delete" | "conflict"
+    pub path: String,   // 相对于同步根目录的路径（空字符串表示根目录本身）
+    pub is_dir: bool,   // 源端是否为目录
+    pub size: u64,
+    pub mtime: u64,     // 源文件修改时间（Unix 时间戳，秒）
+    pub reason: String, // "new" | "size" | "mtime" | "checksum" | "extraneous" | "file_over_dir" | "dir_over_file"
+}
+
+/// 已生成但尚未执行的同步计划
+struct StoredSyncPlan {
+    server_id: String,
+    local_dir: PathBuf,
+    remote_dir: String,
+    direction: SyncDirection,
+    actions: Vec<SyncAction>,
+    created_at: Instant,
+}
+
+/// 同步进度事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncProgressEvent {
+    pub plan_id: String,
+    pub index: usize, // 当前操作序号（从 0 开始）
+    pub total: usize, // 操作总数
+    pub action: String,
+    pub path: String,
+    pub transferred: u64, // 当前文件已传输字节数
+    pub size: u64,        // 当前文件大小
+}
+
+/// 同步失败项
+#[derive(Debug, Serialize)]
+pub struct SyncFailure {
+    pub action: String,
+    pub path: String,
+    pub error: String,
+}
+
+/// 生成同步计划参数
+#[derive(Debug, Deserialize)]
+pub struct PlanDirectorySyncParams {
+    pub server_id: String,
+    pub local_dir: String,
+    pub remote_dir: String,
+    pub direction: String,            // "upload" | "download"
+    pub compare_mode: Option<String>, // "size_mtime"（默认）| "checksum"
+    pub delete_extraneous: Option<bool>, // 是否删除目标端多余的文件
+}
+
+/// 生成同步计划返回
+#[derive(Debug, Serialize)]
+pub struct PlanDirectorySyncResult {
+    pub plan_id: String,
+    pub actions: Vec<SyncAction>,
+    pub total_bytes: u64, // 需要传输的总字节数
+}
+
+/// 执行同步计划参数
+#[derive(Debug, Deserialize)]
+pub struct ApplySyncPlanParams {
+    pub plan_id: String,
+}
+
+/// 执行同步计划返回
+#[derive(Debug, Serialize)]
+pub struct ApplySyncPlanResult {
+    pub success: bool,
+    pub applied: usize,
+    pub failed: Vec<SyncFailure>,
+}
+
+/// 丢弃同步计划参数
+#[derive(Debug, Deserialize)]
+pub struct DiscardSyncPlanParams {
+    pub plan_id: String,
+}
+
+/// 丢弃同步计划返回
+#[derive(Debug, Serialize)]
+pub struct DiscardSyncPlanResult {
+    pub success: bool,
+}
+
+/// 生成目录同步计划（试运行，不修改任何文件）
+///
+/// # 命令名称
+/// `plan_directory_sync`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `local_dir`: 本地目录
+/// - `remote_dir`: 远程目录
+/// - `direction`: 同步方向，"upload" 表示本地到远程，"download" 表示远程到本地
+/// - `compare_mode`: 比较方式，"size_mtime" 或 "checksum"（可选）
+/// - `delete_extraneous`: 是否删除目标端多余的文件（可选）
+///
+/// # 返回
+/// - `plan_id`: 计划ID（用于确认执行）
+/// - `actions`: 同步操作列表
+/// - `total_bytes`: 需要传输的总字节数
+#[tauri::command]
+pub async fn plan_directory_sync(params: PlanDirectorySyncParams) -> Result<PlanDirectorySyncResult, String> {
+    let direction = match params.direction.as_str() {
+        "upload" => SyncDirection::Upload,
+        "download" => SyncDirection::Download,
+        other => return Err(format!("不支持的同步方向: {}", other)),
+    };
+    let use_checksum = match params.compare_mode.as_deref() {
+        None | Some("size_mtime") => false,
+        Some("checksum") => true,
+        Some(other) => return Err(format!("不支持的比较方式: {}", other)),
+    };
+    let delete_extraneous = params.delete_extraneous.unwrap_or(false);
+
+    let local_dir = PathBuf::from(&params.local_dir);
+    if !local_dir.is_dir() {
+        return Err(format!("本地目录不存在: {}", params.local_dir));
+    }
+    let remote_dir = params.remote_dir.trim_end_matches('/').to_string();
+    let remote_dir = if remote_dir.is_empty() { "/".to_string() } else { remote_dir };
+
+    let local_tree = scan_local_tree(&local_dir)?;
+
+    let sftp = open_sftp(&params.server_id).await?;
+    let remote_exists = sftp.try_exists(remote_dir.as_str()).await;
+    let remote_tree = match remote_exists {
+        Ok(true) => scan_remote_tree(&sftp, &remote_dir).await,
+        Ok(false) => Ok(Tree::new()),
+        Err(ref e) => Err(format!("读取远程目录失败: {}", e)),
+    };
+    let _ = sftp.close().await;
+    let remote_tree = remote_tree?;
+    let remote_exists = remote_exists.unwrap_or(false);
+    if !remote_exists && direction == SyncDirection::Download {
+        return Err(format!("远程目录不存在: {}", remote_dir));
+    }
+
+    let (source, target) = match direction {
+        SyncDirection::Upload => (&local_tree, &remote_tree),
+        SyncDirection::Download => (&remote_tree, &local_tree),
+    };
+
+    let mut actions = build_sync_actions(source, target, direction, !use_checksum, delete_extraneous);
+
+    // 校验和模式下，大小相同的文件需要进一步比较内容
+    if use_checksum {
+        let candidates: Vec<String> = source
+            .iter()
+            .filter(|(path, entry)| {
+                !entry.is_dir
+                    && matches!(target.get(*path), Some(t) if !t.is_dir && t.size == entry.size)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let remote_paths: Vec<String> = candidates.iter().map(|p| join_remote_path(&remote_dir, p)).collect();
+        let remote_sums = remote_checksums(&params.server_id, &remote_paths, ChecksumAlgorithm::Sha256).await?;
+
+        for (path, remote_path) in candidates.iter().zip(&remote_paths) {
+            let local_sum = local_sha256(&local_dir.join(path)).await?;
+            if remote_sums.get(remote_path) != Some(&local_sum) {
+                let entry = &source[path];
+                actions.push(SyncAction {
+                    action: transfer_action(direction).to_string(),
+                    path: path.clone(),
+                    is_dir: false,
+                    size: entry.size,
+                    mtime: entry.mtime,
+                    reason: "checksum".to_string(),
+                });
+            }
+        }
+
+        sort_actions(&mut actions);
+    }
+
+    // 远程根目录不存在时先创建（包括缺少的上级目录）
+    if !remote_exists {
+        let root = TreeEntry { is_dir: true, size: 0, mtime: 0 };
+        actions.insert(0, new_action("mkdir", "", &root, "new"));
+    }
+
+    let total_bytes = actions
+        .iter()
+        .filter(|a| a.action == "upload" || a.action == "download")
+        .map(|a| a.size)
+        .sum();
+
+    let plan_id = format!(
+        "sync-{}",
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos()
+    );
+
+    {
+        let mut plans = SYNC_PLANS.lock().unwrap();
+        remove_expired_plans(&mut plans, Instant::now());
+        plans.insert(plan_id.clone(), StoredSyncPlan {
+            server_id: params.server_id,
+            local_dir,
+            remote_dir,
+            direction,
+            actions: actions.clone(),
+            created_at: Instant::now(),
+        });
+    }
+
+    Ok(PlanDirectorySyncResult {
+        plan_id,
+        actions,
+        total_bytes,
+    })
+}
+
+/// 执行已确认的同步计划
+///
+/// 执行过程中通过 `sync-progress` 事件推送进度，单个操作失败不会中断整个计划。
+///
+/// # 命令名称
+/// `apply_sync_plan`
+///
+/// # 参数
+/// - `plan_id`: 计划ID
+///
+/// # 返回
+/// - `success`: 是否全部成功
+/// - `applied`: 成功执行的操作数
+/// - `failed`: 失败的操作列表
+#[tauri::command]
+pub async fn apply_sync_plan(window: Window, params: ApplySyncPlanParams) -> Result<ApplySyncPlanResult, String> {
+    let plan = {
+        let mut plans = SYNC_PLANS.lock().unwrap();
+        remove_expired_plans(&mut plans, Instant::now());
+        plans
+            .remove(&params.plan_id)
+            .ok_or_else(|| "同步计划不存在、已执行或已过期".to_string())?
+    };
+
+    let sftp = open_sftp(&plan.server_id).await?;
+    let total = plan.actions.len();
+    let mut applied = 0;
+    let mut failed = Vec::new();
+
+    for (index, action) in plan.actions.iter().enumerate() {
+        let mut progress = SyncProgressEvent {
+            plan_id: params.plan_id.clone(),
+            index,
+            total,
+            action: action.action.clone(),
+            path: action.path.clone(),
+            transferred: 0,
+            size: action.size,
+        };
+        let _ = window.emit(SYNC_PROGRESS_EVENT, progress.clone());
+
+        let mut last_emit = Instant::now();
+        let on_progress = |transferred: u64| {
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                progress.transferred = transferred;
+                let _ = window.emit(SYNC_PROGRESS_EVENT, progress.clone());
+            }
+        };
+
+        match apply_action(&sftp, &plan, action, on_progress).await {
+            Ok(()) => applied += 1,
+            Err(error) => failed.push(SyncFailure {
+                action: action.action.clone(),
+                path: action.path.clone(),
+                error,
+            }),
+        }
+    }
+
+    let _ = sftp.close().await;
+
+    Ok(ApplySyncPlanResult {
+        success: failed.is_empty(),
+        applied,
+        failed,
+    })
+}
+
+/// 丢弃未执行的同步计划
+///
+/// # 命令名称
+/// `discard_sync_plan`
+///
+/// # 参数
+/// - `plan_id`: 计划ID
+///
+/// # 返回
+/// - `success`: 是否成功
+#[tauri::command]
+pub async fn discard_sync_plan(params: DiscardSyncPlanParams) -> Result<DiscardSyncPlanResult, String> {
+    SYNC_PLANS.lock().unwrap().remove(&params.plan_id);
+    Ok(DiscardSyncPlanResult { success: true })
+}
+
+/// 丢弃指定服务器的所有未执行同步计划（断开连接时调用）
+pub(crate) fn discard_server_sync_plans(server_id: &str) {
+    SYNC_PLANS.lock().unwrap().retain(|_, plan| plan.server_id != server_id);
+}
+
+/// 移除超过保留时长的同步计划
+fn remove_expired_plans(plans: &mut HashMap<String, StoredSyncPlan>, now: Instant) {
+    plans.retain(|_, plan| now.duration_since(plan.created_at) < PLAN_TTL);
+}
+
+/// 执行单个同步操作
+///
+/// 类型冲突不会自动处理（需要先删除目标端的同名文件或目录），计为失败。
+async fn apply_action<F: FnMut(u64)>(sftp: &SftpSession, plan: &StoredSyncPlan, action: &SyncAction, on_progress: F) -> Result<(), String> {
+    let local_path = plan.local_dir.join(&action.path);
+    let remote_path = if action.path.is_empty() {
+        plan.remote_dir.clone()
+    } else {
+        join_remote_path(&plan.remote_dir, &action.path)
+    };
+
+    match (action.action.as_str(), plan.direction) {
+        ("mkdir", SyncDirection::Upload) => create_remote_dir_all(sftp, &remote_path).await,
+        ("mkdir", SyncDirection::Download) => {
+            std::fs::create_dir_all(&local_path).map_err(|e| format!("创建本地目录失败: {}", e))
+        }
+        ("upload", _) => {
+            sftp_upload(sftp, &local_path, &remote_path, &TransferThrottle::global(), on_progress).await?;
+            // 保持修改时间一致，下次比较时不会重复传输
+            let attributes = FileAttributes {
+                atime: Some(action.mtime as u32),
+                mtime: Some(action.mtime as u32),
+                ..FileAttributes::empty()
+            };
+            sftp.set_metadata(remote_path.as_str(), attributes)
+                .await
+                .map_err(|e| format!("设置远程文件时间失败: {}", e))
+        }
+        ("download", _) => {
+            sftp_download(sftp, &remote_path, &local_path, &TransferThrottle::global(), on_progress).await?;
+            let mtime = filetime::FileTime::from_unix_time(action.mtime as i64, 0);
+            filetime::set_file_mtime(&local_path, mtime).map_err(|e| format!("设置本地文件时间失败: {}", e))
+        }
+        ("delete", SyncDirection::Upload) => remove_remote_path(sftp, &remote_path).await,
+        ("delete", SyncDirection::Download) => {
+            let result = if action.is_dir {
+                std::fs::remove_dir_all(&local_path)
+            } else {
+                std::fs::remove_file(&local_path)
+            };
+            result.map_err(|e| format!("删除本地文件失败: {}", e))
+        }
+        ("conflict", _) if action.is_dir => Err("目标端同名路径是文件，已跳过".to_string()),
+        ("conflict", _) => Err("目标端同名路径是目录，已跳过".to_string()),
+        (other, _) => Err(format!("未知的同步操作: {}", other)),
+    }
+}
+
+/// 逐级创建远程目录（相当于 `mkdir -p`）
+async fn create_remote_dir_all(sftp: &SftpSession, path: &str) -> Result<(), String> {
+    let mut current = String::new();
+    for component in path.split('/').filter(|c| !c.is_empty()) {
+        current = format!("{}/{}", current, component);
+        if !sftp.try_exists(current.as_str()).await.unwrap_or(false) {
+            sftp.create_dir(current.as_str())
+                .await
+                .map_err(|e| format!("创建远程目录失败: {}: {}", current, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 比较源目录树和目标目录树，生成同步操作
+///
+/// `compare_mtime` 为 false 时（校验和模式），大小相同的文件不在此处判定。
+/// 源端与目标端类型不同（文件对目录）时生成 `conflict` 操作，冲突路径下的子项不再比较。
+fn build_sync_actions(source: &Tree, target: &Tree, direction: SyncDirection, compare_mtime: bool, delete_extraneous: bool) -> Vec<SyncAction> {
+    let mut actions = Vec::new();
+    let mut conflicts: Vec<&String> = Vec::new();
+    let under = |dirs: &[&String], path: &str| dirs.iter().any(|dir| path.starts_with(&format!("{}/", dir)));
+
+    for (path, entry) in source {
+        if under(&conflicts, path) {
+            continue;
+        }
+        let existing = target.get(path);
+
+        if existing.map_or(false, |t| t.is_dir != entry.is_dir) {
+            let reason = if entry.is_dir { "dir_over_file" } else { "file_over_dir" };
+            actions.push(new_action("conflict", path, entry, reason));
+            conflicts.push(path);
+            continue;
+        }
+
+        if entry.is_dir {
+            if existing.is_none() {
+                actions.push(new_action("mkdir", path, entry, "new"));
+            }
+            continue;
+        }
+
+        let reason = match existing {
+            None => Some("new"),
+            Some(t) if t.size != entry.size => Some("size"),
+            Some(t) if compare_mtime && t.mtime != entry.mtime => Some("mtime"),
+            _ => None,
+        };
+
+        if let Some(reason) = reason {
+            actions.push(new_action(transfer_action(direction), path, entry, reason));
+        }
+    }
+
+    if delete_extraneous {
+        let mut deleted_dirs: Vec<&String> = Vec::new();
+        for (path, entry) in target {
+            // 上级目录已被删除或存在类型冲突时无需单独处理
+            if source.contains_key(path) || under(&deleted_dirs, path) || under(&conflicts, path) {
+                continue;
+            }
+            if entry.is_dir {
+                deleted_dirs.push(path);
+            }
+            actions.push(new_action("delete", path, entry, "extraneous"));
+        }
+    }
+
+    sort_actions(&mut actions);
+    actions
+}
+
+/// 创建同步操作
+fn new_action(action: &str, path: &str, entry: &TreeEntry, reason: &str) -> SyncAction {
+    SyncAction {
+        action: action.to_string(),
+        path: path.to_string(),
+        is_dir: entry.is_dir,
+        size: if entry.is_dir { 0 } else { entry.size },
+        mtime: entry.mtime,
+        reason: reason.to_string(),
+    }
+}
+
+/// 根据同步方向返回传输操作名
+fn transfer_action(direction: SyncDirection) -> &'static str {
+    match direction {
+        SyncDirection::Upload => "upload",
+        SyncDirection::Download => "download",
+    }
+}
+
+/// 排序同步操作：先创建目录，再传输文件，然后删除，冲突放在最后
+fn sort_actions(actions: &mut [SyncAction]) {
+    fn rank(action: &str) -> u8 {
+        match action {
+            "mkdir" => 0,
+            "delete" => 2,
+            "conflict" => 3,
+            _ => 1,
+        }
+    }
+    actions.sort_by(|a, b| rank(&a.action).cmp(&rank(&b.action)).then_with(|| a.path.cmp(&b.path)));
+}
+
+/// 扫描本地目录树（不跟随符号链接）
+fn scan_local_tree(root: &Path) -> Result<Tree, String> {
+    let mut tree = Tree::new();
+    let mut pending = vec![(root.to_path_buf(), String::new())];
+
+    while let Some((dir, prefix)) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| format!("读取本地目录失败: {}: {}", dir.display(), e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("读取本地目录失败: {}", e))?;
+            let metadata = entry.metadata().map_err(|e| format!("读取本地文件信息失败: {}", e))?;
+            let file_type = metadata.file_type();
+            if file_type.is_symlink() {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            let rel_path = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+            let mtime = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+
+            if file_type.is_dir() {
+                pending.push((entry.path(), rel_path.clone()));
+            }
+
+            tree.insert(rel_path, TreeEntry {
+                is_dir: file_type.is_dir(),
+                size: metadata.len(),
+                mtime,
+            });
+        }
+    }
+
+    Ok(tree)
+}
+
+/// 通过 SFTP 扫描远程目录树（不跟随符号链接）
+async fn scan_remote_tree(sftp: &SftpSession, root: &str) -> Result<Tree, String> {
+    let mut tree = Tree::new();
+    let mut pending = vec![(root.to_string(), String::new())];
+
+    while let Some((dir, prefix)) = pending.pop() {
+        let entries = sftp
+            .read_dir(dir.as_str())
+            .await
+            .map_err(|e| format!("读取远程目录失败: {}: {}", dir, e))?;
+
+        for entry in entries {
+            let name = entry.file_name();
+            if name == "." || name == ".." {
+                continue;
+            }
+
+            let metadata = entry.metadata();
+            let file_type = metadata.file_type();
+            if file_type.is_symlink() {
+                continue;
+            }
+
+            let rel_path = if prefix.is_empty() { name.clone() } else { format!("{}/{}", prefix, name) };
+            if file_type.is_dir() {
+                pending.push((join_remote_path(&dir, &name), rel_path.clone()));
+            }
+
+            tree.insert(rel_path, TreeEntry {
+                is_dir: file_type.is_dir(),
+                size: metadata.len(),
+                mtime: metadata.mtime.unwrap_or(0) as u64,
+            });
+        }
+    }
+
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(entries: &[(&str, bool, u64, u64)]) -> Tree {
+        entries
+            .iter()
+            .map(|(path, is_dir, size, mtime)| (path.to_string(), TreeEntry { is_dir: *is_dir, size: *size, mtime: *mtime }))
+            .collect()
+    }
+
+    fn summary(actions: &[SyncAction]) -> Vec<(&str, &str, &str)> {
+        actions.iter().map(|a| (a.action.as_str(), a.path.as_str(), a.reason.as_str())).collect()
+    }
+
+    #[test]
+    fn new_entries_create_dirs_before_transfers() {
+        let source = tree(&[("a", true, 0, 1), ("a/x.txt", false, 3, 1), ("y.txt", false, 5, 1)]);
+        let actions = build_sync_actions(&source, &Tree::new(), SyncDirection::Upload, true, false);
+        assert_eq!(summary(&actions), vec![
+            ("mkdir", "a", "new"),
+            ("upload", "a/x.txt", "new"),
+            ("upload", "y.txt", "new"),
+        ]);
+        assert_eq!(actions[0].size, 0);
+    }
+
+    #[test]
+    fn changed_files_compare_size_then_mtime() {
+        let source = tree(&[("same.txt", false, 3, 10), ("size.txt", false, 4, 10), ("mtime.txt", false, 3, 20)]);
+        let target = tree(&[("same.txt", false, 3, 10), ("size.txt", false, 3, 10), ("mtime.txt", false, 3, 10)]);
+        let actions = build_sync_actions(&source, &target, SyncDirection::Download, true, false);
+        assert_eq!(summary(&actions), vec![
+            ("download", "mtime.txt", "mtime"),
+            ("download", "size.txt", "size"),
+        ]);
+    }
+
+    #[test]
+    fn checksum_mode_leaves_same_size_files_undecided() {
+        let source = tree(&[("mtime.txt", false, 3, 20), ("size.txt", false, 4, 10)]);
+        let target = tree(&[("mtime.txt", false, 3, 10), ("size.txt", false, 3, 10)]);
+        let actions = build_sync_actions(&source, &target, SyncDirection::Upload, false, false);
+        assert_eq!(summary(&actions), vec![("upload", "size.txt", "size")]);
+    }
+
+    #[test]
+    fn extraneous_entries_deleted_only_when_requested() {
+        let source = tree(&[("keep.txt", false, 1, 1)]);
+        let target = tree(&[
+            ("keep.txt", false, 1, 1),
+            ("old", true, 0, 1),
+            ("old/a.txt", false, 1, 1),
+            ("old/b", true, 0, 1),
+            ("stale.txt", false, 1, 1),
+        ]);
+        assert!(build_sync_actions(&source, &target, SyncDirection::Upload, true, false).is_empty());
+
+        let actions = build_sync_actions(&source, &target, SyncDirection::Upload, true, true);
+        assert_eq!(summary(&actions), vec![
+            ("delete", "old", "extraneous"),
+            ("delete", "stale.txt", "extraneous"),
+        ]);
+        assert!(actions[0].is_dir);
+    }
+
+    #[test]
+    fn type_conflicts_are_reported_and_not_descended() {
+        let source = tree(&[
+            ("dir", true, 0, 1),
+            ("dir/inner.txt", false, 1, 1),
+            ("file", false, 2, 1),
+        ]);
+        let target = tree(&[
+            ("dir", false, 7, 1),
+            ("file", true, 0, 1),
+            ("file/child.txt", false, 1, 1),
+        ]);
+        let actions = build_sync_actions(&source, &target, SyncDirection::Upload, true, true);
+        assert_eq!(summary(&actions), vec![
+            ("conflict", "dir", "dir_over_file"),
+            ("conflict", "file", "file_over_dir"),
+        ]);
+        assert!(actions[0].is_dir);
+        assert!(!actions[1].is_dir);
+    }
+
+    fn stored_plan(server_id: &str, created_at: Instant) -> StoredSyncPlan {
+        StoredSyncPlan {
+            server_id: server_id.to_string(),
+            local_dir: PathBuf::from("/tmp/local"),
+            remote_dir: "/srv".to_string(),
+            direction: SyncDirection::Upload,
+            actions: Vec::new(),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn expired_plans_are_removed() {
+        let now = Instant::now();
+        let mut plans = HashMap::new();
+        plans.insert("fresh".to_string(), stored_plan("a", now));
+        if let Some(old) = now.checked_sub(PLAN_TTL) {
+            plans.insert("old".to_string(), stored_plan("a", old));
+        }
+
+        remove_expired_plans(&mut plans, now);
+        assert_eq!(plans.keys().collect::<Vec<_>>(), vec!["fresh"]);
+
+        remove_expired_plans(&mut plans, now + PLAN_TTL);
+        assert!(plans.is_empty());
+    }
+
+    #[test]
+    fn disconnect_discards_only_that_servers_plans() {
+        let now = Instant::now();
+        SYNC_PLANS.lock().unwrap().insert("plan-test-a".to_string(), stored_plan("server-test-a", now));
+        SYNC_PLANS.lock().unwrap().insert("plan-test-b".to_string(), stored_plan("server-test-b", now));
+
+        discard_server_sync_plans("server-test-a");
+        let plans = SYNC_PLANS.lock().unwrap();
+        assert!(!plans.contains_key("plan-test-a"));
+        assert!(plans.contains_key("plan-test-b"));
+    }
+}
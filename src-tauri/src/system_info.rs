@@ -0,0 +1,414 @@
+/**
+ * 主机信息采集相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::ssh::{exec_command, get_system_info_cache, shell_quote};
+use crate::util::{split_sections, unix_now};
+
+/// 一次性采集主机信息的远程命令，各部分输出以 `==名称==` 行分隔
+const SYSTEM_INFO_COMMAND: &str = "echo '==hostname=='; hostname 2>/dev/null || uname -n; \
+echo '==uname=='; uname -s; uname -r; uname -m; \
+echo '==osrelease=='; cat /etc/os-release 2>/dev/null || sw_vers 2>/dev/null; \
+echo '==now=='; date +%s; \
+echo '==uptime=='; cat /proc/uptime 2>/dev/null || sysctl -n kern.boottime 2>/dev/null; \
+echo '==cpumodel=='; grep -m1 -E '^(model name|Hardware|Processor|cpu model)' /proc/cpuinfo 2>/dev/null || sysctl -n machdep.cpu.brand_string 2>/dev/null || sysctl -n hw.model 2>/dev/null; \
+echo '==cores=='; getconf _NPROCESSORS_ONLN 2>/dev/null || sysctl -n hw.ncpu 2>/dev/null; \
+echo '==virt=='; systemd-detect-virt 2>/dev/null; [ -f /.dockerenv ] && echo docker; grep -qs '^flags.* hypervisor' /proc/cpuinfo && echo vm; \
+echo '==timezone=='; timedatectl show -p Timezone --value 2>/dev/null; cat /etc/timezone 2>/dev/null; readlink /etc/localtime 2>/dev/null; date +%Z; \
+echo '==pkg=='; for m in apt-get dnf yum zypper pacman apk brew pkg; do command -v $m >/dev/null 2>&1 && echo $m; done; \
+echo '==users=='; who 2>/dev/null";
+
+/// 已登录用户
+#[derive(Debug, Clone, Serialize)]
+pub struct LoggedInUser {
+    pub user: String,
+    pub tty: String,
+    pub login_time: String,
+    pub from: Option<String>, // 远程登录来源地址（可选）
+}
+
+/// 主机信息
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub hostname: String,
+    pub os_name: String,                 // 发行版名称，如 "Ubuntu 22.04.3 LTS"
+    pub os_id: Option<String>,           // 发行版标识，如 "ubuntu"、"centos"、"macos"
+    pub os_version: Option<String>,      // 发行版版本号
+    pub kernel: String,                  // 内核名称及版本，如 "Linux 5.15.0-88-generic"
+    pub arch: String,                    // 硬件架构，如 "x86_64"
+    pub boot_time: Option<i64>,          // 启动时间（Unix 时间戳，秒）
+    pub uptime: Option<u64>,             // 已运行时间（秒）
+    pub cpu_model: Option<String>,
+    pub cpu_cores: Option<usize>,
+    pub virtualization: String,          // 虚拟化类型，如 "kvm"、"docker"、"vm"，物理机为 "none"
+    pub timezone: Option<String>,
+    pub package_manager: Option<String>, // 软件包管理器，如 "apt-get"、"dnf"
+    pub users: Vec<LoggedInUser>,
+    pub collected_at: i64,               // 采集时间（Unix 时间戳，秒）
+}
+
+impl SystemInfo {
+    /// 生成一段简短的主机描述，用作 AI 对话的上下文
+    pub fn describe(&self) -> String {
+        let mut lines = vec![
+            format!("主机名: {}", self.hostname),
+            format!("操作系统: {}", self.os_name),
+            format!("内核: {} ({})", self.kernel, self.arch),
+        ];
+        if let Some(cpu_model) = &self.cpu_model {
+            lines.push(format!("CPU: {} × {}", cpu_model, self.cpu_cores.unwrap_or(0)));
+        }
+        if self.virtualization != "none" {
+            lines.push(format!("虚拟化: {}", self.virtualization));
+        }
+        if let Some(package_manager) = &self.package_manager {
+            lines.push(format!("软件包管理器: {}", package_manager));
+        }
+        if let Some(timezone) = &self.timezone {
+            lines.push(format!("时区: {}", timezone));
+        }
+        lines.join("\n")
+    }
+}
+
+/// 获取主机信息参数
+#[derive(Debug, Deserialize)]
+pub struct GetSystemInfoParams {
+    pub server_id: String,
+    pub refresh: Option<bool>, // 是否忽略缓存重新采集
+}
+
+/// 获取服务器主机信息
+///
+/// 结果缓存在当前连接上，断开连接后缓存随之释放；返回缓存时会按经过的时间更新运行时长。
+///
+/// # 命令名称
+/// `get_system_info`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `refresh`: 是否重新采集（可选）
+///
+/// # 返回
+/// 主机信息（操作系统、内核、主机名、运行时长、CPU 型号、虚拟化类型、时区、软件包管理器、已登录用户等）
+#[tauri::command]
+pub async fn get_system_info(params: GetSystemInfoParams) -> Result<SystemInfo, String> {
+    let cache = get_system_info_cache(&params.server_id)?;
+
+    if !params.refresh.unwrap_or(false) {
+        if let Some(mut info) = cache.lock().unwrap().clone() {
+            let elapsed = (unix_now() - info.collected_at).max(0) as u64;
+            info.uptime = info.uptime.map(|uptime| uptime + elapsed);
+            return Ok(info);
+        }
+    }
+
+    // 统一交给 sh 执行，FreeBSD 等系统的登录 shell 可能是 csh
+    let command = format!("sh -c {}", shell_quote(SYSTEM_INFO_COMMAND));
+    let output = exec_command(&params.server_id, &command).await?;
+    let info = parse_system_info(&output.stdout, unix_now());
+    *cache.lock().unwrap() = Some(info.clone());
+
+    Ok(info)
+}
+
+/// 获取已缓存的主机信息（不触发远程采集）
+pub(crate) fn cached_system_info(server_id: &str) -> Option<SystemInfo> {
+    get_system_info_cache(server_id).ok()?.lock().unwrap().clone()
+}
+
+/// 解析主机信息命令输出
+fn parse_system_info(output: &str, collected_at: i64) -> SystemInfo {
+    let sections = split_sections(output);
+    // 各部分的非空行
+    let section = |name: &str| -> Vec<String> {
+        sections
+            .get(name)
+            .map(|text| text.lines().map(str::trim_end).filter(|l| !l.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default()
+    };
+    let first = |name: &str| section(name).into_iter().next();
+
+    let uname = section("uname");
+    let kernel = uname.iter().take(2).cloned().collect::<Vec<_>>().join(" ");
+    let arch = uname.get(2).cloned().unwrap_or_default();
+    let (os_name, os_id, os_version) = parse_os_release(&section("osrelease"), &kernel);
+
+    let remote_now = first("now").and_then(|v| v.trim().parse::<i64>().ok());
+    let (boot_time, uptime) = parse_uptime(&section("uptime"), remote_now);
+
+    let virtualization = section("virt")
+        .into_iter()
+        .map(|v| v.trim().to_string())
+        .find(|v| !v.is_empty() && v != "none")
+        .unwrap_or_else(|| "none".to_string());
+
+    SystemInfo {
+        hostname: first("hostname").unwrap_or_default().trim().to_string(),
+        os_name,
+        os_id,
+        os_version,
+        kernel,
+        arch,
+        boot_time,
+        uptime,
+        cpu_model: first("cpumodel").map(|line| parse_cpu_model(&line)).filter(|m| !m.is_empty()),
+        cpu_cores: first("cores").and_then(|v| v.trim().parse().ok()),
+        virtualization,
+        timezone: first("timezone").map(|tz| parse_timezone(&tz)),
+        package_manager: first("pkg"),
+        users: section("users").iter().filter_map(|line| parse_who_line(line)).collect(),
+        collected_at,
+    }
+}
+
+/// 解析 `/etc/os-release` 或 `sw_vers` 输出，返回（名称，标识，版本）
+fn parse_os_release(lines: &[String], kernel: &str) -> (String, Option<String>, Option<String>) {
+    let values: HashMap<&str, String> = lines
+        .iter()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=').or_else(|| line.split_once(':'))?;
+            Some((key.trim(), value.trim().trim_matches('"').to_string()))
+        })
+        .collect();
+
+    // macOS: ProductName / ProductVersion
+    if let Some(product) = values.get("ProductName") {
+        let version = values.get("ProductVersion").cloned();
+        let name = match &version {
+            Some(version) => format!("{} {}", product, version),
+            None => product.clone(),
+        };
+        return (name, Some("macos".to_string()), version);
+    }
+
+    let id = values.get("ID").cloned();
+    let version = values.get("VERSION_ID").cloned();
+    let name = values
+        .get("PRETTY_NAME")
+        .or_else(|| values.get("NAME"))
+        .cloned()
+        .unwrap_or_else(|| kernel.to_string());
+    (name, id, version)
+}
+
+/// 解析 `/proc/uptime`（秒）或 `kern.boottime`（`{ sec = 1700000000, usec = 0 } ...`），返回（启动时间，运行时长）
+fn parse_uptime(lines: &[String], remote_now: Option<i64>) -> (Option<i64>, Option<u64>) {
+    let line = match lines.first() {
+        Some(line) => line,
+        None => return (None, None),
+    };
+
+    if let Some(rest) = line.split("sec =").nth(1) {
+        let boot_time = rest.split(',').next().and_then(|v| v.trim().parse::<i64>().ok());
+        let uptime = match (boot_time, remote_now) {
+            (Some(boot), Some(now)) if now >= boot => Some((now - boot) as u64),
+            _ => None,
+        };
+        return (boot_time, uptime);
+    }
+
+    let uptime = line
+        .split_whitespace()
+        .next()
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|v| v as u64);
+    let boot_time = match (uptime, remote_now) {
+        (Some(uptime), Some(now)) => Some(now - uptime as i64),
+        _ => None,
+    };
+    (boot_time, uptime)
+}
+
+/// 从 `/proc/cpuinfo` 行（`model name : ...`）中取出 CPU 型号，sysctl 输出原样返回
+fn parse_cpu_model(line: &str) -> String {
+    match line.split_once(':') {
+        Some((_, model)) => model.trim().to_string(),
+        None => line.trim().to_string(),
+    }
+}
+
+/// 规范化时区：`/usr/share/zoneinfo/Asia/Shanghai` -> `Asia/Shanghai`
+fn parse_timezone(line: &str) -> String {
+    match line.split_once("zoneinfo/") {
+        Some((_, zone)) => zone.trim().to_string(),
+        None => line.trim().to_string(),
+    }
+}
+
+/// 解析 `who` 输出行：`root     pts/0        2024-01-01 10:00 (192.168.1.10)`
+fn parse_who_line(line: &str) -> Option<LoggedInUser> {
+    let mut fields = line.split_whitespace();
+    let user = fields.next()?.to_string();
+    let tty = fields.next()?.to_string();
+    let rest: Vec<&str> = fields.collect();
+
+    let (time_parts, from) = match rest.last() {
+        Some(last) if last.starts_with('(') && last.ends_with(')') => {
+            (&rest[..rest.len() - 1], Some(last.trim_matches(|c| c == '(' || c == ')').to_string()))
+        }
+        _ => (&rest[..], None),
+    };
+
+    Some(LoggedInUser {
+        user,
+        tty,
+        login_time: time_parts.join(" "),
+        from,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINUX_OUTPUT: &str = "==hostname==
+web-01
+==uname==
+Linux
+5.15.0-88-generic
+x86_64
+==osrelease==
+PRETTY_NAME=\"Ubuntu 22.04.3 LTS\"
+NAME=\"Ubuntu\"
+VERSION_ID=\"22.04\"
+VERSION=\"22.04.3 LTS (Jammy Jellyfish)\"
+ID=ubuntu
+ID_LIKE=debian
+==now==
+1700000000
+==uptime==
+352315.62 1384213.10
+==cpumodel==
+model name\t: Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz
+==cores==
+4
+==virt==
+kvm
+vm
+==timezone==
+Asia/Shanghai
+Asia/Shanghai
+/usr/share/zoneinfo/Asia/Shanghai
+CST
+==pkg==
+apt-get
+==users==
+root     pts/0        2023-11-14 10:00 (192.168.1.10)
+deploy   tty1         2023-11-13 08:12
+";
+
+    const MACOS_OUTPUT: &str = "==hostname==
+mac-mini.local
+==uname==
+Darwin
+23.1.0
+arm64
+==osrelease==
+ProductName:\t\tmacOS
+ProductVersion:\t\t14.1.1
+BuildVersion:\t\t23B81
+==now==
+1700000000
+==uptime==
+{ sec = 1699900000, usec = 123456 } Mon Nov 13 18:26:40 2023
+==cpumodel==
+Apple M2
+==cores==
+8
+==virt==
+==timezone==
+/var/db/timezone/zoneinfo/Europe/Berlin
+CET
+==pkg==
+brew
+==users==
+admin    console  Nov 13 18:27
+admin    ttys000  Nov 14 09:10
+";
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parses_linux_host() {
+        let info = parse_system_info(LINUX_OUTPUT, 42);
+        assert_eq!(info.hostname, "web-01");
+        assert_eq!(info.os_name, "Ubuntu 22.04.3 LTS");
+        assert_eq!(info.os_id.as_deref(), Some("ubuntu"));
+        assert_eq!(info.os_version.as_deref(), Some("22.04"));
+        assert_eq!(info.kernel, "Linux 5.15.0-88-generic");
+        assert_eq!(info.arch, "x86_64");
+        assert_eq!(info.uptime, Some(352315));
+        assert_eq!(info.boot_time, Some(1700000000 - 352315));
+        assert_eq!(info.cpu_model.as_deref(), Some("Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz"));
+        assert_eq!(info.cpu_cores, Some(4));
+        assert_eq!(info.virtualization, "kvm");
+        assert_eq!(info.timezone.as_deref(), Some("Asia/Shanghai"));
+        assert_eq!(info.package_manager.as_deref(), Some("apt-get"));
+        assert_eq!(info.users.len(), 2);
+        assert_eq!(info.users[0].from.as_deref(), Some("192.168.1.10"));
+        assert_eq!(info.collected_at, 42);
+    }
+
+    #[test]
+    fn parses_macos_host() {
+        let info = parse_system_info(MACOS_OUTPUT, 42);
+        assert_eq!(info.os_name, "macOS 14.1.1");
+        assert_eq!(info.os_id.as_deref(), Some("macos"));
+        assert_eq!(info.os_version.as_deref(), Some("14.1.1"));
+        assert_eq!(info.kernel, "Darwin 23.1.0");
+        assert_eq!(info.arch, "arm64");
+        assert_eq!(info.boot_time, Some(1699900000));
+        assert_eq!(info.uptime, Some(100000));
+        assert_eq!(info.cpu_model.as_deref(), Some("Apple M2"));
+        assert_eq!(info.cpu_cores, Some(8));
+        assert_eq!(info.virtualization, "none");
+        assert_eq!(info.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(info.package_manager.as_deref(), Some("brew"));
+        assert_eq!(info.users[1].tty, "ttys000");
+        assert_eq!(info.users[1].login_time, "Nov 14 09:10");
+    }
+
+    #[test]
+    fn falls_back_to_kernel_without_os_release() {
+        let (name, id, version) = parse_os_release(&[], "FreeBSD 14.0-RELEASE");
+        assert_eq!((name.as_str(), id, version), ("FreeBSD 14.0-RELEASE", None, None));
+
+        let (name, _, _) = parse_os_release(&lines("NAME=\"Alpine Linux\"\nID=alpine"), "Linux 6.1");
+        assert_eq!(name, "Alpine Linux");
+    }
+
+    #[test]
+    fn parses_uptime_without_remote_clock() {
+        assert_eq!(parse_uptime(&lines("120.50 300.00"), None), (None, Some(120)));
+        assert_eq!(parse_uptime(&lines("{ sec = 1699900000, usec = 0 } Mon Nov 13"), None), (Some(1699900000), None));
+        // 远端时钟早于启动时间时不计算运行时长
+        assert_eq!(parse_uptime(&lines("{ sec = 1699900000, usec = 0 }"), Some(1699800000)), (Some(1699900000), None));
+        assert_eq!(parse_uptime(&[], Some(1)), (None, None));
+    }
+
+    #[test]
+    fn normalizes_timezone() {
+        assert_eq!(parse_timezone("/usr/share/zoneinfo/America/New_York"), "America/New_York");
+        assert_eq!(parse_timezone("../usr/share/zoneinfo/UTC"), "UTC");
+        assert_eq!(parse_timezone("  Etc/UTC "), "Etc/UTC");
+    }
+
+    #[test]
+    fn parses_who_lines() {
+        let user = parse_who_line("root     pts/0        2023-11-14 10:00 (192.168.1.10)").unwrap();
+        assert_eq!((user.user.as_str(), user.tty.as_str()), ("root", "pts/0"));
+        assert_eq!(user.login_time, "2023-11-14 10:00");
+        assert_eq!(user.from.as_deref(), Some("192.168.1.10"));
+
+        let user = parse_who_line("deploy   tty1         2023-11-13 08:12").unwrap();
+        assert_eq!(user.from, None);
+        assert_eq!(user.login_time, "2023-11-13 08:12");
+
+        assert!(parse_who_line("root").is_none());
+    }
+}
@@ -0,0 +1,529 @@
+/**
+ * systemd 服务管理相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::ssh::{exec_command, exec_privileged_command, is_sudo_password_error, shell_quote};
+
+/// 默认读取的日志行数
+const DEFAULT_JOURNAL_LINES: u32 = 50;
+/// 最大读取的日志行数
+const MAX_JOURNAL_LINES: u32 = 1000;
+
+/// 查询单元详情时读取的属性
+const UNIT_PROPERTIES: &str = "Id,Description,LoadState,ActiveState,SubState,UnitFileState,MainPID,\
+ActiveEnterTimestamp,ExecMainStartTimestamp,MemoryCurrent,TasksCurrent,NRestarts,Result,FragmentPath";
+
+/// 单元操作
+const UNIT_ACTIONS: &[&str] = &["start", "stop", "restart", "reload", "enable", "disable"];
+
+/// 单元列表项
+#[derive(Debug, Clone, Serialize)]
+pub struct UnitInfo {
+    pub name: String,
+    pub load: String,                    // 加载状态，如 "loaded"、"not-found"，未加载时为 "not-loaded"
+    pub active: String,                  // 活动状态，如 "active"、"inactive"、"failed"
+    pub sub: String,                     // 子状态，如 "running"、"exited"、"dead"
+    pub description: String,
+    pub unit_file_state: Option<String>, // 开机启动状态，如 "enabled"、"disabled"、"static"
+}
+
+/// 列出单元参数
+#[derive(Debug, Deserialize)]
+pub struct ListUnitsParams {
+    pub server_id: String,
+    pub unit_type: Option<String>, // 单元类型（默认 "service"）
+    pub state: Option<String>,     // 按活动状态或子状态过滤，如 "active"、"failed"、"running"
+    pub pattern: Option<String>,   // 按名称或描述过滤（不区分大小写）
+}
+
+/// 列出单元返回
+#[derive(Debug, Serialize)]
+pub struct ListUnitsResult {
+    pub units: Vec<UnitInfo>,
+}
+
+/// 单元详情参数
+#[derive(Debug, Deserialize)]
+pub struct GetUnitStatusParams {
+    pub server_id: String,
+    pub unit: String,
+    pub journal_lines: Option<u32>, // 读取的最近日志行数（默认 50）
+}
+
+/// 单元详情
+#[derive(Debug, Clone, Serialize)]
+pub struct UnitStatus {
+    pub name: String,
+    pub description: String,
+    pub load: String,
+    pub active: String,
+    pub sub: String,
+    pub unit_file_state: Option<String>,
+    pub main_pid: Option<u32>,
+    pub active_since: Option<String>, // 进入当前活动状态的时间
+    pub memory: Option<u64>,          // 当前内存占用（字节）
+    pub tasks: Option<u64>,           // 当前任务（线程）数
+    pub restarts: Option<u64>,        // 自动重启次数
+    pub result: Option<String>,       // 上次运行结果，如 "success"、"exit-code"
+    pub fragment_path: Option<String>, // 单元文件路径
+}
+
+/// 单元详情返回
+#[derive(Debug, Serialize)]
+pub struct GetUnitStatusResult {
+    pub status: UnitStatus,
+    pub journal: Vec<String>,          // 最近的日志行
+    pub journal_error: Option<String>, // 读取日志失败的原因（如当前用户无权查看日志）
+}
+
+/// 单元操作参数
+#[derive(Debug, Deserialize)]
+pub struct ManageUnitParams {
+    pub server_id: String,
+    pub unit: String,
+    pub action: String,                // start | stop | restart | reload | enable | disable
+    pub sudo_password: Option<String>, // sudo 密码（可选，非 root 用户且 sudo 需要密码时提供）
+}
+
+/// 单元操作返回
+#[derive(Debug, Serialize)]
+pub struct ManageUnitResult {
+    pub success: bool,
+    pub unit: String,
+    pub action: String,
+    pub password_required: bool,   // 是否因需要 sudo 密码（或密码错误）而失败
+    pub status: Option<UnitStatus>, // 操作后的单元状态
+    pub message: Option<String>,
+}
+
+/// 列出 systemd 单元
+///
+/// 合并 `systemctl list-units` 与 `list-unit-files` 的结果，未加载的已安装单元也会列出。
+///
+/// # 命令名称
+/// `list_systemd_units`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `unit_type`: 单元类型（可选）
+/// - `state`: 状态过滤（可选）
+/// - `pattern`: 名称过滤（可选）
+///
+/// # 返回
+/// - `units`: 单元列表，按名称排序
+#[tauri::command]
+pub async fn list_systemd_units(params: ListUnitsParams) -> Result<ListUnitsResult, String> {
+    let unit_type = params.unit_type.as_deref().unwrap_or("service");
+    if !is_valid_unit_name(unit_type) {
+        return Err(format!("无效的单元类型: {}", unit_type));
+    }
+
+    let command = format!(
+        "systemctl list-units --type={t} --all --no-legend --no-pager --plain; echo '==unit-files=='; \
+         systemctl list-unit-files --type={t} --no-legend --no-pager",
+        t = unit_type
+    );
+    let output = exec_command(&params.server_id, &command).await?;
+    if output.exit_code != 0 && output.stdout.trim().is_empty() {
+        return Err(systemctl_error(&output.stderr));
+    }
+
+    let (units_text, files_text) = output
+        .stdout
+        .split_once("==unit-files==")
+        .unwrap_or((output.stdout.as_str(), ""));
+    let mut units = merge_units(parse_list_units(units_text), parse_unit_files(files_text));
+
+    if let Some(state) = params.state.as_deref().filter(|s| !s.is_empty()) {
+        units.retain(|unit| unit.active == state || unit.sub == state);
+    }
+    if let Some(pattern) = params.pattern.as_deref().filter(|p| !p.is_empty()) {
+        let pattern = pattern.to_lowercase();
+        units.retain(|unit| {
+            unit.name.to_lowercase().contains(&pattern) || unit.description.to_lowercase().contains(&pattern)
+        });
+    }
+
+    Ok(ListUnitsResult { units })
+}
+
+/// 获取 systemd 单元详情及最近日志
+///
+/// # 命令名称
+/// `get_systemd_unit_status`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `unit`: 单元名称
+/// - `journal_lines`: 日志行数（可选）
+///
+/// # 返回
+/// - `status`: 单元详情
+/// - `journal`: 最近的日志行
+/// - `journal_error`: 读取日志失败的原因（可选）
+#[tauri::command]
+pub async fn get_systemd_unit_status(params: GetUnitStatusParams) -> Result<GetUnitStatusResult, String> {
+    if !is_valid_unit_name(&params.unit) {
+        return Err(format!("无效的单元名称: {}", params.unit));
+    }
+
+    let status = query_unit_status(&params.server_id, &params.unit).await?;
+
+    let lines = params
+        .journal_lines
+        .unwrap_or(DEFAULT_JOURNAL_LINES)
+        .min(MAX_JOURNAL_LINES);
+    let (journal, journal_error) = if lines == 0 {
+        (Vec::new(), None)
+    } else {
+        let command = format!(
+            "journalctl -u {} -n {} --no-pager -o short-iso",
+            shell_quote(&params.unit),
+            lines
+        );
+        let output = exec_command(&params.server_id, &command).await?;
+        parse_journal(&output.stdout, &output.stderr, output.exit_code)
+    };
+
+    Ok(GetUnitStatusResult {
+        status,
+        journal,
+        journal_error,
+    })
+}
+
+/// 启动、停止、重启、重载、启用或禁用 systemd 单元
+///
+/// 以 root 权限执行：当前用户是 root 时直接执行，否则通过 sudo 执行。
+/// sudo 需要密码而未提供（或密码错误）时返回 `password_required = true`，前端可提示输入密码后重试。
+///
+/// # 命令名称
+/// `manage_systemd_unit`
+///
+/// # 参数
+/// - `server_id`: 服务器ID
+/// - `unit`: 单元名称
+/// - `action`: 操作类型
+/// - `sudo_password`: sudo 密码（可选）
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `unit` / `action`: 单元名称和操作
+/// - `password_required`: 是否需要 sudo 密码
+/// - `status`: 操作后的单元状态（可选）
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn manage_systemd_unit(params: ManageUnitParams) -> Result<ManageUnitResult, String> {
+    if !is_valid_unit_name(&params.unit) {
+        return Err(format!("无效的单元名称: {}", params.unit));
+    }
+    if !UNIT_ACTIONS.contains(&params.action.as_str()) {
+        return Err(format!("不支持的操作: {}", params.action));
+    }
+
+    let command = format!("systemctl {} {}", params.action, shell_quote(&params.unit));
+    let output = exec_privileged_command(&params.server_id, &command, params.sudo_password.as_deref()).await?;
+
+    let success = output.exit_code == 0;
+    let password_required = !success && is_sudo_password_error(&output.stderr);
+    let message = if success {
+        None
+    } else if password_required {
+        Some("需要 sudo 密码或密码错误".to_string())
+    } else {
+        Some(systemctl_error(&output.stderr))
+    };
+
+    // 操作失败时仍返回单元当前状态，便于前端展示
+    let status = query_unit_status(&params.server_id, &params.unit).await.ok();
+
+    Ok(ManageUnitResult {
+        success,
+        unit: params.unit,
+        action: params.action,
+        password_required,
+        status,
+        message,
+    })
+}
+
+/// 查询单元详情
+async fn query_unit_status(server_id: &str, unit: &str) -> Result<UnitStatus, String> {
+    let command = format!(
+        "systemctl show {} --no-pager -p {}",
+        shell_quote(unit),
+        UNIT_PROPERTIES
+    );
+    let output = exec_command(server_id, &command).await?;
+    if output.exit_code != 0 {
+        return Err(systemctl_error(&output.stderr));
+    }
+
+    Ok(parse_unit_show(unit, &output.stdout))
+}
+
+/// 单元名称只允许字母、数字和 systemd 单元名中的合法符号
+fn is_valid_unit_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '-' | ':' | '\\'))
+}
+
+/// 提取 systemctl 的错误信息
+fn systemctl_error(stderr: &str) -> String {
+    let message = stderr.lines().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+    if message.is_empty() {
+        "systemctl 执行失败，目标系统可能未使用 systemd".to_string()
+    } else if message.contains("not found") && message.contains("systemctl") {
+        "目标系统未安装 systemctl".to_string()
+    } else {
+        message.to_string()
+    }
+}
+
+/// 解析 `systemctl list-units --plain --no-legend` 输出：`UNIT LOAD ACTIVE SUB DESCRIPTION`
+fn parse_list_units(text: &str) -> Vec<UnitInfo> {
+    text.lines()
+        .filter_map(|line| {
+            // 较老的 systemd 在 --plain 下仍可能输出状态圆点
+            let line = line.trim().trim_start_matches(['●', '*']).trim_start();
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let load = fields.next()?.to_string();
+            let active = fields.next()?.to_string();
+            let sub = fields.next()?.to_string();
+            Some(UnitInfo {
+                name,
+                load,
+                active,
+                sub,
+                description: fields.collect::<Vec<_>>().join(" "),
+                unit_file_state: None,
+            })
+        })
+        .collect()
+}
+
+/// 解析 `systemctl list-unit-files --no-legend` 输出：`UNIT STATE [PRESET]`
+fn parse_unit_files(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.to_string(), fields.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// 合并已加载单元和已安装单元文件，未加载的单元文件（模板单元除外）作为未加载单元加入列表
+fn merge_units(mut units: Vec<UnitInfo>, mut files: HashMap<String, String>) -> Vec<UnitInfo> {
+    for unit in units.iter_mut() {
+        unit.unit_file_state = files.remove(&unit.name);
+    }
+
+    units.extend(
+        files
+            .into_iter()
+            .filter(|(name, _)| !name.contains("@."))
+            .map(|(name, state)| UnitInfo {
+                name,
+                load: "not-loaded".to_string(),
+                active: "inactive".to_string(),
+                sub: "dead".to_string(),
+                description: String::new(),
+                unit_file_state: Some(state),
+            }),
+    );
+
+    units.sort_by(|a, b| a.name.cmp(&b.name));
+    units
+}
+
+/// 解析 `systemctl show -p ...` 输出（`Key=Value`）
+fn parse_unit_show(unit: &str, text: &str) -> UnitStatus {
+    let values: HashMap<&str, &str> = text
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .collect();
+    // 空值、"[not set]" 及 u64::MAX（systemd 表示未知）均视为无值
+    let text_value = |key: &str| {
+        values
+            .get(key)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty() && *v != "[not set]" && *v != "n/a")
+            .map(|v| v.to_string())
+    };
+    let number = |key: &str| {
+        text_value(key)
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v != u64::MAX)
+    };
+
+    UnitStatus {
+        name: text_value("Id").unwrap_or_else(|| unit.to_string()),
+        description: text_value("Description").unwrap_or_default(),
+        load: text_value("LoadState").unwrap_or_default(),
+        active: text_value("ActiveState").unwrap_or_default(),
+        sub: text_value("SubState").unwrap_or_default(),
+        unit_file_state: text_value("UnitFileState"),
+        main_pid: number("MainPID").filter(|pid| *pid > 0).map(|pid| pid as u32),
+        active_since: text_value("ActiveEnterTimestamp").or_else(|| text_value("ExecMainStartTimestamp")),
+        memory: number("MemoryCurrent"),
+        tasks: number("TasksCurrent"),
+        restarts: number("NRestarts"),
+        result: text_value("Result"),
+        fragment_path: text_value("FragmentPath"),
+    }
+}
+
+/// 解析 journalctl 输出，返回（日志行，错误信息）
+///
+/// 当前用户无权读取系统日志时 journalctl 只输出提示而没有日志行，此时将提示作为错误信息返回。
+fn parse_journal(stdout: &str, stderr: &str, exit_code: u32) -> (Vec<String>, Option<String>) {
+    let lines: Vec<String> = stdout
+        .lines()
+        .filter(|line| !line.starts_with("-- ") && !line.trim().is_empty())
+        .map(|line| line.to_string())
+        .collect();
+
+    let notice = stderr
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .map(|line| line.to_string());
+    let error = if exit_code != 0 || (lines.is_empty() && notice.is_some()) {
+        Some(notice.unwrap_or_else(|| "读取日志失败".to_string()))
+    } else {
+        None
+    };
+
+    (lines, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_UNITS: &str = "\
+cron.service                 loaded    active   running Regular background program processing daemon
+● nginx.service              loaded    failed   failed  A high performance web server and a reverse proxy server
+* mysql.service              not-found inactive dead    mysql.service
+ssh.service                  loaded    active   running OpenBSD Secure Shell server
+";
+
+    const UNIT_FILES: &str = "\
+cron.service                 enabled         enabled
+getty@.service               enabled         enabled
+nginx.service                enabled         enabled
+rsync.service                disabled        enabled
+ssh.service                  enabled         enabled
+";
+
+    const UNIT_SHOW: &str = "\
+Id=nginx.service
+Description=A high performance web server and a reverse proxy server
+LoadState=loaded
+ActiveState=active
+SubState=running
+UnitFileState=enabled
+MainPID=1234
+ActiveEnterTimestamp=Tue 2023-11-14 10:00:00 UTC
+ExecMainStartTimestamp=Tue 2023-11-14 09:59:59 UTC
+MemoryCurrent=18446744073709551615
+TasksCurrent=3
+NRestarts=0
+Result=success
+FragmentPath=/lib/systemd/system/nginx.service
+";
+
+    #[test]
+    fn parses_list_units_with_status_markers() {
+        let units = parse_list_units(LIST_UNITS);
+        assert_eq!(units.len(), 4);
+        assert_eq!(units[0].name, "cron.service");
+        assert_eq!(units[0].description, "Regular background program processing daemon");
+
+        let nginx = &units[1];
+        assert_eq!(nginx.name, "nginx.service");
+        assert_eq!((nginx.load.as_str(), nginx.active.as_str(), nginx.sub.as_str()), ("loaded", "failed", "failed"));
+
+        let mysql = &units[2];
+        assert_eq!((mysql.name.as_str(), mysql.load.as_str()), ("mysql.service", "not-found"));
+        assert_eq!(mysql.unit_file_state, None);
+    }
+
+    #[test]
+    fn skips_incomplete_unit_lines() {
+        assert!(parse_list_units("\n●\nbroken.service loaded active\n").is_empty());
+    }
+
+    #[test]
+    fn merges_unit_files_into_loaded_units() {
+        let files = parse_unit_files(UNIT_FILES);
+        assert_eq!(files.get("rsync.service").map(String::as_str), Some("disabled"));
+
+        let units = merge_units(parse_list_units(LIST_UNITS), files);
+        let names: Vec<&str> = units.iter().map(|u| u.name.as_str()).collect();
+        // 模板单元不加入列表，结果按名称排序
+        assert_eq!(names, vec!["cron.service", "mysql.service", "nginx.service", "rsync.service", "ssh.service"]);
+
+        assert_eq!(units[0].unit_file_state.as_deref(), Some("enabled"));
+        assert_eq!(units[1].unit_file_state, None);
+        let rsync = &units[3];
+        assert_eq!((rsync.load.as_str(), rsync.active.as_str(), rsync.sub.as_str()), ("not-loaded", "inactive", "dead"));
+        assert_eq!(rsync.unit_file_state.as_deref(), Some("disabled"));
+    }
+
+    #[test]
+    fn parses_unit_show_properties() {
+        let status = parse_unit_show("nginx", UNIT_SHOW);
+        assert_eq!(status.name, "nginx.service");
+        assert_eq!((status.active.as_str(), status.sub.as_str()), ("active", "running"));
+        assert_eq!(status.unit_file_state.as_deref(), Some("enabled"));
+        assert_eq!(status.main_pid, Some(1234));
+        assert_eq!(status.active_since.as_deref(), Some("Tue 2023-11-14 10:00:00 UTC"));
+        // u64::MAX 表示未知
+        assert_eq!(status.memory, None);
+        assert_eq!(status.tasks, Some(3));
+        assert_eq!(status.restarts, Some(0));
+        assert_eq!(status.result.as_deref(), Some("success"));
+        assert_eq!(status.fragment_path.as_deref(), Some("/lib/systemd/system/nginx.service"));
+    }
+
+    #[test]
+    fn treats_unset_show_values_as_missing() {
+        let status = parse_unit_show(
+            "gone.service",
+            "Id=\nLoadState=not-found\nActiveState=inactive\nMainPID=0\nActiveEnterTimestamp=n/a\n\
+             ExecMainStartTimestamp=[not set]\nMemoryCurrent=[not set]\nTasksCurrent=\n",
+        );
+        assert_eq!(status.name, "gone.service");
+        assert_eq!(status.load, "not-found");
+        assert_eq!(status.main_pid, None);
+        assert_eq!(status.active_since, None);
+        assert_eq!((status.memory, status.tasks), (None, None));
+        assert_eq!(status.unit_file_state, None);
+    }
+
+    #[test]
+    fn parses_journal_lines_and_permission_notice() {
+        let (lines, error) = parse_journal(
+            "-- Logs begin at Mon 2023-11-13 08:00:00 UTC. --\nNov 14 10:00:00 web nginx[1234]: started\n\n",
+            "",
+            0,
+        );
+        assert_eq!(lines, vec!["Nov 14 10:00:00 web nginx[1234]: started"]);
+        assert_eq!(error, None);
+
+        let notice = "Hint: You are currently not seeing messages from other users and the system.";
+        let (lines, error) = parse_journal("-- No entries --\n", &format!("\n{}\n", notice), 0);
+        assert!(lines.is_empty());
+        assert_eq!(error.as_deref(), Some(notice));
+
+        let (_, error) = parse_journal("", "", 1);
+        assert_eq!(error.as_deref(), Some("读取日志失败"));
+    }
+}
@@ -0,0 +1,309 @@
+/**
+ * 传输带宽限制相关命令处理
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// 令牌桶限速器
+///
+/// 不依赖系统时钟，所有时间点由调用方传入，便于用固定时间序列验证限速行为。
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: u64,      // 每秒字节数，0 表示不限速
+    tokens: f64,    // 当前可用字节数（可以为负，表示需要偿还的额度）
+    last: Instant,  // 上次补充令牌的时间
+}
+
+impl TokenBucket {
+    /// 创建限速器，初始令牌为满桶（允许 1 秒的突发量）
+    pub fn new(rate: u64, now: Instant) -> Self {
+        TokenBucket {
+            rate,
+            tokens: rate as f64,
+            last: now,
+        }
+    }
+
+    /// 当前速率（字节/秒）
+    pub fn rate(&self) -> u64 {
+        self.rate
+    }
+
+    /// 调整速率，正在进行的传输从下一块数据开始按新速率计算
+    pub fn set_rate(&mut self, rate: u64, now: Instant) {
+        if self.rate == 0 {
+            // 从不限速切换为限速时从满桶开始
+            self.tokens = rate as f64;
+        } else {
+            self.refill(now);
+            self.tokens = self.tokens.min(rate as f64);
+        }
+        self.rate = rate;
+        self.last = now;
+    }
+
+    /// 申请发送 `bytes` 字节，返回发送前需要等待的时间
+    pub fn reserve(&mut self, bytes: u64, now: Instant) -> Duration {
+        if self.rate == 0 {
+            return Duration::ZERO;
+        }
+
+        self.refill(now);
+        self.tokens -= bytes as f64;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate as f64)
+        }
+    }
+
+    /// 按经过的时间补充令牌，最多补满 1 秒的额度
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.rate as f64);
+        self.last = now;
+    }
+}
+
+/// 单个传输的限速器集合（transfer_id -> 限速器）
+type TransferBuckets = Arc<Mutex<HashMap<String, Arc<Mutex<TokenBucket>>>>>;
+
+lazy_static::lazy_static! {
+    static ref GLOBAL_BUCKET: Arc<Mutex<TokenBucket>> = Arc::new(Mutex::new(TokenBucket::new(0, Instant::now())));
+    static ref TRANSFER_BUCKETS: TransferBuckets = Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// 传输限速句柄
+///
+/// 同时受全局限速和（可选的）单个传输限速约束，句柄释放时自动注销单个传输的限速器。
+pub struct TransferThrottle {
+    transfer_id: Option<String>,
+    bucket: Option<Arc<Mutex<TokenBucket>>>,
+}
+
+impl TransferThrottle {
+    /// 仅受全局限速约束
+    pub fn global() -> Self {
+        TransferThrottle {
+            transfer_id: None,
+            bucket: None,
+        }
+    }
+
+    /// 注册单个传输的限速器，传输过程中可通过 `set_transfer_bandwidth_limit` 调整
+    pub fn for_transfer(transfer_id: &str, bytes_per_sec: Option<u64>) -> Self {
+        let bucket = Arc::new(Mutex::new(TokenBucket::new(bytes_per_sec.unwrap_or(0), Instant::now())));
+        TRANSFER_BUCKETS
+            .lock()
+            .unwrap()
+            .insert(transfer_id.to_string(), bucket.clone());
+
+        TransferThrottle {
+            transfer_id: Some(transfer_id.to_string()),
+            bucket: Some(bucket),
+        }
+    }
+
+    /// 申请发送 `bytes` 字节，必要时等待
+    pub async fn consume(&self, bytes: u64) {
+        let now = Instant::now();
+        let global_wait = GLOBAL_BUCKET.lock().unwrap().reserve(bytes, now);
+        let transfer_wait = match &self.bucket {
+            Some(bucket) => bucket.lock().unwrap().reserve(bytes, now),
+            None => Duration::ZERO,
+        };
+
+        let wait = global_wait.max(transfer_wait);
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+}
+
+impl Drop for TransferThrottle {
+    fn drop(&mut self) {
+        if let Some(transfer_id) = &self.transfer_id {
+            let mut buckets = TRANSFER_BUCKETS.lock().unwrap();
+            // 只注销自己注册的限速器，避免误删同 ID 的新传输
+            let is_own = match (buckets.get(transfer_id), &self.bucket) {
+                (Some(current), Some(own)) => Arc::ptr_eq(current, own),
+                _ => false,
+            };
+            if is_own {
+                buckets.remove(transfer_id);
+            }
+        }
+    }
+}
+
+/// 设置全局带宽限制参数
+#[derive(Debug, Deserialize)]
+pub struct SetGlobalBandwidthLimitParams {
+    pub bytes_per_sec: u64, // 0 表示不限速
+}
+
+/// 设置单个传输带宽限制参数
+#[derive(Debug, Deserialize)]
+pub struct SetTransferBandwidthLimitParams {
+    pub transfer_id: String,
+    pub bytes_per_sec: u64, // 0 表示不限速
+}
+
+/// 设置带宽限制返回
+#[derive(Debug, Serialize)]
+pub struct SetBandwidthLimitResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+/// 获取带宽限制返回
+#[derive(Debug, Serialize)]
+pub struct GetBandwidthLimitsResult {
+    pub global: u64,                    // 全局限速（字节/秒，0 表示不限速）
+    pub transfers: HashMap<String, u64>, // 正在进行的传输的限速
+}
+
+/// 设置全局带宽限制
+///
+/// # 命令名称
+/// `set_global_bandwidth_limit`
+///
+/// # 参数
+/// - `bytes_per_sec`: 每秒字节数，0 表示不限速
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn set_global_bandwidth_limit(params: SetGlobalBandwidthLimitParams) -> Result<SetBandwidthLimitResult, String> {
+    GLOBAL_BUCKET
+        .lock()
+        .unwrap()
+        .set_rate(params.bytes_per_sec, Instant::now());
+
+    Ok(SetBandwidthLimitResult {
+        success: true,
+        message: Some("设置成功".to_string()),
+    })
+}
+
+/// 设置单个传输的带宽限制（传输进行中也可调整）
+///
+/// # 命令名称
+/// `set_transfer_bandwidth_limit`
+///
+/// # 参数
+/// - `transfer_id`: 传输ID
+/// - `bytes_per_sec`: 每秒字节数，0 表示不限速
+///
+/// # 返回
+/// - `success`: 是否成功
+/// - `message`: 消息（可选）
+#[tauri::command]
+pub async fn set_transfer_bandwidth_limit(params: SetTransferBandwidthLimitParams) -> Result<SetBandwidthLimitResult, String> {
+    let bucket = TRANSFER_BUCKETS.lock().unwrap().get(&params.transfer_id).cloned();
+
+    match bucket {
+        Some(bucket) => {
+            bucket.lock().unwrap().set_rate(params.bytes_per_sec, Instant::now());
+            Ok(SetBandwidthLimitResult {
+                success: true,
+                message: Some("设置成功".to_string()),
+            })
+        }
+        None => Err("传输不存在或已结束".to_string()),
+    }
+}
+
+/// 获取当前带宽限制
+///
+/// # 命令名称
+/// `get_bandwidth_limits`
+///
+/// # 返回
+/// - `global`: 全局限速
+/// - `transfers`: 正在进行的传输的限速
+#[tauri::command]
+pub async fn get_bandwidth_limits() -> Result<GetBandwidthLimitsResult, String> {
+    let global = GLOBAL_BUCKET.lock().unwrap().rate();
+    let transfers = TRANSFER_BUCKETS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(id, bucket)| (id.clone(), bucket.lock().unwrap().rate()))
+        .collect();
+
+    Ok(GetBandwidthLimitsResult { global, transfers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn full_bucket_allows_one_second_burst() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000, start);
+
+        assert_eq!(bucket.reserve(600, start), Duration::ZERO);
+        assert_eq!(bucket.reserve(400, start), Duration::ZERO);
+        // 桶已空，再发送 500 字节需要等待 0.5 秒
+        assert_eq!(bucket.reserve(500, start), 500 * MS);
+    }
+
+    #[test]
+    fn refills_over_injected_time() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000, start);
+        bucket.reserve(1000, start);
+
+        // 0.25 秒补充 250 字节
+        assert_eq!(bucket.reserve(250, start + 250 * MS), Duration::ZERO);
+        assert_eq!(bucket.reserve(100, start + 250 * MS), 100 * MS);
+
+        // 长时间空闲后最多补满 1 秒的额度
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(1000 - 100, later), Duration::ZERO);
+        assert_eq!(bucket.reserve(200, later), 100 * MS);
+    }
+
+    #[test]
+    fn zero_rate_is_unlimited() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(0, start);
+        assert_eq!(bucket.reserve(u32::MAX as u64, start), Duration::ZERO);
+        assert_eq!(bucket.reserve(u32::MAX as u64, start), Duration::ZERO);
+    }
+
+    #[test]
+    fn switching_between_limited_and_unlimited() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(0, start);
+        bucket.reserve(1_000_000, start);
+
+        // 从不限速切换为限速时从满桶开始，不受之前的发送量影响
+        bucket.set_rate(1000, start);
+        assert_eq!(bucket.reserve(1000, start), Duration::ZERO);
+        assert_eq!(bucket.reserve(1000, start), Duration::from_secs(1));
+
+        bucket.set_rate(0, start);
+        assert_eq!(bucket.rate(), 0);
+        assert_eq!(bucket.reserve(1_000_000, start), Duration::ZERO);
+    }
+
+    #[test]
+    fn lowering_rate_caps_accumulated_tokens() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000, start);
+        bucket.set_rate(100, start);
+        assert_eq!(bucket.reserve(100, start), Duration::ZERO);
+        assert_eq!(bucket.reserve(50, start), 500 * MS);
+    }
+}
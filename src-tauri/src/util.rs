@@ -0,0 +1,137 @@
+/**
+ * 各模块共用的辅助函数
+ */
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::oneshot;
+
+/// 当前 Unix 时间戳（秒）
+pub(crate) fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// 按 `==名称==` 分隔行拆分远程命令输出，返回 名称 -> 该部分的原始文本
+pub(crate) fn split_sections(output: &str) -> HashMap<&str, &str> {
+    let mut sections = HashMap::new();
+    let mut current: Option<(&str, usize)> = None;
+    let mut offset = 0;
+
+    for line in output.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed.len() > 4 && trimmed.starts_with("==") && trimmed.ends_with("==") {
+            if let Some((name, start)) = current {
+                sections.insert(name, &output[start..offset]);
+            }
+            current = Some((&trimmed[2..trimmed.len() - 2], offset + line.len()));
+        }
+        offset += line.len();
+    }
+    if let Some((name, start)) = current {
+        sections.insert(name, &output[start..]);
+    }
+
+    sections
+}
+
+/// 可停止的后台任务登记表（任务ID -> (任务代号, 停止信号)）
+///
+/// 任务ID 由前端提供。任务代号用于在任务结束时确认登记项仍属于自己：
+/// 停止后立即以相同 ID 重新启动的任务会占用同一个键，旧任务结束时不能把它移除。
+pub(crate) struct TaskRegistry {
+    tasks: Mutex<HashMap<String, (u64, oneshot::Sender<()>)>>,
+    next_generation: AtomicU64,
+}
+
+impl TaskRegistry {
+    pub(crate) fn new() -> Self {
+        TaskRegistry {
+            tasks: Mutex::new(HashMap::new()),
+            next_generation: AtomicU64::new(0),
+        }
+    }
+
+    /// 登记任务，返回任务代号和停止信号接收端；ID 已被占用时返回 `None`
+    pub(crate) fn register(&self, id: &str) -> Option<(u64, oneshot::Receiver<()>)> {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.contains_key(id) {
+            return None;
+        }
+        let generation = self.next_generation.fetch_add(1, Ordering::SeqCst);
+        let (stop_tx, stop_rx) = oneshot::channel();
+        tasks.insert(id.to_string(), (generation, stop_tx));
+        Some((generation, stop_rx))
+    }
+
+    /// 任务结束时移除登记，仅当登记项仍属于该代号的任务时才移除
+    pub(crate) fn finish(&self, id: &str, generation: u64) {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.get(id).map(|(g, _)| *g) == Some(generation) {
+            tasks.remove(id);
+        }
+    }
+
+    /// 移除登记并发送停止信号，返回任务是否仍在运行
+    pub(crate) fn stop(&self, id: &str) -> bool {
+        match self.tasks.lock().unwrap().remove(id) {
+            Some((_, stop_tx)) => {
+                let _ = stop_tx.send(());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_sections_by_marker_lines() {
+        let sections = split_sections("ignored\n==os==\nLinux\n==stat==\ncpu 1 2 3 4\r\n==empty==\n");
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections["os"], "Linux\n");
+        assert_eq!(sections["stat"], "cpu 1 2 3 4\r\n");
+        assert_eq!(sections["empty"], "");
+    }
+
+    #[test]
+    fn last_section_runs_to_end_without_newline() {
+        let sections = split_sections("==a==\n1\n==b==\n2");
+        assert_eq!(sections["a"], "1\n");
+        assert_eq!(sections["b"], "2");
+    }
+
+    #[test]
+    fn registry_rejects_duplicate_ids_and_stops_once() {
+        let registry = TaskRegistry::new();
+        let (_, mut stop_rx) = registry.register("a").unwrap();
+        assert!(registry.register("a").is_none());
+
+        assert!(registry.stop("a"));
+        assert!(stop_rx.try_recv().is_ok());
+        assert!(!registry.stop("a"));
+    }
+
+    #[test]
+    fn registry_finish_keeps_restarted_task() {
+        let registry = TaskRegistry::new();
+        let (old, _old_rx) = registry.register("a").unwrap();
+        assert!(registry.stop("a"));
+
+        // 旧任务尚未结束时以相同 ID 重新启动
+        let (new, mut new_rx) = registry.register("a").unwrap();
+        registry.finish("a", old);
+        assert!(registry.stop("a"));
+        assert!(new_rx.try_recv().is_ok());
+
+        registry.finish("a", new);
+        assert!(registry.register("a").is_some());
+    }
+}
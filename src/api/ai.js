@@ -3,6 +3,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
 
 /**
  * 与 AI 对话
@@ -24,7 +25,95 @@ export async function chatWithAi(params) {
     return result
   } catch (error) {
     console.error('AI对话失败:', error)
-    throw new Error(error.message || 'AI对话失败')
+    // Tauri 错误可能是字符串或对象
+    let errorMessage = 'AI对话失败'
+    if (typeof error === 'string') {
+      errorMessage = error
+    } else if (error?.message) {
+      errorMessage = error.message
+    }
+    throw new Error(errorMessage)
+  }
+}
+
+/**
+ * 与 AI 对话（流式返回，回复片段通过 onToken 回调逐段传入）
+ * @param {Object} params - 对话参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.requestId - 请求ID（由调用方生成，用于取消）
+ * @param {string} params.question - 用户问题
+ * @param {Array<{role: 'user'|'assistant', content: string, timestamp?: number}>} [params.history] - 对话历史（可选）
+ * @param {(delta: string) => void} [onToken] - 收到回复片段时的回调
+ * @returns {Promise<{request_id: string, content: string, timestamp: number, cancelled: boolean}>}
+ *   失败时抛出的 Error 附带 kind（'config'|'connect'|'timeout'|'auth'|'rate_limit'|'http'|'invalid_response'|'stream'）、
+ *   status、retryable 和已收到的部分回复 content
+ */
+export async function streamChatWithAi(params, onToken) {
+  const requestId = params.requestId
+  let resolveDone
+  let rejectDone
+  const done = new Promise((resolve, reject) => {
+    resolveDone = resolve
+    rejectDone = reject
+  })
+
+  // 先注册事件监听再启动对话，避免错过最早的事件
+  const unlisteners = await Promise.all([
+    listen('ai-chat-token', event => {
+      if (event.payload.request_id === requestId) {
+        onToken?.(event.payload.delta)
+      }
+    }),
+    listen('ai-chat-finished', event => {
+      if (event.payload.request_id === requestId) {
+        resolveDone(event.payload)
+      }
+    }),
+    listen('ai-chat-error', event => {
+      if (event.payload.request_id === requestId) {
+        const { message, kind, status, retryable, content } = event.payload
+        rejectDone(Object.assign(new Error(message), { kind, status, retryable, content }))
+      }
+    })
+  ])
+
+  try {
+    await invoke('start_ai_chat', {
+      params: {
+        server_id: params.serverId,
+        request_id: requestId,
+        question: params.question,
+        history: params.history || []
+      }
+    })
+    return await done
+  } catch (error) {
+    console.error('AI对话失败:', error)
+    if (error instanceof Error) {
+      throw error
+    }
+    throw new Error(typeof error === 'string' ? error : 'AI对话失败')
+  } finally {
+    unlisteners.forEach(unlisten => unlisten())
+  }
+}
+
+/**
+ * 停止正在进行的流式对话（已收到的部分回复仍会返回）
+ * @param {string} requestId - 请求ID
+ * @returns {Promise<{success: boolean, message?: string}>}
+ */
+export async function cancelAiChat(requestId) {
+  try {
+    const result = await invoke('cancel_ai_chat', {
+      params: {
+        request_id: requestId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('停止AI对话失败:', error)
+    throw new Error(error.message || '停止AI对话失败')
   }
 }
 
@@ -47,3 +136,56 @@ export async function getAiQuickActions(serverId) {
   }
 }
 
+
+/**
+ * 获取 AI 服务配置（不返回 API Key 明文）
+ * @returns {Promise<{configured: boolean, provider: 'openai'|'anthropic'|'ollama', base_url: string, model: string, has_api_key: boolean, timeout_secs: number, connect_timeout_secs: number, max_tokens: number}>}
+ */
+export async function getAiConfig() {
+  try {
+    const result = await invoke('get_ai_config')
+    return result
+  } catch (error) {
+    console.error('获取AI服务配置失败:', error)
+    throw new Error(error.message || '获取AI服务配置失败')
+  }
+}
+
+/**
+ * 保存 AI 服务配置
+ * @param {Object} config - 服务配置
+ * @param {'openai'|'anthropic'|'ollama'} config.provider - 服务提供方（openai 表示 OpenAI 兼容接口）
+ * @param {string} [config.baseUrl] - 服务地址（可选，默认使用提供方的官方地址）
+ * @param {string} config.model - 模型名称
+ * @param {string} [config.apiKey] - API Key（可选，不传则保留原值，空字符串表示清除）
+ * @param {number} [config.timeoutSecs] - 响应超时时间（秒，等待响应和两次收到数据之间的最长间隔，默认 120）
+ * @param {number} [config.connectTimeoutSecs] - 连接超时时间（秒，默认 10）
+ * @param {number} [config.maxTokens] - 最大回复 token 数（默认 4096）
+ * @returns {Promise<Object>} 保存后的配置，格式同 getAiConfig
+ */
+export async function setAiConfig(config) {
+  try {
+    const result = await invoke('set_ai_config', {
+      params: {
+        provider: config.provider,
+        base_url: config.baseUrl,
+        model: config.model,
+        api_key: config.apiKey,
+        timeout_secs: config.timeoutSecs,
+        connect_timeout_secs: config.connectTimeoutSecs,
+        max_tokens: config.maxTokens
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('保存AI服务配置失败:', error)
+    // Tauri 错误可能是字符串或对象
+    let errorMessage = '保存AI服务配置失败'
+    if (typeof error === 'string') {
+      errorMessage = error
+    } else if (error?.message) {
+      errorMessage = error.message
+    }
+    throw new Error(errorMessage)
+  }
+}
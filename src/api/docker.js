@@ -0,0 +1,160 @@
+/**
+ * Docker 容器管理相关 API
+ */
+
+import { invoke } from '@tauri-apps/api/tauri'
+
+/**
+ * 列出容器
+ * @param {string} serverId - 服务器ID
+ * @param {boolean} [all=true] - 是否包含已停止的容器
+ * @returns {Promise<{containers: Array<{id: string, name: string, image: string, command: string, created_at: string, running_for: string, state: string, status: string, ports: string, networks: string[]}>}>}
+ */
+export async function listContainers(serverId, all = true) {
+  try {
+    const result = await invoke('list_containers', {
+      params: {
+        server_id: serverId,
+        all
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取容器列表失败:', error)
+    throw new Error(error.message || '获取容器列表失败')
+  }
+}
+
+/**
+ * 获取容器详情
+ * @param {string} serverId - 服务器ID
+ * @param {string} container - 容器 ID 或名称
+ * @returns {Promise<{details: Object}>} `docker inspect` 返回的容器详情
+ */
+export async function inspectContainer(serverId, container) {
+  try {
+    const result = await invoke('inspect_container', {
+      params: {
+        server_id: serverId,
+        container
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取容器详情失败:', error)
+    throw new Error(error.message || '获取容器详情失败')
+  }
+}
+
+/**
+ * 列出镜像
+ * @param {string} serverId - 服务器ID
+ * @returns {Promise<{images: Array<{id: string, repository: string, tag: string, created_at: string, created_since: string, size: number}>}>}
+ */
+export async function listImages(serverId) {
+  try {
+    const result = await invoke('list_images', {
+      params: {
+        server_id: serverId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取镜像列表失败:', error)
+    throw new Error(error.message || '获取镜像列表失败')
+  }
+}
+
+/**
+ * 启动、停止、重启、暂停、恢复或删除容器
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.container - 容器 ID 或名称
+ * @param {'start'|'stop'|'restart'|'pause'|'unpause'|'remove'} params.action - 操作类型
+ * @param {boolean} [params.force] - 删除时是否强制删除运行中的容器（可选）
+ * @returns {Promise<{success: boolean, container: string, action: string, message: string|null}>}
+ */
+export async function containerAction(params) {
+  try {
+    const result = await invoke('container_action', {
+      params: {
+        server_id: params.serverId,
+        container: params.container,
+        action: params.action,
+        force: params.force ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('容器操作失败:', error)
+    throw new Error(error.message || '容器操作失败')
+  }
+}
+
+/**
+ * 获取运行中容器的资源占用
+ * @param {string} serverId - 服务器ID
+ * @returns {Promise<{stats: Array<{id: string, name: string, cpu: number, memory_used: number, memory_limit: number, memory: number, net_rx: number, net_tx: number, block_read: number, block_write: number, pids: number}>}>}
+ */
+export async function getContainerStats(serverId) {
+  try {
+    const result = await invoke('get_container_stats', {
+      params: {
+        server_id: serverId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取容器资源占用失败:', error)
+    throw new Error(error.message || '获取容器资源占用失败')
+  }
+}
+
+/**
+ * 开始读取容器日志
+ *
+ * 新日志通过 `docker-log-lines` 事件推送（{logs_id, stream, lines}），结束时推送 `docker-log-stopped` 事件（{logs_id, stopped}）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.logsId - 日志任务ID（用于匹配事件）
+ * @param {string} params.container - 容器 ID 或名称
+ * @param {number} [params.tail] - 初始读取的末尾行数（可选，默认 200）
+ * @param {boolean} [params.timestamps] - 是否附带时间戳（可选）
+ * @returns {Promise<{success: boolean, logs_id: string}>}
+ */
+export async function startContainerLogs(params) {
+  try {
+    const result = await invoke('start_container_logs', {
+      params: {
+        server_id: params.serverId,
+        logs_id: params.logsId,
+        container: params.container,
+        tail: params.tail ?? null,
+        timestamps: params.timestamps ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('读取容器日志失败:', error)
+    throw new Error(error.message || '读取容器日志失败')
+  }
+}
+
+/**
+ * 停止读取容器日志
+ * @param {string} logsId - 日志任务ID
+ * @returns {Promise<{success: boolean, message: string|null}>}
+ */
+export async function stopContainerLogs(logsId) {
+  try {
+    const result = await invoke('stop_container_logs', {
+      params: {
+        logs_id: logsId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('停止读取容器日志失败:', error)
+    throw new Error(error.message || '停止读取容器日志失败')
+  }
+}
@@ -32,6 +32,9 @@ export async function listRemoteDirectory(params) {
  * @param {string} params.serverId - 服务器ID
  * @param {string} params.localPath - 本地文件路径
  * @param {string} params.remotePath - 远程保存路径（目录）
+ * @param {boolean} [params.verify] - 传输完成后是否校验 SHA-256
+ * @param {string} [params.transferId] - 传输ID（传输过程中可通过 setTransferBandwidthLimit 调整限速）
+ * @param {number} [params.bandwidthLimit] - 限速（字节/秒）
  * @returns {Promise<{success: boolean}>}
  */
 export async function uploadFile(params) {
@@ -40,13 +43,19 @@ export async function uploadFile(params) {
       params: {
         server_id: params.serverId,
         local_path: params.localPath,
-        remote_path: params.remotePath
+        remote_path: params.remotePath,
+        verify: params.verify ?? null,
+        transfer_id: params.transferId || null,
+        bandwidth_limit: params.bandwidthLimit ?? null
       }
     })
     return result
   } catch (error) {
     console.error('上传文件失败:', error)
-    throw new Error(error.message || '上传文件失败')
+    const err = new Error(error.message || '上传文件失败')
+    // 自动重传后仍校验失败时 kind 为 checksum_mismatch
+    err.kind = error.kind
+    throw err
   }
 }
 
@@ -94,6 +103,9 @@ export async function uploadFiles(params) {
  * @param {string} params.serverId - 服务器ID
  * @param {string} params.remotePath - 远程文件路径
  * @param {string} params.localPath - 本地保存路径（完整路径，包含文件名）
+ * @param {boolean} [params.verify] - 传输完成后是否校验 SHA-256
+ * @param {string} [params.transferId] - 传输ID（传输过程中可通过 setTransferBandwidthLimit 调整限速）
+ * @param {number} [params.bandwidthLimit] - 限速（字节/秒）
  * @returns {Promise<{success: boolean}>}
  */
 export async function downloadFile(params) {
@@ -102,13 +114,19 @@ export async function downloadFile(params) {
       params: {
         server_id: params.serverId,
         remote_path: params.remotePath,
-        local_path: params.localPath
+        local_path: params.localPath,
+        verify: params.verify ?? null,
+        transfer_id: params.transferId || null,
+        bandwidth_limit: params.bandwidthLimit ?? null
       }
     })
     return result
   } catch (error) {
     console.error('下载文件失败:', error)
-    throw new Error(error.message || '下载文件失败')
+    const err = new Error(error.message || '下载文件失败')
+    // 自动重传后仍校验失败时 kind 为 checksum_mismatch
+    err.kind = error.kind
+    throw err
   }
 }
 
@@ -204,3 +222,408 @@ export async function changeFileMode(params) {
   }
 }
 
+
+/**
+ * 搜索远程文件（结果通过 file-search-result 事件推送，结束时推送 file-search-finished 事件）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.searchId - 搜索ID（用于匹配事件）
+ * @param {string} params.path - 搜索起始目录
+ * @param {string} [params.namePattern] - 文件名通配符，如 "*.conf"
+ * @param {boolean} [params.caseSensitive] - 文件名是否区分大小写
+ * @param {string} [params.contentPattern] - 文件内容正则
+ * @param {'file'|'directory'} [params.fileType] - 文件类型
+ * @param {number} [params.minSize] - 最小文件大小（字节）
+ * @param {number} [params.maxSize] - 最大文件大小（字节）
+ * @param {number} [params.modifiedWithin] - 最近 N 分钟内修改过
+ * @param {number} [params.modifiedBefore] - N 分钟之前修改过
+ * @param {number} [params.maxDepth] - 最大搜索深度
+ * @param {number} [params.maxResults] - 最大结果数
+ * @returns {Promise<{success: boolean, search_id: string}>}
+ */
+export async function searchRemoteFiles(params) {
+  try {
+    const result = await invoke('search_remote_files', {
+      params: {
+        server_id: params.serverId,
+        search_id: params.searchId,
+        path: params.path,
+        name_pattern: params.namePattern || null,
+        case_sensitive: params.caseSensitive ?? null,
+        content_pattern: params.contentPattern || null,
+        file_type: params.fileType || null,
+        min_size: params.minSize ?? null,
+        max_size: params.maxSize ?? null,
+        modified_within: params.modifiedWithin ?? null,
+        modified_before: params.modifiedBefore ?? null,
+        max_depth: params.maxDepth ?? null,
+        max_results: params.maxResults ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('搜索远程文件失败:', error)
+    throw new Error(error.message || '搜索远程文件失败')
+  }
+}
+
+/**
+ * 取消远程文件搜索
+ * @param {Object} params - 参数
+ * @param {string} params.searchId - 搜索ID
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function cancelFileSearch(params) {
+  try {
+    const result = await invoke('cancel_file_search', {
+      params: {
+        search_id: params.searchId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('取消搜索失败:', error)
+    throw new Error(error.message || '取消搜索失败')
+  }
+}
+
+/**
+ * 获取远程服务器可用的压缩工具
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @returns {Promise<{tar: boolean, gzip: boolean, zip: boolean, unzip: boolean, python3: boolean}>}
+ */
+export async function getArchiveTools(params) {
+  try {
+    const result = await invoke('get_archive_tools', {
+      params: {
+        server_id: params.serverId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取压缩工具失败:', error)
+    throw new Error(error.message || '获取压缩工具失败')
+  }
+}
+
+/**
+ * 在远程服务器上创建压缩包（进度通过 archive-progress 事件推送）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.taskId - 任务ID（用于匹配进度事件）
+ * @param {string[]} params.sourcePaths - 要压缩的文件/目录（绝对路径）
+ * @param {string} params.archivePath - 压缩包保存路径（绝对路径）
+ * @param {'tar.gz'|'zip'} [params.format] - 压缩格式，默认根据扩展名推断
+ * @returns {Promise<{success: boolean, archive_path: string}>}
+ */
+export async function createRemoteArchive(params) {
+  try {
+    const result = await invoke('create_remote_archive', {
+      params: {
+        server_id: params.serverId,
+        task_id: params.taskId,
+        source_paths: params.sourcePaths,
+        archive_path: params.archivePath,
+        format: params.format || null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('创建压缩包失败:', error)
+    throw new Error(error.message || '创建压缩包失败')
+  }
+}
+
+/**
+ * 在远程服务器上解压压缩包（进度通过 archive-progress 事件推送）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.taskId - 任务ID（用于匹配进度事件）
+ * @param {string} params.archivePath - 压缩包路径
+ * @param {string} params.targetDir - 解压目标目录
+ * @param {'tar.gz'|'zip'} [params.format] - 压缩格式，默认根据扩展名推断
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function extractRemoteArchive(params) {
+  try {
+    const result = await invoke('extract_remote_archive', {
+      params: {
+        server_id: params.serverId,
+        task_id: params.taskId,
+        archive_path: params.archivePath,
+        target_dir: params.targetDir,
+        format: params.format || null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('解压失败:', error)
+    throw new Error(error.message || '解压失败')
+  }
+}
+
+/**
+ * 生成目录同步计划（试运行，不修改任何文件）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.localDir - 本地目录
+ * @param {string} params.remoteDir - 远程目录
+ * @param {'upload'|'download'} params.direction - 同步方向
+ * @param {'size_mtime'|'checksum'} [params.compareMode] - 比较方式
+ * @param {boolean} [params.deleteExtraneous] - 是否删除目标端多余的文件
+ * @returns {Promise<{plan_id: string, actions: Array<{action: string, path: string, is_dir: boolean, size: number, mtime: number, reason: string}>, total_bytes: number}>}
+ */
+export async function planDirectorySync(params) {
+  try {
+    const result = await invoke('plan_directory_sync', {
+      params: {
+        server_id: params.serverId,
+        local_dir: params.localDir,
+        remote_dir: params.remoteDir,
+        direction: params.direction,
+        compare_mode: params.compareMode || null,
+        delete_extraneous: params.deleteExtraneous ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('生成同步计划失败:', error)
+    throw new Error(error.message || '生成同步计划失败')
+  }
+}
+
+/**
+ * 执行已确认的同步计划（进度通过 sync-progress 事件推送）
+ * @param {Object} params - 参数
+ * @param {string} params.planId - 计划ID
+ * @returns {Promise<{success: boolean, applied: number, failed: Array<{action: string, path: string, error: string}>}>}
+ */
+export async function applySyncPlan(params) {
+  try {
+    const result = await invoke('apply_sync_plan', {
+      params: {
+        plan_id: params.planId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('执行同步计划失败:', error)
+    throw new Error(error.message || '执行同步计划失败')
+  }
+}
+
+/**
+ * 丢弃未执行的同步计划
+ * @param {Object} params - 参数
+ * @param {string} params.planId - 计划ID
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function discardSyncPlan(params) {
+  try {
+    const result = await invoke('discard_sync_plan', {
+      params: {
+        plan_id: params.planId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('丢弃同步计划失败:', error)
+    throw new Error(error.message || '丢弃同步计划失败')
+  }
+}
+
+/**
+ * 计算远程文件校验和
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string[]} params.paths - 远程文件路径数组
+ * @param {'sha256'|'sha1'|'md5'} [params.algorithm] - 校验算法，默认 sha256
+ * @returns {Promise<{algorithm: string, checksums: Array<{path: string, checksum: string|null, error: string|null}>}>}
+ */
+export async function computeRemoteChecksums(params) {
+  try {
+    const result = await invoke('compute_remote_checksums', {
+      params: {
+        server_id: params.serverId,
+        paths: params.paths,
+        algorithm: params.algorithm || null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('计算校验和失败:', error)
+    throw new Error(error.message || '计算校验和失败')
+  }
+}
+
+/**
+ * 在两台已连接的服务器之间复制文件或目录（进度通过 server-copy-progress 事件推送）
+ * @param {Object} params - 参数
+ * @param {string} params.taskId - 任务ID（用于匹配进度事件）
+ * @param {string} params.sourceServerId - 源服务器ID
+ * @param {string} params.sourcePath - 源文件或目录
+ * @param {string} params.targetServerId - 目标服务器ID
+ * @param {string} params.targetPath - 目标完整路径
+ * @param {'stream'|'scp'} [params.mode] - 复制模式，默认 stream（经本机中转，不落盘）
+ * @param {string} [params.targetHost] - scp 模式下源服务器访问目标服务器的地址（默认与本机连接时相同）
+ * @param {number} [params.targetPort] - scp 模式下源服务器访问目标服务器的端口（默认与本机连接时相同）
+ * @returns {Promise<{success: boolean, transferred: number}>}
+ */
+export async function copyBetweenServers(params) {
+  try {
+    const result = await invoke('copy_between_servers', {
+      params: {
+        task_id: params.taskId,
+        source_server_id: params.sourceServerId,
+        source_path: params.sourcePath,
+        target_server_id: params.targetServerId,
+        target_path: params.targetPath,
+        mode: params.mode || null,
+        target_host: params.targetHost || null,
+        target_port: params.targetPort ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('服务器间复制失败:', error)
+    throw new Error(error.message || '服务器间复制失败')
+  }
+}
+
+/**
+ * 设置全局传输带宽限制（所有上传、下载、同步和服务器间复制共享）
+ * @param {Object} params - 参数
+ * @param {number} params.bytesPerSec - 每秒字节数，0 表示不限速
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function setGlobalBandwidthLimit(params) {
+  try {
+    const result = await invoke('set_global_bandwidth_limit', {
+      params: {
+        bytes_per_sec: params.bytesPerSec
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('设置全局限速失败:', error)
+    throw new Error(error.message || '设置全局限速失败')
+  }
+}
+
+/**
+ * 设置单个传输的带宽限制（传输进行中也可调整）
+ * @param {Object} params - 参数
+ * @param {string} params.transferId - 传输ID（服务器间复制使用 taskId）
+ * @param {number} params.bytesPerSec - 每秒字节数，0 表示不限速
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function setTransferBandwidthLimit(params) {
+  try {
+    const result = await invoke('set_transfer_bandwidth_limit', {
+      params: {
+        transfer_id: params.transferId,
+        bytes_per_sec: params.bytesPerSec
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('设置传输限速失败:', error)
+    throw new Error(error.message || '设置传输限速失败')
+  }
+}
+
+/**
+ * 获取当前带宽限制
+ * @returns {Promise<{global: number, transfers: Object<string, number>}>}
+ */
+export async function getBandwidthLimits() {
+  try {
+    const result = await invoke('get_bandwidth_limits')
+    return result
+  } catch (error) {
+    console.error('获取限速设置失败:', error)
+    throw new Error(error.message || '获取限速设置失败')
+  }
+}
+
+/**
+ * 开始实时跟踪远程日志文件
+ * 新行通过 log-tail-lines 事件推送，轮转等提示通过 log-tail-notice 事件推送，结束时推送 log-tail-stopped 事件
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.tailId - 跟踪ID（用于匹配事件）
+ * @param {string} params.path - 远程日志文件路径
+ * @param {number} [params.initialLines] - 先输出文件末尾 N 行，默认 200
+ * @param {string} [params.filter] - 服务端过滤正则（扩展正则）
+ * @param {boolean} [params.caseSensitive] - 过滤是否区分大小写
+ * @returns {Promise<{success: boolean, tail_id: string}>}
+ */
+export async function startLogTail(params) {
+  try {
+    const result = await invoke('start_log_tail', {
+      params: {
+        server_id: params.serverId,
+        tail_id: params.tailId,
+        path: params.path,
+        initial_lines: params.initialLines ?? null,
+        filter: params.filter || null,
+        case_sensitive: params.caseSensitive ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('跟踪日志失败:', error)
+    throw new Error(error.message || '跟踪日志失败')
+  }
+}
+
+/**
+ * 停止跟踪远程日志文件
+ * @param {Object} params - 参数
+ * @param {string} params.tailId - 跟踪ID
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function stopLogTail(params) {
+  try {
+    const result = await invoke('stop_log_tail', {
+      params: {
+        tail_id: params.tailId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('停止跟踪日志失败:', error)
+    throw new Error(error.message || '停止跟踪日志失败')
+  }
+}
+
+/**
+ * 分析远程目录的磁盘占用（结果缓存在当前连接上，在已扫描范围内下钻时不会重新扫描）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.path - 要分析的目录
+ * @param {number} [params.maxDepth] - 扫描深度，默认 3
+ * @param {boolean} [params.oneFileSystem] - 是否只统计同一文件系统，默认 true
+ * @param {number} [params.timeoutSecs] - 超时时间（秒），默认 60
+ * @param {boolean} [params.refresh] - 是否忽略缓存重新扫描
+ * @returns {Promise<{tree: {path: string, name: string, size: number, children: Array, truncated: boolean}, cached: boolean, scanned_at: number, partial: boolean}>}
+ */
+export async function analyzeDiskUsage(params) {
+  try {
+    const result = await invoke('analyze_disk_usage', {
+      params: {
+        server_id: params.serverId,
+        path: params.path,
+        max_depth: params.maxDepth ?? null,
+        one_file_system: params.oneFileSystem ?? null,
+        timeout_secs: params.timeoutSecs ?? null,
+        refresh: params.refresh ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('分析磁盘占用失败:', error)
+    throw new Error(error.message || '分析磁盘占用失败')
+  }
+}
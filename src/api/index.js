@@ -9,4 +9,4 @@ export * from './file'
 export * from './monitor'
 export * from './server'
 export * from './ai'
-
+export * from './docker'
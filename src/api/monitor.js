@@ -34,6 +34,30 @@ import { invoke } from '@tauri-apps/api/tauri'
  *     upload: number,
  *     downloadTotal: number,
  *     uploadTotal: number
+ *   },
+ *   interfaces: Array<{
+ *     name: string,
+ *     rx_rate: number,
+ *     tx_rate: number,
+ *     rx_total: number,
+ *     tx_total: number,
+ *     rx_errors: number,
+ *     tx_errors: number,
+ *     rx_dropped: number,
+ *     tx_dropped: number
+ *   }>,
+ *   disk_io: Array<{
+ *     device: string,
+ *     read_iops: number,
+ *     write_iops: number,
+ *     read_rate: number,
+ *     write_rate: number,
+ *     utilization: number
+ *   }>,
+ *   sensors: {
+ *     temperatures: Array<{source: 'thermal'|'hwmon', chip: string, label: string, celsius: number, critical: number|null}>,
+ *     fans: Array<{chip: string, label: string, rpm: number}>,
+ *     power_supplies: Array<{name: string, kind: string, status: string|null, capacity: number|null, online: boolean|null, power: number|null}>
  *   }
  * }>}
  */
@@ -51,3 +75,440 @@ export async function getSystemMonitor(serverId) {
   }
 }
 
+
+/**
+ * 开始推送服务器监控数据
+ * 数据通过 system-monitor-data 事件推送（{ server_id, data }），采样失败时推送 system-monitor-error 事件，
+ * 停止或连接断开后推送 system-monitor-stopped 事件（reason: 'stopped' | 'disconnected'）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {number} [params.intervalMs] - 采样间隔（毫秒），默认 2000，最小 1000
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function startMonitoring(params) {
+  try {
+    const result = await invoke('start_monitoring', {
+      params: {
+        server_id: params.serverId,
+        interval_ms: params.intervalMs ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('开始监控失败:', error)
+    throw new Error(error.message || '开始监控失败')
+  }
+}
+
+/**
+ * 停止推送服务器监控数据
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function stopMonitoring(params) {
+  try {
+    const result = await invoke('stop_monitoring', {
+      params: {
+        server_id: params.serverId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('停止监控失败:', error)
+    throw new Error(error.message || '停止监控失败')
+  }
+}
+
+/**
+ * 并发采样多台服务器，获取精简概览（未连接的服务器标记为 disconnected）
+ * @param {Object} [params] - 参数
+ * @param {string} [params.groupName] - 服务器分组（可选，默认全部服务器）
+ * @param {string[]} [params.serverIds] - 指定服务器（可选）
+ * @param {number} [params.concurrency] - 并发采样数（可选，默认 8）
+ * @param {number} [params.timeoutSecs] - 单台服务器超时时间（可选，默认 10 秒）
+ * @returns {Promise<{servers: Array<{server_id: string, name: string, group_name: string|null, status: 'ok'|'disconnected'|'timeout'|'error', reachable: boolean, cpu: number|null, memory: number|null, disk: number|null, disk_mount: string|null, load1: number|null, cores: number|null, latency_ms: number|null, error: string|null}>, totals: {total: number, reachable: number, unreachable: number, avg_cpu: number|null, max_cpu: number|null, avg_memory: number|null, max_memory: number|null, max_disk: number|null}}>}
+ */
+export async function getFleetSummary(params = {}) {
+  try {
+    const result = await invoke('get_fleet_summary', {
+      params: {
+        group_name: params.groupName || null,
+        server_ids: params.serverIds || null,
+        concurrency: params.concurrency ?? null,
+        timeout_secs: params.timeoutSecs ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取服务器概览失败:', error)
+    throw new Error(error.message || '获取服务器概览失败')
+  }
+}
+
+/**
+ * 获取 Prometheus 指标导出服务状态
+ * @returns {Promise<{enabled: boolean, port: number, running: boolean, url: string|null}>}
+ */
+export async function getMetricsExporter() {
+  try {
+    const result = await invoke('get_metrics_exporter')
+    return result
+  } catch (error) {
+    console.error('获取指标导出状态失败:', error)
+    throw new Error(error.message || '获取指标导出状态失败')
+  }
+}
+
+/**
+ * 启用或停用 Prometheus 指标导出服务（仅监听 127.0.0.1，设置会被保存）
+ * @param {boolean} enabled - 是否启用
+ * @param {number} [port] - 监听端口（可选，默认 9464）
+ * @returns {Promise<{enabled: boolean, port: number, running: boolean, url: string|null}>}
+ */
+export async function setMetricsExporter(enabled, port) {
+  try {
+    const result = await invoke('set_metrics_exporter', {
+      params: {
+        enabled,
+        port: port ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('设置指标导出失败:', error)
+    throw new Error(error.message || '设置指标导出失败')
+  }
+}
+
+/**
+ * 获取服务器的历史监控指标（根据时间跨度自动选择 10 秒 / 1 分钟 / 1 小时粒度）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {number} params.start - 开始时间（Unix 时间戳，秒）
+ * @param {number} [params.end] - 结束时间（Unix 时间戳，秒），默认当前时间
+ * @param {number} [params.maxPoints] - 最大返回点数，默认 500
+ * @returns {Promise<{
+ *   resolution: number,
+ *   timestamps: number[],
+ *   cpu: number[],
+ *   memory: number[],
+ *   load1: number[],
+ *   download: number[],
+ *   upload: number[],
+ *   disk: number[]
+ * }>}
+ */
+export async function getMetricHistory(params) {
+  try {
+    const result = await invoke('get_metric_history', {
+      params: {
+        server_id: params.serverId,
+        start: params.start,
+        end: params.end ?? null,
+        max_points: params.maxPoints ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取历史监控数据失败:', error)
+    throw new Error(error.message || '获取历史监控数据失败')
+  }
+}
+
+/**
+ * 清除服务器的历史监控指标
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function clearMetricHistory(params) {
+  try {
+    const result = await invoke('clear_metric_history', {
+      params: {
+        server_id: params.serverId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('清除历史监控数据失败:', error)
+    throw new Error(error.message || '清除历史监控数据失败')
+  }
+}
+
+/**
+ * 获取所有告警规则
+ * @returns {Promise<{rules: Array<Object>}>}
+ */
+export async function getAlertRules() {
+  try {
+    const result = await invoke('get_alert_rules')
+    return result
+  } catch (error) {
+    console.error('获取告警规则失败:', error)
+    throw new Error(error.message || '获取告警规则失败')
+  }
+}
+
+/**
+ * 保存告警规则（没有 id 时新建）
+ * 例如 "磁盘 / 使用率 > 90% 持续 5 分钟"：{ metric: 'disk', mount: '/', operator: '>', threshold: 90, durationSecs: 300 }
+ * "负载 > 核心数 × 2"：{ metric: 'load_per_core', operator: '>', threshold: 2 }
+ * @param {Object} rule - 告警规则
+ * @param {string} [rule.id] - 规则ID
+ * @param {string} rule.name - 规则名称
+ * @param {string} [rule.serverId] - 只对指定服务器生效
+ * @param {string} [rule.groupName] - 只对指定分组生效（与 serverId 都为空时对所有服务器生效）
+ * @param {'cpu'|'memory'|'disk'|'load'|'load_per_core'|'download'|'upload'} rule.metric - 指标
+ * @param {string} [rule.mount] - 磁盘挂载点（为空表示使用率最高的磁盘）
+ * @param {'>'|'<'} rule.operator - 比较方式
+ * @param {number} rule.threshold - 触发阈值
+ * @param {number} [rule.clearThreshold] - 恢复阈值，默认与触发阈值相差 5%
+ * @param {number} [rule.durationSecs] - 持续超过阈值多久才触发（秒），默认 0
+ * @param {boolean} [rule.enabled] - 是否启用，默认 true
+ * @returns {Promise<{success: boolean, id: string}>}
+ */
+export async function saveAlertRule(rule) {
+  try {
+    const result = await invoke('save_alert_rule', {
+      params: {
+        id: rule.id || null,
+        name: rule.name,
+        server_id: rule.serverId || null,
+        group_name: rule.groupName || null,
+        metric: rule.metric,
+        mount: rule.mount || null,
+        operator: rule.operator,
+        threshold: rule.threshold,
+        clear_threshold: rule.clearThreshold ?? null,
+        duration_secs: rule.durationSecs ?? 0,
+        enabled: rule.enabled ?? true
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('保存告警规则失败:', error)
+    throw new Error(error.message || '保存告警规则失败')
+  }
+}
+
+/**
+ * 删除告警规则
+ * @param {string} ruleId - 规则ID
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function deleteAlertRule(ruleId) {
+  try {
+    const result = await invoke('delete_alert_rule', {
+      params: {
+        rule_id: ruleId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('删除告警规则失败:', error)
+    throw new Error(error.message || '删除告警规则失败')
+  }
+}
+
+/**
+ * 获取告警记录（新的告警同时通过 alert-event 事件推送）
+ * @param {Object} [params] - 参数
+ * @param {string} [params.serverId] - 只返回指定服务器的记录
+ * @param {number} [params.limit] - 最大返回条数，默认 200
+ * @returns {Promise<{events: Array<{id: number, rule_id: string, rule_name: string, server_id: string, state: 'firing'|'resolved', value: number, threshold: number, message: string, created_at: number}>}>}
+ */
+export async function getAlertEvents(params = {}) {
+  try {
+    const result = await invoke('get_alert_events', {
+      params: {
+        server_id: params.serverId || null,
+        limit: params.limit ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取告警记录失败:', error)
+    throw new Error(error.message || '获取告警记录失败')
+  }
+}
+
+/**
+ * 清除告警记录
+ * @param {Object} [params] - 参数
+ * @param {string} [params.serverId] - 只清除指定服务器的记录，为空时清除全部
+ * @returns {Promise<{success: boolean}>}
+ */
+export async function clearAlertEvents(params = {}) {
+  try {
+    const result = await invoke('clear_alert_events', {
+      params: {
+        server_id: params.serverId || null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('清除告警记录失败:', error)
+    throw new Error(error.message || '清除告警记录失败')
+  }
+}
+
+/**
+ * 获取远程进程列表
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {'cpu'|'mem'|'rss'|'pid'|'start'|'user'|'command'} [params.sortBy] - 排序字段，默认 cpu
+ * @param {boolean} [params.descending] - 是否降序，默认 true
+ * @param {string} [params.filter] - 按命令行、用户或 PID 过滤
+ * @param {string} [params.user] - 只显示指定用户的进程
+ * @param {number} [params.limit] - 最大返回条数（树形模式下不生效）
+ * @param {boolean} [params.tree] - 是否以进程树返回（节点包含 children）
+ * @returns {Promise<{
+ *   total: number,
+ *   processes: Array<{pid: number, ppid: number, user: string, cpu: number, mem: number, rss: number, start_time: number, elapsed: number, state: string, command: string}>,
+ *   tree: Array<Object>|null
+ * }>}
+ */
+export async function listProcesses(params) {
+  try {
+    const result = await invoke('list_processes', {
+      params: {
+        server_id: params.serverId,
+        sort_by: params.sortBy || null,
+        descending: params.descending ?? null,
+        filter: params.filter || null,
+        user: params.user || null,
+        limit: params.limit ?? null,
+        tree: params.tree ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取进程列表失败:', error)
+    throw new Error(error.message || '获取进程列表失败')
+  }
+}
+
+/**
+ * 向远程进程发送信号（结果中包含发送前的命令行，便于确认操作对象）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {number[]} params.pids - 进程ID数组
+ * @param {'TERM'|'KILL'|'HUP'|'INT'|'QUIT'|'STOP'|'CONT'|'USR1'|'USR2'} [params.signal] - 信号，默认 TERM
+ * @returns {Promise<{success: boolean, signal: string, results: Array<{pid: number, command: string|null, success: boolean, message: string|null}>}>}
+ */
+export async function signalProcesses(params) {
+  try {
+    const result = await invoke('signal_processes', {
+      params: {
+        server_id: params.serverId,
+        pids: params.pids,
+        signal: params.signal || null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('发送信号失败:', error)
+    throw new Error(error.message || '发送信号失败')
+  }
+}
+
+/**
+ * 调整远程进程优先级
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {number} params.pid - 进程ID
+ * @param {number} params.nice - 新的 nice 值（-20 ~ 19）
+ * @returns {Promise<{success: boolean, pid: number, command: string|null, old_nice: number|null, new_nice: number, message: string|null}>}
+ */
+export async function reniceProcess(params) {
+  try {
+    const result = await invoke('renice_process', {
+      params: {
+        server_id: params.serverId,
+        pid: params.pid,
+        nice: params.nice
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('调整优先级失败:', error)
+    throw new Error(error.message || '调整优先级失败')
+  }
+}
+
+/**
+ * 列出 systemd 单元
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} [params.unitType] - 单元类型（可选，默认 service）
+ * @param {string} [params.state] - 按活动状态或子状态过滤，如 active、failed、running（可选）
+ * @param {string} [params.pattern] - 按名称或描述过滤（可选）
+ * @returns {Promise<{units: Array<{name: string, load: string, active: string, sub: string, description: string, unit_file_state: string|null}>}>}
+ */
+export async function listSystemdUnits(params) {
+  try {
+    const result = await invoke('list_systemd_units', {
+      params: {
+        server_id: params.serverId,
+        unit_type: params.unitType || null,
+        state: params.state || null,
+        pattern: params.pattern || null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取服务列表失败:', error)
+    throw new Error(error.message || '获取服务列表失败')
+  }
+}
+
+/**
+ * 获取 systemd 单元详情及最近日志
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.unit - 单元名称
+ * @param {number} [params.journalLines] - 日志行数（可选，默认 50）
+ * @returns {Promise<{status: Object, journal: string[], journal_error: string|null}>}
+ */
+export async function getSystemdUnitStatus(params) {
+  try {
+    const result = await invoke('get_systemd_unit_status', {
+      params: {
+        server_id: params.serverId,
+        unit: params.unit,
+        journal_lines: params.journalLines ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取服务状态失败:', error)
+    throw new Error(error.message || '获取服务状态失败')
+  }
+}
+
+/**
+ * 启动、停止、重启、重载、启用或禁用 systemd 单元
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} params.unit - 单元名称
+ * @param {'start'|'stop'|'restart'|'reload'|'enable'|'disable'} params.action - 操作类型
+ * @param {string} [params.sudoPassword] - sudo 密码（可选，返回 password_required 时提示用户输入后重试）
+ * @returns {Promise<{success: boolean, unit: string, action: string, password_required: boolean, status: Object|null, message: string|null}>}
+ */
+export async function manageSystemdUnit(params) {
+  try {
+    const result = await invoke('manage_systemd_unit', {
+      params: {
+        server_id: params.serverId,
+        unit: params.unit,
+        action: params.action,
+        sudo_password: params.sudoPassword || null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('操作服务失败:', error)
+    throw new Error(error.message || '操作服务失败')
+  }
+}
@@ -6,7 +6,7 @@ import { invoke } from '@tauri-apps/api/tauri'
 
 /**
  * 获取所有服务器配置
- * @returns {Promise<Array<{id: string, name: string, host: string, port: number, username: string, password?: string, keyPath?: string}>>}
+ * @returns {Promise<Array<{id: string, name: string, host: string, port: number, username: string, password?: string, keyPath?: string, groupName?: string}>>}
  */
 export async function getServers() {
   try {
@@ -17,7 +17,9 @@ export async function getServers() {
       return result.servers.map(server => ({
         ...server,
         keyPath: server.key_path,
-        key_path: undefined
+        key_path: undefined,
+        groupName: server.group_name,
+        group_name: undefined
       }))
     }
     return []
@@ -37,6 +39,7 @@ export async function getServers() {
  * @param {string} server.username - 用户名
  * @param {string} [server.password] - 密码（可选）
  * @param {string} [server.keyPath] - 密钥路径（可选）
+ * @param {string} [server.groupName] - 服务器分组（可选）
  * @returns {Promise<{success: boolean, id: string}>}
  */
 export async function saveServer(server) {
@@ -50,7 +53,8 @@ export async function saveServer(server) {
         port: server.port,
         username: server.username,
         password: server.password || null,
-        key_path: server.keyPath || null
+        key_path: server.keyPath || null,
+        group_name: server.groupName || null
       }
     })
     return result
@@ -70,6 +74,7 @@ export async function saveServer(server) {
  * @param {string} [server.username] - 用户名
  * @param {string} [server.password] - 密码（可选）
  * @param {string} [server.keyPath] - 密钥路径（可选）
+ * @param {string} [server.groupName] - 服务器分组（可选）
  * @returns {Promise<{success: boolean}>}
  */
 export async function updateServer(server) {
@@ -82,7 +87,8 @@ export async function updateServer(server) {
         port: server.port || null,
         username: server.username || null,
         password: server.password || null,
-        key_path: server.keyPath || null
+        key_path: server.keyPath || null,
+        group_name: server.groupName || null
       }
     })
     return result
@@ -114,7 +120,7 @@ export async function deleteServer(serverId) {
 /**
  * 获取单个服务器配置
  * @param {string} serverId - 服务器ID
- * @returns {Promise<{id: string, name: string, host: string, port: number, username: string, password?: string, keyPath?: string}>}
+ * @returns {Promise<{id: string, name: string, host: string, port: number, username: string, password?: string, keyPath?: string, groupName?: string}>}
  */
 export async function getServer(serverId) {
   try {
@@ -128,7 +134,9 @@ export async function getServer(serverId) {
       return {
         ...result.server,
         keyPath: result.server.key_path,
-        key_path: undefined
+        key_path: undefined,
+        groupName: result.server.group_name,
+        group_name: undefined
       }
     }
     return null
@@ -138,3 +146,24 @@ export async function getServer(serverId) {
   }
 }
 
+
+/**
+ * 获取服务器主机信息（缓存在当前连接上）
+ * @param {string} serverId - 服务器ID
+ * @param {boolean} [refresh] - 是否忽略缓存重新采集（可选）
+ * @returns {Promise<{hostname: string, os_name: string, os_id: string|null, os_version: string|null, kernel: string, arch: string, boot_time: number|null, uptime: number|null, cpu_model: string|null, cpu_cores: number|null, virtualization: string, timezone: string|null, package_manager: string|null, users: Array<{user: string, tty: string, login_time: string, from: string|null}>, collected_at: number}>}
+ */
+export async function getSystemInfo(serverId, refresh = false) {
+  try {
+    const result = await invoke('get_system_info', {
+      params: {
+        server_id: serverId,
+        refresh
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取主机信息失败:', error)
+    throw new Error(error.message || '获取主机信息失败')
+  }
+}
@@ -161,3 +161,87 @@ export async function completeCommand(params) {
   }
 }
 
+
+/**
+ * 列出远程服务器的监听端口（及已建立的连接）
+ * @param {string} serverId - 服务器ID
+ * @param {boolean} [includeConnections=false] - 是否包含已建立的连接
+ * @returns {Promise<{sockets: Array<{protocol: string, state: string, local_address: string, local_port: number, peer_address: string, peer_port: number|null, process: string|null, pid: number|null, forward_host: string|null}>, source: 'ss'|'netstat', process_complete: boolean}>}
+ */
+export async function listListeningPorts(serverId, includeConnections = false) {
+  try {
+    const result = await invoke('list_listening_ports', {
+      params: {
+        server_id: serverId,
+        include_connections: includeConnections
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('获取端口列表失败:', error)
+    throw new Error(error.message || '获取端口列表失败')
+  }
+}
+
+/**
+ * 开始本地端口转发（监听 127.0.0.1，经 SSH 连接转发到远程端口）
+ *
+ * 对端口列表中的监听端口，将 `forward_host` 和 `local_port` 作为 remoteHost 和 remotePort 传入即可。
+ * 连接断开后转发自动停止并推送 `port-forward-stopped` 事件（{forward_id, reason}）；
+ * 单个连接无法打开远程通道时推送 `port-forward-error` 事件（{forward_id, message}）
+ * @param {Object} params - 参数
+ * @param {string} params.serverId - 服务器ID
+ * @param {string} [params.remoteHost] - 远程目标地址（可选，默认 127.0.0.1）
+ * @param {number} params.remotePort - 远程目标端口
+ * @param {number} [params.localPort] - 本地端口（可选，默认与远程端口相同，被占用时自动分配）
+ * @returns {Promise<{forward_id: string, server_id: string, local_port: number, remote_host: string, remote_port: number, connections: number}>}
+ */
+export async function startPortForward(params) {
+  try {
+    const result = await invoke('start_port_forward', {
+      params: {
+        server_id: params.serverId,
+        remote_host: params.remoteHost || null,
+        remote_port: params.remotePort,
+        local_port: params.localPort ?? null
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('端口转发失败:', error)
+    throw new Error(error.message || '端口转发失败')
+  }
+}
+
+/**
+ * 停止本地端口转发
+ * @param {string} forwardId - 转发ID
+ * @returns {Promise<{success: boolean, message: string|null}>}
+ */
+export async function stopPortForward(forwardId) {
+  try {
+    const result = await invoke('stop_port_forward', {
+      params: {
+        forward_id: forwardId
+      }
+    })
+    return result
+  } catch (error) {
+    console.error('停止端口转发失败:', error)
+    throw new Error(error.message || '停止端口转发失败')
+  }
+}
+
+/**
+ * 列出正在运行的端口转发
+ * @returns {Promise<{forwards: Array<{forward_id: string, server_id: string, local_port: number, remote_host: string, remote_port: number, connections: number}>}>}
+ */
+export async function listPortForwards() {
+  try {
+    const result = await invoke('list_port_forwards')
+    return result
+  } catch (error) {
+    console.error('获取端口转发列表失败:', error)
+    throw new Error(error.message || '获取端口转发列表失败')
+  }
+}
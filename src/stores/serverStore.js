@@ -1,7 +1,7 @@
 import { defineStore } from 'pinia'
 import { ref, onMounted } from 'vue'
 import { connectSshServer, disconnectSshServer } from '@/api/ssh'
-import { saveServer, deleteServer, getServers } from '@/api/server'
+import { saveServer, deleteServer, getServers, getSystemInfo } from '@/api/server'
 
 export const useServerStore = defineStore('server', () => {
   const servers = ref([])
@@ -21,7 +21,9 @@ export const useServerStore = defineStore('server', () => {
         username: server.username,
         password: server.password,
         keyPath: server.keyPath || server.key_path, // 兼容两种命名
+        groupName: server.groupName || null,
         connected: false,
+        systemInfo: null,
         tabs: []
       }))
     } catch (error) {
@@ -39,7 +41,9 @@ export const useServerStore = defineStore('server', () => {
       username: server.username,
       password: server.password,
       keyPath: server.keyPath,
+      groupName: server.groupName || null,
       connected: false,
+      systemInfo: null,
       tabs: []
     }
     
@@ -97,6 +101,11 @@ export const useServerStore = defineStore('server', () => {
         
         server.connected = true
         activeServerId.value = serverId
+
+        // 获取主机信息用于列表展示，失败不影响连接
+        getSystemInfo(serverId)
+          .then(info => { server.systemInfo = info })
+          .catch(error => console.error('获取主机信息失败:', error))
       } catch (error) {
         console.error('连接服务器失败:', error)
         throw error
@@ -113,6 +122,7 @@ export const useServerStore = defineStore('server', () => {
         await disconnectSshServer(serverId)
         
         server.connected = false
+        server.systemInfo = null
         // 清理所有标签页
         server.tabs = []
         if (activeServerId.value === serverId) {